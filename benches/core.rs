@@ -100,6 +100,13 @@ fn benchmark(c: &mut Criterion) {
                     description: Some(Cow::Borrowed("test")),
                     alias: Cow::Borrowed("test"),
                     tags: None,
+                    parameters: None,
+                    shell: None,
+                    cwd: None,
+                    env: None,
+                    steps: None,
+                    source: None,
+                    read_only: false,
                 },
             )
         });
@@ -111,6 +118,13 @@ fn benchmark(c: &mut Criterion) {
         description: Some(Cow::Borrowed("test")),
         alias: Cow::Borrowed("test"),
         tags: None,
+        parameters: None,
+        shell: None,
+        cwd: None,
+        env: None,
+        steps: None,
+        source: None,
+        read_only: false,
     };
     c.bench_function("remove command", |b| {
         b.iter(|| {
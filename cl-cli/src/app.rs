@@ -1,4 +1,6 @@
-use super::subcommands::{Add, Config, Exec, Misc, Share};
+use super::subcommands::{
+    Add, Backup, Config, Doctor, Edit, Exec, History, Import, Misc, Move, Remove, Share, Show,
+};
 use clap::{Parser, Subcommand as ClapSubcommand};
 
 const PKG_NAME: &str = env!("CARGO_PKG_NAME");
@@ -40,6 +42,26 @@ pub enum Subcommands {
     Misc(Misc),
     #[clap(about = "Add your command via cli")]
     Add(Add),
+    #[clap(about = "Edit the fields of a command")]
+    Edit(Edit),
+    #[clap(name = "rm", aliases = &["remove"],
+    about = "Remove a command")]
+    Remove(Remove),
+    #[clap(name = "mv", aliases = &["move"],
+    about = "Move a command to another namespace and/or rename it")]
+    Move(Move),
+    #[clap(about = "Show the details of a command")]
+    Show(Show),
+    #[clap(aliases = &["H", "h"],
+    about = "List and run again your executed commands")]
+    History(History),
+    #[clap(about = "List and restore the backups of your commands")]
+    Backup(Backup),
+    #[clap(aliases = &["validate"],
+    about = "Check your commands for problems, exiting with an error if any is found")]
+    Doctor(Doctor),
+    #[clap(about = "Import commands from your shell")]
+    Import(Import),
 }
 
 #[cfg(test)]
@@ -1,17 +1,37 @@
 use anyhow::Result;
 use app::Subcommands;
-use cl_core::Config;
+use cl_core::{Config, ExecStatus};
+use std::process::ExitCode;
 use subcommands::Subcommand;
 
 pub mod app;
 pub mod subcommands;
 
-pub fn run_subcommands(subcommands: Subcommands, config: impl Config) -> Result<()> {
-    match subcommands {
-        Subcommands::Exec(exec) => exec.run(config),
+/// Runs the given subcommand, returning the exit code of the `cl` process
+///
+/// `exec` and `history --rerun` exit with the same code as the executed command
+pub fn run_subcommands(subcommands: Subcommands, config: impl Config) -> Result<ExitCode> {
+    let result = match subcommands {
+        Subcommands::Exec(exec) => return exec.execute(config).map(exit_code),
         Subcommands::Share(share) => share.run(config),
         Subcommands::Config(_config) => _config.run(config),
         Subcommands::Misc(misc) => misc.run(config),
         Subcommands::Add(add) => add.run(config),
-    }
+        Subcommands::Edit(edit) => edit.run(config),
+        Subcommands::Remove(remove) => remove.run(config),
+        Subcommands::Move(mv) => mv.run(config),
+        Subcommands::Show(show) => show.run(config),
+        Subcommands::History(history) => return history.execute(config).map(exit_code),
+        Subcommands::Backup(backup) => backup.run(config),
+        Subcommands::Doctor(doctor) => doctor.run(config),
+        Subcommands::Import(import) => import.run(config),
+    };
+
+    result.map(|_| ExitCode::SUCCESS)
+}
+
+/// Converts an execution status into a process exit code
+pub fn exit_code(status: ExecStatus) -> ExitCode {
+    // exit codes are truncated to 8 bits by the OS anyway
+    ExitCode::from(status.exit_code() as u8)
 }
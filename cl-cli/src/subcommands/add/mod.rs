@@ -1,22 +1,13 @@
 mod maybe_stdin;
 
+use super::alias::{generate_alias, taken_aliases};
 use super::Subcommand;
 use anyhow::Result;
-use cl_core::{fs, initialize_commands, CommandBuilder, Config};
+use cl_core::{initialize_commands, CommandBuilder, Config};
 use clap::Parser;
 use maybe_stdin::MaybeStdin;
 use tracing::{info, warn};
 
-fn generate_alias(command: &str) -> String {
-    command
-        .split_whitespace()
-        .next()
-        .unwrap_or("")
-        .chars()
-        .take(5)
-        .collect()
-}
-
 #[derive(Parser, Debug)]
 pub struct Add {
     #[clap(
@@ -24,8 +15,32 @@ pub struct Add {
         help = "The command to be added (may be read from stdin)"
     )]
     command: MaybeStdin<String>,
+    #[clap(
+        short,
+        long,
+        help = "The alias of the command. Defaults to the initials of its words, e.g. `gcm` for `git checkout main`"
+    )]
+    alias: Option<String>,
+    #[clap(
+        short,
+        long,
+        default_value = DEFAULT_NAMESPACE,
+        help = "The namespace of the command"
+    )]
+    namespace: String,
+    #[clap(short, long, help = "The description of the command")]
+    description: Option<String>,
+    #[clap(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "The tags of the command, separated by commas"
+    )]
+    tags: Vec<String>,
 }
 
+const DEFAULT_NAMESPACE: &str = "from_stdin";
+
 impl Subcommand for Add {
     fn run(&self, config: impl Config) -> Result<()> {
         let command_string = self.command.value.to_owned();
@@ -35,21 +50,33 @@ impl Subcommand for Add {
             return Ok(());
         }
 
-        let alias = generate_alias(&command_string);
-
-        const DEFAULT_NAMESPACE: &str = "from_stdin";
+        let storage = config.storage();
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let mut commands = initialize_commands!(storage);
+
+        let alias = self.alias.clone().unwrap_or_else(|| {
+            let mut taken = taken_aliases(&commands.as_list(), &self.namespace);
+            generate_alias(&command_string, &mut taken)
+        });
+        let tags = self
+            .tags
+            .iter()
+            .filter(|tag| !tag.trim().is_empty())
+            .map(|tag| tag.trim().to_owned())
+            .collect::<Vec<_>>();
 
         let builder = CommandBuilder::default();
         let command = builder
             .command(command_string.to_owned())
             .alias(alias.to_owned())
-            .namespace(DEFAULT_NAMESPACE.to_owned())
+            .namespace(self.namespace.to_owned())
+            .description(self.description.clone())
+            .tags((!tags.is_empty()).then_some(tags))
             .build();
 
-        let mut commands = initialize_commands!(config.command_file_path());
-
         let result = commands.add(&command)?;
-        fs::save_at(result, config.command_file_path())?;
+        storage.save_if_unchanged(result, &fingerprint)?;
         info!(target: "cl::add", alias = %alias, command = %command_string, "command added");
 
         Ok(())
@@ -58,38 +85,27 @@ impl Subcommand for Add {
 
 #[cfg(test)]
 mod test {
-    use super::generate_alias;
-
-    #[test]
-    fn should_take_first_5_chars_of_first_word_for_alias() {
-        assert_eq!(generate_alias("echo hello world"), "echo");
-    }
-
-    #[test]
-    fn should_take_first_5_chars_when_first_word_is_long() {
-        assert_eq!(generate_alias("echoo hello"), "echoo");
-    }
-
-    #[test]
-    fn should_use_full_first_word_when_shorter_than_5_chars() {
-        assert_eq!(generate_alias("rm -rf /tmp"), "rm");
-    }
-
-    #[test]
-    fn should_return_empty_string_for_empty_input() {
-        assert_eq!(generate_alias(""), "");
-    }
-
-    #[test]
-    fn should_return_empty_string_for_whitespace_only_input() {
-        assert_eq!(generate_alias("   "), "");
-    }
+    use super::*;
 
     #[test]
-    fn should_handle_multibyte_chars_without_panicking() {
-        // Each 'é' is 2 bytes; byte-level truncate(5) would panic mid-char
-        let alias = generate_alias("écho hello");
-        assert_eq!(alias, "écho");
-        assert!(alias.is_char_boundary(alias.len()));
+    fn should_parse_the_fields_of_the_command() {
+        let add = Add::parse_from([
+            "add",
+            "git status",
+            "--alias",
+            "st",
+            "-n",
+            "git",
+            "--tags",
+            "git,status",
+        ]);
+
+        assert_eq!(add.command.value, "git status");
+        assert_eq!(add.alias.as_deref(), Some("st"));
+        assert_eq!(add.namespace, "git");
+        assert_eq!(add.tags, vec!["git", "status"]);
+
+        let add = Add::parse_from(["add", "git status"]);
+        assert_eq!(add.namespace, DEFAULT_NAMESPACE);
     }
 }
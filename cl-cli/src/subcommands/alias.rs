@@ -0,0 +1,127 @@
+use cl_core::Command;
+use std::collections::HashSet;
+
+/// The words skipped at the start of a command to find its program
+const PREFIXES: [&str; 4] = ["sudo", "command", "nohup", "time"];
+/// The words of a command used for its alias, after the program
+const ALIAS_WORDS: usize = 3;
+
+/// The aliases of the commands of `namespace`, in lowercase as the aliases differing only in
+/// case are the same
+pub fn taken_aliases(commands: &[Command], namespace: &str) -> HashSet<String> {
+    commands
+        .iter()
+        .filter(|command| command.namespace.eq_ignore_ascii_case(namespace))
+        .map(|command| command.alias.to_lowercase())
+        .collect()
+}
+
+/// An alias made of the initials of the program and its first words, e.g. `gcm` for
+/// `git checkout main`, followed by a number when it is `taken`
+///
+/// The generated alias is added to `taken`
+pub fn generate_alias(command: &str, taken: &mut HashSet<String>) -> String {
+    let words: Vec<&str> = command
+        .split_whitespace()
+        .skip_while(|word| PREFIXES.contains(word) || is_assignment(word))
+        // the words after a pipe or a redirection do not describe the command
+        .take_while(|word| !word.starts_with(['|', '&', ';', '>', '<']))
+        .collect();
+    let Some((program, args)) = words.split_first() else {
+        return String::new();
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+
+    let initials: String = std::iter::once(program)
+        .chain(args.iter().copied())
+        .filter_map(|word| {
+            word.trim_start_matches('-')
+                .chars()
+                .next()
+                .filter(char::is_ascii_alphanumeric)
+        })
+        .take(ALIAS_WORDS + 1)
+        .collect::<String>()
+        .to_lowercase();
+    // a single letter says nothing of the command
+    let base = match initials.len() {
+        0 | 1 => program
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_'))
+            .collect::<String>()
+            .to_lowercase(),
+        _ => initials,
+    };
+
+    let alias = (1..)
+        .map(|n| match n {
+            1 => base.clone(),
+            n => format!("{base}{n}"),
+        })
+        .find(|alias| !taken.contains(alias))
+        .unwrap_or(base);
+    taken.insert(alias.clone());
+
+    alias
+}
+
+/// `KEY=value`, as the environment variables set before a program
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(key, _)| {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::CommandBuilder;
+
+    #[test]
+    fn should_generate_unique_aliases_from_the_initials() {
+        let mut taken = HashSet::from(["gs".to_owned()]);
+
+        assert_eq!(generate_alias("git status --short", &mut taken), "gss");
+        assert_eq!(generate_alias("git status", &mut taken), "gs2");
+        assert_eq!(generate_alias("git status", &mut taken), "gs3");
+        assert_eq!(generate_alias("git checkout main", &mut taken), "gcm");
+        assert_eq!(
+            generate_alias(
+                "sudo RUST_LOG=debug /usr/bin/docker compose up -d --build",
+                &mut taken
+            ),
+            "dcud"
+        );
+        assert_eq!(generate_alias("make | tee out.log", &mut taken), "make");
+        assert_eq!(generate_alias("ls -la", &mut taken), "ll");
+    }
+
+    #[test]
+    fn should_use_the_program_when_there_are_not_enough_initials() {
+        let mut taken = HashSet::new();
+
+        assert_eq!(generate_alias("écho hello", &mut taken), "écho");
+        assert_eq!(generate_alias("   ", &mut taken), "");
+    }
+
+    #[test]
+    fn should_list_the_aliases_of_a_namespace() {
+        let commands = [
+            CommandBuilder::default()
+                .alias("St")
+                .namespace("git")
+                .command("git status")
+                .build(),
+            CommandBuilder::default()
+                .alias("ps")
+                .namespace("docker")
+                .command("docker ps")
+                .build(),
+        ];
+
+        assert_eq!(
+            taken_aliases(&commands, "Git"),
+            HashSet::from(["st".to_owned()])
+        );
+    }
+}
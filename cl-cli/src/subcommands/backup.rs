@@ -0,0 +1,106 @@
+use super::history::elapsed;
+use super::Subcommand;
+use anyhow::{Context, Result};
+use cl_core::{Backup as CoreBackup, CommandMapExt, Config};
+use clap::{Parser, Subcommand as ClapSubcommand};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug_span, info};
+
+#[derive(Parser)]
+pub struct Backup {
+    #[clap(subcommand)]
+    action: BackupAction,
+}
+
+#[derive(ClapSubcommand)]
+enum BackupAction {
+    #[clap(about = "List the backups of your commands, most recent first")]
+    List,
+    #[clap(about = "Replace your commands with the ones of a backup")]
+    Restore {
+        #[clap(
+            required = true,
+            help = "The id of the backup, as shown by `cl backup list`"
+        )]
+        id: usize,
+    },
+}
+
+impl Subcommand for Backup {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let backups = config.backups();
+        let list = backups.list().context("Cannot list the backups")?;
+
+        match self.action {
+            BackupAction::List => {
+                if list.is_empty() {
+                    println!("No backups found in {}", backups.dir().display());
+                    return Ok(());
+                }
+
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default();
+                list.iter()
+                    .enumerate()
+                    .for_each(|(idx, backup)| println!("{}", summarize(idx + 1, backup, now)));
+            }
+            BackupAction::Restore { id } => {
+                let _span = debug_span!("backup::restore", id).entered();
+                let backup = id
+                    .checked_sub(1)
+                    .and_then(|idx| list.get(idx))
+                    .with_context(|| format!("There is no backup with the id {id}"))?;
+
+                let commands = backups
+                    .load(backup)
+                    .with_context(|| format!("Cannot load {}", backup.path.display()))?;
+                // the current commands are backed up before being replaced, so a restore can be undone
+                config
+                    .file_storage()
+                    .save(&commands)
+                    .context("Cannot restore the backup")?;
+                info!(target: "cl::backup", path = %backup.path.display(), count = commands.to_vec().len(), "backup restored");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn summarize(id: usize, backup: &CoreBackup, now: u64) -> String {
+    format!(
+        "{:>4}  {:>8}  {}",
+        id,
+        elapsed(now.saturating_sub(backup.timestamp())),
+        backup.path.display()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_parse_the_backup_actions() {
+        let backup = Backup::parse_from(["backup", "restore", "2"]);
+        assert!(matches!(backup.action, BackupAction::Restore { id: 2 }));
+
+        assert!(Backup::try_parse_from(["backup", "restore"]).is_err());
+    }
+
+    #[test]
+    fn should_summarize_a_backup() {
+        let backup = CoreBackup {
+            path: PathBuf::from("/backups/commands-120000.toml"),
+            timestamp_ms: 120_000,
+        };
+
+        assert_eq!(
+            summarize(1, &backup, 180),
+            "   1    1m ago  /backups/commands-120000.toml"
+        );
+    }
+}
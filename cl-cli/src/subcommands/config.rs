@@ -1,6 +1,8 @@
 use super::Subcommand;
 use anyhow::{bail, Context, Result};
-use cl_core::{config, Config as CoreConfig, LogLevel as ConfigLogLevel};
+use cl_core::{
+    config, Config as CoreConfig, LogLevel as ConfigLogLevel, SortMode as ConfigSortMode,
+};
 use clap::{Parser, Subcommand as ClapSubcommand, ValueEnum};
 use dirs::home_dir;
 use std::{
@@ -29,6 +31,21 @@ impl From<LogLevel> for ConfigLogLevel {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum SortMode {
+    Alphabetical,
+    Frecency,
+}
+
+impl From<SortMode> for ConfigSortMode {
+    fn from(value: SortMode) -> ConfigSortMode {
+        match value {
+            SortMode::Alphabetical => ConfigSortMode::Alphabetical,
+            SortMode::Frecency => ConfigSortMode::Frecency,
+        }
+    }
+}
+
 #[derive(Parser)]
 pub struct Config {
     #[clap(
@@ -57,6 +74,35 @@ pub struct Config {
         help = "Set the `highlight matches` mode"
     )]
     highlight_matches: Option<bool>,
+    #[clap(
+        value_parser,
+        long,
+        short = 's',
+        ignore_case = true,
+        required = false,
+        num_args(1),
+        help = "Set how the commands are sorted in the GUI"
+    )]
+    sort_mode: Option<SortMode>,
+    #[clap(
+        long,
+        short = 'S',
+        required = false,
+        num_args(1),
+        value_name = "SHELL",
+        help = "Set the shell used to run the commands (e.g. `bash` or `sh -eu`).\n\
+            An empty value falls back to $SHELL"
+    )]
+    shell: Option<String>,
+    #[clap(
+        long,
+        short = 'b',
+        required = false,
+        num_args(1),
+        value_name = "COUNT",
+        help = "Set how many backups of your commands are kept. 0 disables them"
+    )]
+    backups: Option<usize>,
     #[clap(subcommand)]
     subcommand: Option<ConfigSubcommand>,
 }
@@ -104,6 +150,29 @@ impl Subcommand for Config {
                 .if_ok(|| info!(target: "cl::config", highlight_matches = highlight, "highlight matches updated"))?;
         }
 
+        if let Some(sort_mode) = self.sort_mode {
+            any_flag = true;
+            config
+                .change_and_save(|c| c.preferences_mut().set_sort_mode(sort_mode.into()))
+                .if_ok(
+                    || info!(target: "cl::config", sort_mode = ?sort_mode, "sort mode updated"),
+                )?;
+        }
+
+        if let Some(shell) = &self.shell {
+            any_flag = true;
+            config
+                .change_and_save(|c| c.preferences_mut().set_shell(Some(shell.to_owned())))
+                .if_ok(|| info!(target: "cl::config", shell = %shell, "shell updated"))?;
+        }
+
+        if let Some(backups) = self.backups {
+            any_flag = true;
+            config
+                .change_and_save(|c| c.preferences_mut().set_backups(backups))
+                .if_ok(|| info!(target: "cl::config", backups, "backups updated"))?;
+        }
+
         if !any_flag {
             println!("{}", printable(&config));
         }
@@ -180,6 +249,15 @@ fn printable(config: &impl CoreConfig) -> String {
         "  highlight-matches: {}\n",
         preferences.highlight()
     ));
+    result.push_str(&format!(
+        "  sort-mode: {}\n",
+        String::from(&preferences.sort_mode())
+    ));
+    result.push_str(&format!(
+        "  shell: {}\n",
+        preferences.shell().unwrap_or("$SHELL")
+    ));
+    result.push_str(&format!("  backups: {}\n", preferences.backups()));
     result
 }
 
@@ -298,6 +376,9 @@ mod test {
         assert!(output.contains("quiet-mode:"));
         assert!(output.contains("log-level:"));
         assert!(output.contains("highlight-matches:"));
+        assert!(output.contains("sort-mode:"));
+        assert!(output.contains("shell: $SHELL"));
+        assert!(output.contains("backups: 10"));
     }
 
     #[test]
@@ -305,6 +386,9 @@ mod test {
         let mut prefs = Preferences::default();
         prefs.set_quiet_mode(true);
         prefs.set_log_level(CoreLogLevel::Debug);
+        prefs.set_sort_mode(ConfigSortMode::Frecency);
+        prefs.set_shell(Some("bash".to_owned()));
+        prefs.set_backups(3);
         let config = MockConfig {
             preferences: prefs,
             command_file: PathBuf::from("/tmp/commands.toml"),
@@ -312,5 +396,8 @@ mod test {
         let output = printable(&config);
         assert!(output.contains("quiet-mode: true"));
         assert!(output.contains("log-level: debug"));
+        assert!(output.contains("sort-mode: frecency"));
+        assert!(output.contains("shell: bash"));
+        assert!(output.contains("backups: 3"));
     }
 }
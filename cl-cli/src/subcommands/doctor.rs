@@ -0,0 +1,101 @@
+use super::Subcommand;
+use anyhow::{bail, Context, Result};
+use cl_core::doctor::{self, Diagnostic};
+use cl_core::Config;
+use clap::Parser;
+use tracing::{debug_span, info};
+
+#[derive(Parser)]
+pub struct Doctor {
+    #[clap(
+        long,
+        help = "Fix the problems that can be solved without losing commands, i.e. whitespaces, \
+        misplaced and duplicated commands"
+    )]
+    fix: bool,
+}
+
+impl Subcommand for Doctor {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let _span = debug_span!("doctor", fix = self.fix).entered();
+        let storage = config.storage();
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let mut commands = storage.load().context("Cannot load the commands")?;
+        // the commands of a single file do not know where they come from
+        let file = config.command_file_path();
+        commands
+            .values_mut()
+            .flatten()
+            .filter(|command| command.source.is_none())
+            .for_each(|command| command.source = Some(file.clone()));
+
+        let mut diagnostics = doctor::diagnose(&commands);
+        if self.fix && diagnostics.iter().any(Diagnostic::is_fixable) {
+            let fixed = doctor::fix(&commands);
+            storage
+                .save_if_unchanged(&fixed, &fingerprint)
+                .context("Cannot save the fixed commands")?;
+            let remaining = doctor::diagnose(&fixed);
+            info!(target: "cl::doctor", fixed = diagnostics.len() - remaining.len(), "commands fixed");
+            println!("Fixed {}", plural(diagnostics.len() - remaining.len()));
+            diagnostics = remaining;
+        }
+
+        if diagnostics.is_empty() {
+            println!("No problems found");
+            return Ok(());
+        }
+
+        for diagnostic in &diagnostics {
+            println!("{}", describe(diagnostic));
+        }
+        let fixable = diagnostics.iter().filter(|d| d.is_fixable()).count();
+        if fixable > 0 {
+            println!("{fixable} of them can be fixed with `cl doctor --fix`");
+        }
+
+        bail!("Found {}", plural(diagnostics.len()))
+    }
+}
+
+fn describe(diagnostic: &Diagnostic) -> String {
+    match diagnostic.read_only {
+        true => format!("{diagnostic} (read-only)"),
+        false => diagnostic.to_string(),
+    }
+}
+
+fn plural(problems: usize) -> String {
+    match problems {
+        1 => "1 problem".to_owned(),
+        n => format!("{n} problems"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::doctor::{Location, Problem};
+
+    #[test]
+    fn should_describe_a_diagnostic() {
+        let diagnostic = Diagnostic {
+            location: Location {
+                file: Some("/team/commands.toml".into()),
+                table: "git".to_owned(),
+                position: 2,
+                alias: "st atus".to_owned(),
+            },
+            problem: Problem::AliasWithWhitespaces,
+            read_only: true,
+        };
+
+        assert_eq!(
+            describe(&diagnostic),
+            "/team/commands.toml: [[git]] #2 'st atus': the alias contains whitespace (read-only)"
+        );
+        assert_eq!(plural(1), "1 problem");
+        assert_eq!(plural(3), "3 problems");
+    }
+}
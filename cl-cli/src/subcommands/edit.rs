@@ -0,0 +1,188 @@
+use super::prompt::Prompt;
+use super::Subcommand;
+use anyhow::{Context, Result};
+use cl_core::{initialize_commands, parse_env_var, Command, Config, Editor};
+use clap::{ArgGroup, Parser};
+use std::borrow::Cow;
+use std::io::{self, IsTerminal};
+use tracing::{debug_span, info, warn};
+
+#[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("fields")
+        .required(true)
+        .multiple(true)
+        .args(["command", "description", "tags", "shell", "cwd", "env", "editor"])
+))]
+pub struct Edit {
+    #[clap(required = true, help = "The alias of the command to be edited")]
+    alias: String,
+    #[clap(
+        short,
+        long,
+        help = "The namespace of the command, in case of duplicate aliases"
+    )]
+    namespace: Option<String>,
+    #[clap(short, long, help = "The new command")]
+    command: Option<String>,
+    #[clap(short, long, help = "The new description. An empty one removes it")]
+    description: Option<String>,
+    #[clap(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "The new tags, separated by commas. Empty tags remove them"
+    )]
+    tags: Option<Vec<String>>,
+    #[clap(long, help = "The new shell. An empty one removes it")]
+    shell: Option<String>,
+    #[clap(long, help = "The new working directory. An empty one removes it")]
+    cwd: Option<String>,
+    #[clap(
+        short,
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = parse_env_var,
+        help = "Set an environment variable of the command. An empty value removes it.\n\
+            Can be used multiple times"
+    )]
+    env: Vec<(String, String)>,
+    #[clap(
+        long,
+        conflicts_with_all = ["command", "description", "tags", "shell", "cwd", "env"],
+        help = "Edit the whole command as TOML in $VISUAL or $EDITOR"
+    )]
+    editor: bool,
+}
+
+impl Subcommand for Edit {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let _span = debug_span!("edit", alias = %self.alias, namespace = ?self.namespace).entered();
+        let storage = config.storage();
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let mut commands = initialize_commands!(storage);
+
+        let current = commands
+            .find(&self.alias, self.namespace.as_deref())
+            .context("Failed to find the command with the given alias and namespace")?;
+        let edited = if self.editor {
+            let mut prompt = Prompt::new(io::stdin().lock(), io::stderr());
+            // the changes are kept in the file, so it may be fixed instead of starting over
+            let reopen = |err: &anyhow::Error| {
+                warn!(target: "cl::edit", "{err:#}");
+                io::stdin().is_terminal()
+                    && prompt.confirm("Reopen the editor?").unwrap_or_default()
+            };
+            match Editor::from_env().edit_command(&current, reopen)? {
+                Some(edited) => edited,
+                None => {
+                    info!(target: "cl::edit", "nothing changed");
+                    return Ok(());
+                }
+            }
+        } else {
+            self.apply_fields(&current)
+        };
+
+        let result = commands.edit(&edited, &current)?;
+        storage.save_if_unchanged(result, &fingerprint)?;
+        info!(target: "cl::edit", alias = %edited.alias, namespace = %edited.namespace, "command edited");
+
+        Ok(())
+    }
+}
+
+impl Edit {
+    /// The command with the fields given in the arguments
+    fn apply_fields<'cmd>(&self, current: &Command<'cmd>) -> Command<'cmd> {
+        let mut edited = current.clone();
+        if let Some(command) = &self.command {
+            edited.command = Cow::Owned(command.to_owned());
+        }
+        if let Some(description) = &self.description {
+            edited.description = non_empty(description).map(Cow::Owned);
+        }
+        if let Some(tags) = &self.tags {
+            let tags: Vec<Cow<str>> = tags
+                .iter()
+                .filter_map(|tag| non_empty(tag))
+                .map(Cow::Owned)
+                .collect();
+            edited.tags = (!tags.is_empty()).then_some(tags);
+        }
+        if let Some(shell) = &self.shell {
+            edited.shell = non_empty(shell).map(Cow::Owned);
+        }
+        if let Some(cwd) = &self.cwd {
+            edited.cwd = non_empty(cwd).map(Cow::Owned);
+        }
+        if !self.env.is_empty() {
+            let mut env = edited.env.take().unwrap_or_default();
+            for (key, value) in &self.env {
+                match value.is_empty() {
+                    true => env.remove(key),
+                    false => env.insert(key.to_owned(), value.to_owned()),
+                };
+            }
+            edited.env = (!env.is_empty()).then_some(env);
+        }
+
+        edited
+    }
+}
+
+/// Empty values remove the optional fields
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::CommandBuilder;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn should_require_a_field_to_edit() {
+        assert!(Edit::try_parse_from(["edit", "st"]).is_err());
+
+        let edit = Edit::parse_from(["edit", "st", "-n", "git", "--tags", "git,", "-d", ""]);
+        assert_eq!(edit.namespace.as_deref(), Some("git"));
+        assert_eq!(edit.tags, Some(vec!["git".to_owned(), "".to_owned()]));
+        assert_eq!(edit.description.as_deref().and_then(non_empty), None);
+
+        assert!(Edit::parse_from(["edit", "st", "--editor"]).editor);
+        assert!(Edit::try_parse_from(["edit", "st", "--editor", "-c", "git status"]).is_err());
+        assert!(Edit::try_parse_from(["edit", "st", "--editor", "-e", "A=1"]).is_err());
+        assert!(Edit::try_parse_from(["edit", "st", "-e", "A"]).is_err());
+    }
+
+    #[test]
+    fn should_set_and_remove_the_environment_variables() {
+        let env = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let current = CommandBuilder::default()
+            .alias("st")
+            .namespace("git")
+            .command("git status")
+            .env(Some(env(&[("GIT_PAGER", "cat"), ("LANG", "C")])))
+            .build();
+
+        let edit = Edit::parse_from(["edit", "st", "-e", "LANG=", "--env", "TERM=dumb"]);
+        let edited = edit.apply_fields(&current);
+
+        assert_eq!(
+            edited.env,
+            Some(env(&[("GIT_PAGER", "cat"), ("TERM", "dumb")]))
+        );
+
+        let edit = Edit::parse_from(["edit", "st", "-e", "GIT_PAGER=", "-e", "TERM="]);
+        assert_eq!(edit.apply_fields(&edited).env, None);
+    }
+}
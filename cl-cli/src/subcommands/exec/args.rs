@@ -1,4 +1,5 @@
 use anyhow::{bail, Result};
+use cl_core::Parameter;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
@@ -18,7 +19,7 @@ pub struct CommandArgs {
 }
 
 impl CommandArgs {
-    pub fn init(command: &str, args: Vec<String>) -> Result<CommandArgs> {
+    pub fn init(command: &str, args: Vec<String>, parameters: &[Parameter]) -> Result<CommandArgs> {
         let named_parameters = Self::filter_named_parameters(command)?;
 
         let mut command_args = Self {
@@ -45,6 +46,8 @@ impl CommandArgs {
             command_args.push(command_arg);
         }
 
+        command_args.fill_defaults(parameters);
+
         let named_len = command_args.named.len();
         let named_parameters_len = command_args.named_parameters.len();
 
@@ -65,9 +68,42 @@ impl CommandArgs {
                     .join(", ")
             )
         };
+
+        command_args.validate(parameters)?;
+
         Ok(command_args)
     }
 
+    /// Returns the named parameters of `command` that are neither given in `args` nor have
+    /// a default value, in order of appearance
+    pub fn missing_named_parameters(
+        command: &str,
+        args: &[String],
+        parameters: &[Parameter],
+    ) -> Vec<String> {
+        let provided: HashSet<&str> = args
+            .iter()
+            .filter_map(|arg| arg.strip_prefix(ARG_PREFIX))
+            .map(|arg| arg.split('=').next().unwrap_or(""))
+            .collect();
+        let has_default = |name: &str| {
+            parameters
+                .iter()
+                .any(|p| p.name == name && p.default.is_some())
+        };
+
+        let mut missing: Vec<String> = Vec::new();
+        for m in NAMED_PARAM_REGEX.find_iter(command) {
+            let name = Self::clean_named_parameter(m.as_str());
+            if !provided.contains(name.as_str()) && !has_default(&name) && !missing.contains(&name)
+            {
+                missing.push(name);
+            }
+        }
+
+        missing
+    }
+
     pub(super) fn named_parameters_map(&self) -> Option<HashMap<String, String>> {
         if self.named.is_empty() {
             return None;
@@ -96,6 +132,35 @@ impl CommandArgs {
         }
     }
 
+    /// Adds the declared default value of every named parameter that was not provided
+    fn fill_defaults(&mut self, parameters: &[Parameter]) {
+        for parameter in parameters {
+            let provided = self.named.iter().any(|a| a.arg == parameter.name);
+            if provided || !self.named_parameters.contains(&parameter.name) {
+                continue;
+            }
+
+            if let Some(default) = &parameter.default {
+                self.named.push(CommandArg::new(
+                    parameter.name.to_owned(),
+                    Some(ARG_PREFIX.to_string()),
+                    Some(default.to_owned()),
+                ));
+            }
+        }
+    }
+
+    /// Checks the named parameters values against their declarations
+    fn validate(&self, parameters: &[Parameter]) -> Result<()> {
+        for arg in &self.named {
+            if let Some(parameter) = parameters.iter().find(|p| p.name == arg.arg) {
+                parameter.validate_value(arg.value.as_deref().unwrap_or_default())?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn filter_named_parameters(command: &str) -> Result<HashSet<String>> {
         let matches = NAMED_PARAM_REGEX
             .find_iter(command)
@@ -136,6 +201,18 @@ impl CommandArg {
     }
 }
 
+impl Display for CommandArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+
+        let str = match &self.value {
+            Some(value) => format!("{}{}={}", prefix, self.arg, value),
+            None => format!("{}{}", prefix, self.arg),
+        };
+        write!(f, "{str}")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -144,7 +221,7 @@ mod test {
     fn should_preserve_value_containing_equals_sign() {
         let command = "curl #{url}";
         let args = vec!["--url=https://example.com?foo=bar&baz=qux".to_string()];
-        let result = CommandArgs::init(command, args);
+        let result = CommandArgs::init(command, args, &[]);
         assert!(result.is_ok());
         let map = result.unwrap().named_parameters_map().unwrap();
         assert_eq!(
@@ -158,7 +235,7 @@ mod test {
         let command = "echo #{greeting} #{name}";
         // only --greeting provided, --name is missing
         let args = vec!["--greeting=hello".to_string()];
-        let result = CommandArgs::init(command, args);
+        let result = CommandArgs::init(command, args, &[]);
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(
@@ -168,22 +245,73 @@ mod test {
     }
 
     #[test]
-    fn should_succeed_when_all_named_parameters_are_provided() {
+    fn should_fill_missing_named_parameters_with_their_defaults() {
         let command = "echo #{greeting} #{name}";
-        let args = vec!["--greeting=hello".to_string(), "--name=world".to_string()];
-        let result = CommandArgs::init(command, args);
+        let args = vec!["--name=world".to_string()];
+        let parameters = vec![Parameter {
+            name: "greeting".to_string(),
+            default: Some("hello".to_string()),
+            ..Default::default()
+        }];
+        let result = CommandArgs::init(command, args, &parameters);
         assert!(result.is_ok());
+        let map = result.unwrap().named_parameters_map().unwrap();
+        assert_eq!(map.get("greeting").unwrap(), "hello");
+        assert_eq!(map.get("name").unwrap(), "world");
     }
-}
 
-impl Display for CommandArg {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let prefix = self.prefix.as_deref().unwrap_or_default();
+    #[test]
+    fn should_prefer_provided_values_over_defaults() {
+        let command = "echo #{greeting}";
+        let args = vec!["--greeting=hi".to_string()];
+        let parameters = vec![Parameter {
+            name: "greeting".to_string(),
+            default: Some("hello".to_string()),
+            ..Default::default()
+        }];
+        let map = CommandArgs::init(command, args, &parameters)
+            .unwrap()
+            .named_parameters_map()
+            .unwrap();
+        assert_eq!(map.get("greeting").unwrap(), "hi");
+    }
 
-        let str = match &self.value {
-            Some(value) => format!("{}{}={}", prefix, self.arg, value),
-            None => format!("{}{}", prefix, self.arg),
-        };
-        write!(f, "{str}")
+    #[test]
+    fn should_reject_values_that_do_not_match_the_declaration() {
+        let command = "deploy #{env}";
+        let args = vec!["--env=staging".to_string()];
+        let parameters = vec![Parameter {
+            name: "env".to_string(),
+            choices: Some(vec!["dev".to_string(), "prod".to_string()]),
+            ..Default::default()
+        }];
+        let result = CommandArgs::init(command, args, &parameters);
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("staging") && err.contains("dev, prod"),
+            "Error should name the value and the valid choices, got: {err}"
+        );
+    }
+
+    #[test]
+    fn should_list_missing_named_parameters_in_order_of_appearance() {
+        let command = "echo #{greeting} #{name} #{greeting} #{punctuation}";
+        let args = vec!["--name=world".to_string(), "extra".to_string()];
+        let parameters = vec![Parameter {
+            name: "punctuation".to_string(),
+            default: Some("!".to_string()),
+            ..Default::default()
+        }];
+        let missing = CommandArgs::missing_named_parameters(command, &args, &parameters);
+        assert_eq!(missing, vec!["greeting"]);
+    }
+
+    #[test]
+    fn should_succeed_when_all_named_parameters_are_provided() {
+        let command = "echo #{greeting} #{name}";
+        let args = vec!["--greeting=hello".to_string(), "--name=world".to_string()];
+        let result = CommandArgs::init(command, args, &[]);
+        assert!(result.is_ok());
     }
 }
@@ -1,5 +1,6 @@
 use super::args::CommandArgs;
 use anyhow::{Context, Result};
+use cl_core::Parameter;
 use std::ops::Deref;
 use strfmt::strfmt;
 
@@ -10,9 +11,14 @@ pub struct Command {
 }
 
 impl Command {
-    pub fn new(command: impl Into<String>, args: Vec<String>) -> Result<Self> {
+    pub fn new(
+        command: impl Into<String>,
+        args: Vec<String>,
+        parameters: &[Parameter],
+    ) -> Result<Self> {
         let command = command.into();
-        let args = CommandArgs::init(&command, args).context("Cannot parse the given args")?;
+        let args =
+            CommandArgs::init(&command, args, parameters).context("Cannot parse the given args")?;
 
         let cs = Self {
             inner: command,
@@ -2,13 +2,21 @@ pub mod args;
 pub mod command;
 
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal};
 
+use super::prompt::Prompt;
 use super::Subcommand;
 use anyhow::{Context, Result};
-use cl_core::{initialize_commands, CommandExec, Config};
+use args::CommandArgs;
+use cl_core::{initialize_commands, parse_env_var, CommandExec, Config, ExecStatus, History};
 use clap::Parser;
 use command::Command;
-use tracing::{debug, debug_span};
+use itertools::Itertools;
+use tracing::{debug, debug_span, warn};
+
+/// How many of the previous values of a named parameter are offered when asking for it
+const RECENT_VALUES: usize = 5;
 
 #[derive(Parser)]
 pub struct Exec {
@@ -26,7 +34,7 @@ pub struct Exec {
         short,
         long,
         action,
-        help = "Dry run mode (prints the alias command in the terminal without executing it)"
+        help = "Dry run mode (prints the command invocation in the terminal without executing it)"
     )]
     dry_run: bool,
     #[clap(
@@ -36,6 +44,30 @@ pub struct Exec {
         help = "Quiet mode (prints only the command execution output)"
     )]
     quiet: bool,
+    #[clap(
+        short,
+        long,
+        action,
+        help = "Interactive mode (asks for missing named parameters).\n\
+            Enabled by default when the stdin is a terminal"
+    )]
+    interactive: bool,
+    #[clap(
+        short = 'C',
+        long,
+        value_name = "DIR",
+        help = "Run the command from the given directory, overriding its working directory"
+    )]
+    cwd: Option<String>,
+    #[clap(
+        short,
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = parse_env_var,
+        help = "Set an environment variable for the command, overriding its own value.\n\
+            Can be used multiple times"
+    )]
+    env: Vec<(String, String)>,
 
     #[clap(
         num_args(1..),
@@ -50,10 +82,17 @@ pub struct Exec {
 
 impl Subcommand for Exec {
     fn run(&self, config: impl Config) -> Result<()> {
-        let commands = initialize_commands!(config.command_file_path());
+        self.execute(config).map(|_| ())
+    }
+}
+
+impl Exec {
+    /// Runs the command, returning how it finished so its exit code can be propagated
+    pub fn execute(&self, config: impl Config) -> Result<ExecStatus> {
+        let commands = initialize_commands!(config.storage());
         let alias = &self.alias;
         let namespace = &self.namespace;
-        let args = &self.command_args;
+        let mut args = self.command_args.clone();
         let dry_run = self.dry_run;
         let quiet_mode = self.quiet || config.preferences().quiet_mode();
 
@@ -66,20 +105,76 @@ impl Subcommand for Exec {
         )
         .entered();
 
-        let mut command_item = commands
+        let command_item = commands
             .find(alias, namespace.as_deref())
             .context("Failed to find the command with the given alias and namespace")?;
-
-        let new_command = Command::new(command_item.command, args.clone())
-            .context("Cannot prepare the command to be executed")?
-            .inner;
-
-        debug!(target: "cl::exec", command = %new_command, "command to be executed");
-
-        command_item.command = Cow::Owned(new_command);
-        command_item
-            .exec(dry_run, quiet_mode)
-            .context("Cannot run the command")
+        let mut command_item = commands
+            .expand(&command_item)
+            .context("Cannot resolve the references to other commands")?;
+
+        command_item.override_context(self.cwd.as_deref(), &self.env);
+
+        let parameters = command_item.parameters.as_deref().unwrap_or_default();
+        let history = History::new(config.history_file_path());
+
+        if self.interactive || io::stdin().is_terminal() {
+            let missing =
+                CommandArgs::missing_named_parameters(&command_item.script(), &args, parameters);
+            if !missing.is_empty() {
+                debug!(target: "cl::exec", missing = ?missing, "asking for missing named parameters");
+                let recent = history
+                    .recent_parameters(&command_item.alias, &command_item.namespace, RECENT_VALUES)
+                    .unwrap_or_else(|err| {
+                        warn!(target: "cl::exec", "cannot read the history: {err}");
+                        HashMap::new()
+                    });
+                let mut prompt = Prompt::new(io::stdin().lock(), io::stderr());
+                args.extend(prompt.ask(&missing, parameters, &recent)?);
+            }
+        }
+
+        let recorded: BTreeMap<String, String>;
+        if command_item.is_pipeline() {
+            let args = CommandArgs::init(&command_item.script(), args, parameters)
+                .context("Cannot parse the given args")?;
+            let values = args.named_parameters_map().unwrap_or_default();
+            recorded = values.clone().into_iter().collect();
+            let options = args
+                .options()
+                .map(|o| o.iter().join(" "))
+                .unwrap_or_default();
+            command_item = command_item
+                .with_arguments(&values, &options)
+                .context("Cannot prepare the pipeline to be executed")?;
+        } else {
+            let new_command = Command::new(command_item.command, args, parameters)
+                .context("Cannot prepare the command to be executed")?;
+            recorded = new_command
+                .args
+                .named_parameters_map()
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            command_item.command = Cow::Owned(new_command.inner);
+        }
+
+        debug!(target: "cl::exec", command = %command_item.script(), "command to be executed");
+
+        let history = history.with_parameters(recorded);
+        let status = command_item
+            .exec(
+                dry_run,
+                quiet_mode,
+                config.preferences().shell(),
+                Some(&history),
+            )
+            .context("Cannot run the command")?;
+
+        if !status.success() {
+            debug!(target: "cl::exec", %status, "the command did not succeed");
+        }
+
+        Ok(status)
     }
 }
 
@@ -90,7 +185,7 @@ mod test {
     #[test]
     fn should_prepare_a_simple_command() {
         let command = "echo hello";
-        let result = Command::new(command, vec![]);
+        let result = Command::new(command, vec![], &[]);
         assert_eq!(*result.unwrap(), "echo hello");
     }
 
@@ -100,6 +195,7 @@ mod test {
         let result = Command::new(
             command,
             vec![String::from("--name"), String::from("unit_test")],
+            &[],
         );
         assert!(result.is_ok());
         assert_eq!(*result.unwrap(), "echo unit_test");
@@ -109,7 +205,7 @@ mod test {
     fn should_return_error_when_an_invalid_named_parameter_is_given() {
         let named_parameters: Vec<String> = vec![String::from("--invalid=unit_test")];
         let command = "echo #{name}";
-        let result = Command::new(command, named_parameters);
+        let result = Command::new(command, named_parameters, &[]);
 
         assert!(result.is_err());
 
@@ -119,11 +215,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn should_parse_the_working_dir_and_env_overrides() {
+        let exec = Exec::parse_from([
+            "exec", "alias", "-C", "/tmp", "-e", "FOO=bar", "--env", "BAZ=",
+        ]);
+
+        assert_eq!(exec.cwd.as_deref(), Some("/tmp"));
+        assert_eq!(
+            exec.env,
+            vec![
+                ("FOO".to_owned(), "bar".to_owned()),
+                ("BAZ".to_owned(), String::new())
+            ]
+        );
+        assert!(Exec::try_parse_from(["exec", "alias", "-e", "FOO"]).is_err());
+    }
+
     #[test]
     fn should_append_the_options_to_the_command() {
         let command = "echo Hello";
         let args = vec![String::from("World")];
-        let result = Command::new(command, args.clone());
+        let result = Command::new(command, args.clone(), &[]);
 
         assert!(result.is_ok());
         assert_eq!(*result.unwrap(), format!("{} {}", command, args[0]));
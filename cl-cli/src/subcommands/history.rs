@@ -0,0 +1,299 @@
+use super::Subcommand;
+use anyhow::{Context, Result};
+use cl_core::{
+    Command, CommandBuilder, CommandExec, Config, ExecStatus, History as CoreHistory, HistoryEntry,
+};
+use clap::Parser;
+use owo_colors::{colors::CustomColor, OwoColorize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, debug_span};
+
+#[derive(Parser)]
+pub struct History {
+    #[clap(short, long, help = "Show only the executions of the given alias")]
+    alias: Option<String>,
+    #[clap(short, long, help = "Show only the executions of the given namespace")]
+    namespace: Option<String>,
+    #[clap(short, long, action, help = "Show only the failed executions")]
+    failed: bool,
+    #[clap(
+        short,
+        long,
+        default_value_t = 20,
+        help = "The maximum number of entries to show"
+    )]
+    limit: usize,
+    #[clap(
+        short,
+        long,
+        value_name = "ID",
+        conflicts_with_all = ["alias", "namespace", "failed"],
+        help = "Run again the command of the entry with the given id"
+    )]
+    rerun: Option<usize>,
+}
+
+impl Subcommand for History {
+    fn run(&self, config: impl Config) -> Result<()> {
+        self.execute(config).map(|_| ())
+    }
+}
+
+impl History {
+    /// Shows the history or, with `--rerun`, runs an entry again, returning how it finished so
+    /// its exit code can be propagated
+    pub fn execute(&self, config: impl Config) -> Result<ExecStatus> {
+        let history = CoreHistory::new(config.history_file_path());
+        let entries = history.load().context("Cannot load the history file")?;
+
+        if let Some(id) = self.rerun {
+            let _span = debug_span!("history::rerun", id).entered();
+            let entry = id
+                .checked_sub(1)
+                .and_then(|idx| entries.get(idx))
+                .with_context(|| format!("There is no history entry with the id {id}"))?;
+            let command = command_of(entry);
+            // the values are recorded again, so the next reruns and suggestions keep them
+            let history = history.with_parameters(entry.parameters.clone());
+            debug!(target: "cl::history", command = %command.command, "running the command again");
+
+            return command
+                .exec(
+                    false,
+                    config.preferences().quiet_mode(),
+                    config.preferences().shell(),
+                    Some(&history),
+                )
+                .context("Cannot run the command");
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        self.filter(&entries)
+            .into_iter()
+            .for_each(|(id, entry)| println!("{}", summarize(id, entry, now)));
+
+        Ok(ExecStatus::Exited(0))
+    }
+
+    /// Returns the matching entries and their ids, most recent first
+    fn filter<'a>(&self, entries: &'a [HistoryEntry]) -> Vec<(usize, &'a HistoryEntry)> {
+        entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, entry)| {
+                self.alias
+                    .as_ref()
+                    .is_none_or(|alias| entry.alias.eq_ignore_ascii_case(alias))
+                    && self
+                        .namespace
+                        .as_ref()
+                        .is_none_or(|namespace| entry.namespace.eq_ignore_ascii_case(namespace))
+                    && (!self.failed || !entry.succeeded())
+            })
+            .take(self.limit)
+            .map(|(idx, entry)| (idx + 1, entry))
+            .collect()
+    }
+}
+
+/// The command recorded by `entry`, run from the same directory and with the same environment
+///
+/// Pipelines run their recorded steps again, each one with its own failure policy
+fn command_of(entry: &HistoryEntry) -> Command<'static> {
+    CommandBuilder::default()
+        .alias(entry.alias.to_owned())
+        .namespace(entry.namespace.to_owned())
+        .command(entry.command.to_owned())
+        .shell(entry.shell.to_owned())
+        .cwd(entry.cwd.as_ref().map(|cwd| cwd.to_string_lossy()))
+        .env(Some(entry.env.to_owned()))
+        .steps(entry.steps.to_owned())
+        .build()
+}
+
+fn summarize(id: usize, entry: &HistoryEntry, now: u64) -> String {
+    let status = match entry.exit_code {
+        Some(0) => "ok".to_owned(),
+        Some(code) => format!("exit {code}"),
+        None => "killed".to_owned(),
+    };
+    let cwd = entry
+        .cwd
+        .as_ref()
+        .map(|cwd| format!(" (in {})", cwd.display()))
+        .unwrap_or_default();
+
+    format!(
+        "{:>4}  {:>8}  {:>7}  {:>8}  {}.{} --> {}{}",
+        id,
+        elapsed(now.saturating_sub(entry.timestamp)),
+        status,
+        format!("{:.1}s", entry.duration_ms as f64 / 1000.0),
+        entry.namespace,
+        entry.alias,
+        entry.command.fg::<CustomColor<201, 165, 249>>(),
+        cwd
+    )
+}
+
+/// Returns a short, human readable representation of `seconds` ago
+pub(super) fn elapsed(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::{FailurePolicy, Preferences, Step};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    struct TestConfig {
+        preferences: Preferences,
+        command_file: PathBuf,
+    }
+
+    impl Config for TestConfig {
+        fn load() -> Result<Self> {
+            unimplemented!()
+        }
+
+        fn save(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn preferences(&self) -> &Preferences {
+            &self.preferences
+        }
+
+        fn preferences_mut(&mut self) -> &mut Preferences {
+            &mut self.preferences
+        }
+
+        fn command_file_path(&self) -> PathBuf {
+            self.command_file.clone()
+        }
+
+        fn log_dir_path(&self) -> Result<PathBuf> {
+            Ok(self.command_file.with_file_name("log"))
+        }
+    }
+
+    fn entry(alias: &str, namespace: &str, exit_code: Option<i32>) -> HistoryEntry {
+        HistoryEntry {
+            alias: alias.to_owned(),
+            namespace: namespace.to_owned(),
+            command: "echo".to_owned(),
+            exit_code,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_list_the_most_recent_entries_first() {
+        let history = History::parse_from(["history", "--limit", "2"]);
+        let entries = vec![
+            entry("a", "ns", Some(0)),
+            entry("b", "ns", Some(0)),
+            entry("c", "ns", Some(0)),
+        ];
+
+        let ids: Vec<usize> = history.filter(&entries).iter().map(|(id, _)| *id).collect();
+
+        assert_eq!(ids, vec![3, 2]);
+    }
+
+    #[test]
+    fn should_filter_entries() {
+        let history = History::parse_from(["history", "--namespace", "NS", "--failed"]);
+        let entries = vec![
+            entry("a", "ns", Some(1)),
+            entry("b", "ns", Some(0)),
+            entry("c", "other", None),
+        ];
+
+        let filtered = history.filter(&entries);
+
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].1.alias, "a");
+    }
+
+    #[test]
+    fn should_rerun_the_command_where_it_was_run() {
+        let entry = HistoryEntry {
+            cwd: Some("/tmp/project".into()),
+            env: [("RUST_LOG".to_owned(), "debug".to_owned())].into(),
+            ..entry("a", "ns", Some(0))
+        };
+
+        let command = command_of(&entry);
+
+        assert_eq!(command.command, "echo");
+        assert_eq!(command.cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(command.env, Some(entry.env));
+        assert!(!command.is_pipeline());
+    }
+
+    #[test]
+    fn should_rerun_the_steps_of_a_pipeline() {
+        let steps = vec![Step {
+            on_failure: FailurePolicy::Retry,
+            ..Step::run("cargo test")
+        }];
+        let entry = HistoryEntry {
+            steps: Some(steps.clone()),
+            ..entry("a", "ns", Some(0))
+        };
+
+        let command = command_of(&entry);
+
+        assert!(command.is_pipeline());
+        assert_eq!(command.steps, Some(steps));
+    }
+
+    #[test]
+    fn should_record_the_parameters_of_the_rerun_entry() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut preferences = Preferences::default();
+        preferences.set_quiet_mode(true);
+        let config = TestConfig {
+            preferences,
+            command_file: dir.path().join("commands.toml"),
+        };
+        let history = CoreHistory::new(config.history_file_path());
+        let parameters: std::collections::BTreeMap<_, _> =
+            [("name".to_owned(), "world".to_owned())].into();
+        history.append(&HistoryEntry {
+            command: "true".to_owned(),
+            parameters: parameters.clone(),
+            ..entry("a", "ns", Some(0))
+        })?;
+
+        let status = History::parse_from(["history", "--rerun", "1"]).execute(config)?;
+
+        let entries = history.load()?;
+        assert_eq!(status, ExecStatus::Exited(0));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].parameters, parameters);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_describe_the_elapsed_time() {
+        assert_eq!(elapsed(5), "5s ago");
+        assert_eq!(elapsed(120), "2m ago");
+        assert_eq!(elapsed(7200), "2h ago");
+        assert_eq!(elapsed(172800), "2d ago");
+    }
+}
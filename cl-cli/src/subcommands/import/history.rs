@@ -0,0 +1,243 @@
+use clap::ValueEnum;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The programs whose commands are not worth saving, as they only change the state of the shell
+const IGNORED_PROGRAMS: [&str; 8] = ["cd", "cl", "exit", "clear", "history", "source", ".", "z"];
+
+/// The shells whose history can be read
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum HistoryShell {
+    /// One command per line, with optional `#<timestamp>` lines
+    Bash,
+    /// The extended history format, `: <timestamp>:<duration>;<command>`, or one command per line
+    Zsh,
+    /// The `- cmd: <command>` entries of the fish history
+    Fish,
+}
+
+impl HistoryShell {
+    /// The shell of a history file, by its name, e.g. `.zsh_history` or `fish_history`
+    pub fn of(path: &Path) -> HistoryShell {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.contains("fish") {
+            HistoryShell::Fish
+        } else if name.contains("zsh") || name.contains("zhistory") {
+            HistoryShell::Zsh
+        } else {
+            HistoryShell::Bash
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HistoryShell::Bash => "bash",
+            HistoryShell::Zsh => "zsh",
+            HistoryShell::Fish => "fish",
+        }
+    }
+
+    /// The commands of a history file, from the oldest to the newest
+    pub fn parse(&self, content: &[u8]) -> Vec<String> {
+        match self {
+            HistoryShell::Bash => parse_bash(&String::from_utf8_lossy(content)),
+            HistoryShell::Zsh => parse_zsh(&String::from_utf8_lossy(&unmetafy(content))),
+            HistoryShell::Fish => parse_fish(&String::from_utf8_lossy(content)),
+        }
+    }
+}
+
+/// A command of the history, with how many times it was run
+#[derive(Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub command: String,
+    pub count: usize,
+}
+
+/// The commands worth saving, the most frequent first, or the most recent on ties
+pub fn rank(commands: &[String]) -> Vec<Candidate> {
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (idx, command) in commands.iter().enumerate() {
+        let command = command.trim();
+        if !is_worth_saving(command) {
+            continue;
+        }
+        let (count, last_use) = counts.entry(command).or_default();
+        *count += 1;
+        *last_use = idx;
+    }
+
+    counts
+        .into_iter()
+        .sorted_by(|(_, (a_count, a_last)), (_, (b_count, b_last))| {
+            b_count.cmp(a_count).then(b_last.cmp(a_last))
+        })
+        .map(|(command, (count, _))| Candidate {
+            command: command.to_owned(),
+            count,
+        })
+        .collect()
+}
+
+/// Whether a command does something on its own, e.g. not `ls` nor `cd ~/projects`
+fn is_worth_saving(command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some(program), Some(_)) => !IGNORED_PROGRAMS.contains(&program),
+        _ => false,
+    }
+}
+
+/// One command per line, skipping the timestamps written when `HISTTIMEFORMAT` is set
+fn parse_bash(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| {
+            !line
+                .strip_prefix('#')
+                .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// The extended format, `: <timestamp>:<duration>;<command>`, where the lines of a command end
+/// with a `\`
+fn parse_zsh(content: &str) -> Vec<String> {
+    let mut commands = vec![];
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let mut command = match line
+            .strip_prefix(": ")
+            .and_then(|rest| rest.split_once(';'))
+        {
+            Some((_, command)) => command.to_owned(),
+            None => line.to_owned(),
+        };
+        while command.ends_with('\\') {
+            let Some(next) = lines.next() else {
+                break;
+            };
+            command.pop();
+            command.push('\n');
+            command.push_str(next);
+        }
+        commands.push(command);
+    }
+
+    commands
+}
+
+/// The `- cmd: <command>` entries, with their `\\` and `\n` escaped
+fn parse_fish(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("- cmd: "))
+        .map(|command| {
+            let mut unescaped = String::with_capacity(command.len());
+            let mut chars = command.chars();
+            while let Some(c) = chars.next() {
+                match (c, chars.clone().next()) {
+                    ('\\', Some('\\')) => {
+                        unescaped.push('\\');
+                        chars.next();
+                    }
+                    ('\\', Some('n')) => {
+                        unescaped.push('\n');
+                        chars.next();
+                    }
+                    (c, _) => unescaped.push(c),
+                }
+            }
+            unescaped
+        })
+        .collect()
+}
+
+/// zsh writes some bytes of the non ASCII characters as a `0x83` marker followed by the byte
+/// xor `0x20`
+fn unmetafy(content: &[u8]) -> Vec<u8> {
+    const META: u8 = 0x83;
+
+    let mut bytes = Vec::with_capacity(content.len());
+    let mut iter = content.iter();
+    while let Some(&byte) = iter.next() {
+        match byte {
+            META => bytes.extend(iter.next().map(|next| next ^ 0x20)),
+            byte => bytes.push(byte),
+        }
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_parse_the_history_of_each_shell() {
+        let bash = "#1700000000\ngit status\n# a comment\nls -la\n";
+        let zsh =
+            ": 1700000000:0;git status\n: 1700000001:2;for f in *; do\\\necho $f\\\ndone\nls\n";
+        let fish = "- cmd: git status\n  when: 1700000000\n- cmd: echo \\\\n\\nok\n  when: 1700000001\n  paths:\n    - ok\n";
+
+        assert_eq!(
+            HistoryShell::Bash.parse(bash.as_bytes()),
+            vec!["git status", "# a comment", "ls -la"]
+        );
+        assert_eq!(
+            HistoryShell::Zsh.parse(zsh.as_bytes()),
+            vec!["git status", "for f in *; do\necho $f\ndone", "ls"]
+        );
+        assert_eq!(
+            HistoryShell::Fish.parse(fish.as_bytes()),
+            vec!["git status", "echo \\n\nok"]
+        );
+        assert_eq!(
+            HistoryShell::Zsh.parse(b": 1700000000:0;echo \xc3\x83\x80"),
+            vec!["echo à"]
+        );
+    }
+
+    #[test]
+    fn should_rank_the_commands_by_frequency_then_by_recency() {
+        let history = [
+            "git status",
+            "cargo test",
+            "cd ~/projects",
+            "ls",
+            "git status",
+            "cargo build",
+            "cargo test",
+            "cl exec st",
+            "  git status ",
+        ]
+        .map(String::from);
+
+        let ranked = rank(&history);
+
+        assert_eq!(
+            ranked,
+            vec![
+                Candidate {
+                    command: "git status".to_owned(),
+                    count: 3
+                },
+                Candidate {
+                    command: "cargo test".to_owned(),
+                    count: 2
+                },
+                Candidate {
+                    command: "cargo build".to_owned(),
+                    count: 1
+                },
+            ]
+        );
+    }
+}
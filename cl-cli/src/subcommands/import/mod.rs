@@ -0,0 +1,361 @@
+mod history;
+mod rc_file;
+
+use super::alias;
+use super::prompt::Prompt;
+use super::share::Share;
+use super::Subcommand;
+use anyhow::{bail, Context, Result};
+use cl_core::{initialize_commands, Command, CommandBuilder, Config};
+use clap::{Parser, Subcommand as ClapSubcommand};
+use dirs::home_dir;
+use history::HistoryShell;
+use itertools::Itertools;
+use rc_file::Failure;
+use std::collections::HashSet;
+use std::env;
+use std::fs::{self, read_to_string};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use tracing::{debug_span, info};
+
+/// The rc files read when none is given
+const RC_FILES: [&str; 3] = [".bashrc", ".bash_aliases", ".zshrc"];
+/// How many of the most frequent commands of the history are listed to be picked
+const HISTORY_CANDIDATES: usize = 30;
+
+#[derive(Parser)]
+pub struct Import {
+    #[clap(subcommand)]
+    source: ImportSource,
+}
+
+#[derive(ClapSubcommand)]
+enum ImportSource {
+    #[clap(about = "Import the aliases and the simple functions of your bash or zsh rc files")]
+    Aliases {
+        #[clap(
+            help = "The rc files to import. Defaults to ~/.bashrc, ~/.bash_aliases and ~/.zshrc"
+        )]
+        files: Vec<PathBuf>,
+        #[clap(
+            short,
+            long,
+            default_value = "shell",
+            help = "The namespace of the imported commands"
+        )]
+        namespace: String,
+        #[clap(long, help = "List the commands to be imported, without saving them")]
+        dry_run: bool,
+    },
+    #[clap(about = "Import the most frequent commands of your bash, zsh or fish history")]
+    History {
+        #[clap(help = "The history file. Defaults to the one of your $SHELL")]
+        file: Option<PathBuf>,
+        #[clap(
+            long,
+            value_enum,
+            help = "The shell that wrote the history. Defaults to the one of the file name"
+        )]
+        shell: Option<HistoryShell>,
+        #[clap(
+            short,
+            long,
+            default_value = "history",
+            help = "The namespace of the imported commands"
+        )]
+        namespace: String,
+        #[clap(
+            long,
+            help = "Import the N most frequent commands, instead of picking them.\n\
+            Required when the stdin is not a terminal"
+        )]
+        top: Option<usize>,
+        #[clap(long, help = "List the commands to be imported, without saving them")]
+        dry_run: bool,
+    },
+}
+
+impl Subcommand for Import {
+    fn run(&self, config: impl Config) -> Result<()> {
+        match &self.source {
+            ImportSource::Aliases {
+                files,
+                namespace,
+                dry_run,
+            } => import_aliases(config, files, namespace, *dry_run),
+            ImportSource::History {
+                file,
+                shell,
+                namespace,
+                top,
+                dry_run,
+            } => import_history(config, file.as_deref(), *shell, namespace, *top, *dry_run),
+        }
+    }
+}
+
+fn import_aliases(
+    config: impl Config,
+    files: &[PathBuf],
+    namespace: &str,
+    dry_run: bool,
+) -> Result<()> {
+    let _span = debug_span!("import::aliases", namespace, dry_run).entered();
+    let files = if files.is_empty() {
+        default_rc_files()
+    } else {
+        files.to_vec()
+    };
+    if files.is_empty() {
+        bail!("No rc file found. Give the files to import")
+    }
+
+    let mut converted = vec![];
+    let mut failed = 0;
+    for file in &files {
+        let content =
+            read_to_string(file).with_context(|| format!("Cannot read {}", file.display()))?;
+        let (definitions, mut failures) = rc_file::parse(&content);
+
+        for definition in definitions {
+            let command = CommandBuilder::default()
+                .alias(definition.name.to_owned())
+                .namespace(namespace.to_owned())
+                .command(definition.command.to_owned())
+                .shell(shell_of(file))
+                .build();
+            match command.validate() {
+                Ok(_) => converted.push(command),
+                Err(err) => failures.push(Failure {
+                    line: definition.line,
+                    name: Some(definition.name),
+                    reason: err.to_string(),
+                }),
+            }
+        }
+        for failure in failures.iter().sorted_by_key(|failure| failure.line) {
+            println!("Cannot import {}:{failure}", file.display());
+            failed += 1;
+        }
+    }
+    // as in the shell, the last definition wins
+    let mut converted: Vec<Command> = converted.into_iter().rev().unique().collect();
+    converted.reverse();
+
+    let storage = config.storage();
+    // taken before loading, so the commands changed in the meantime are not overwritten
+    let fingerprint = storage.fingerprint()?;
+    let mut commands = initialize_commands!(storage);
+
+    let stored = commands.as_list();
+    let duplicates: HashSet<(String, String)> = Share::find_duplicates(&stored, &converted)
+        .into_iter()
+        .map(|(alias, namespace)| (alias.to_owned(), namespace.to_owned()))
+        .collect();
+    converted.retain(|command| {
+        !duplicates.contains(&(command.alias.to_string(), command.namespace.to_string()))
+    });
+
+    for (alias, namespace) in duplicates.iter().sorted() {
+        println!("Skipped '{alias}', as it already exists in the '{namespace}' namespace");
+    }
+
+    if dry_run {
+        for command in &converted {
+            println!(
+                "{} = {}",
+                command.alias,
+                command.command.replace('\n', "; ")
+            );
+        }
+        println!("{} commands would be imported", converted.len());
+        return Ok(());
+    }
+
+    let mut imported = 0;
+    for command in &converted {
+        // e.g. the aliases differing only in case from the stored ones
+        match commands.add(command) {
+            Ok(_) => imported += 1,
+            Err(err) => println!("Skipped '{}': {err}", command.alias),
+        }
+    }
+    if imported == 0 {
+        println!("No commands to import");
+        return Ok(());
+    }
+
+    storage
+        .save_if_unchanged(commands.as_map(), &fingerprint)
+        .context("Could not import the aliases")?;
+    info!(target: "cl::import", count = imported, skipped = duplicates.len(), failed, "aliases imported");
+    println!("Imported {imported} commands into the '{namespace}' namespace");
+
+    Ok(())
+}
+
+fn import_history(
+    config: impl Config,
+    file: Option<&Path>,
+    shell: Option<HistoryShell>,
+    namespace: &str,
+    top: Option<usize>,
+    dry_run: bool,
+) -> Result<()> {
+    let _span = debug_span!("import::history", namespace, ?top, dry_run).entered();
+    let file = match file {
+        Some(file) => file.to_path_buf(),
+        None => default_history_file().context("No history file found. Give the file to import")?,
+    };
+    let shell = shell.unwrap_or_else(|| HistoryShell::of(&file));
+    let content = fs::read(&file).with_context(|| format!("Cannot read {}", file.display()))?;
+    let history = shell.parse(&content);
+
+    let storage = config.storage();
+    // taken before loading, so the commands changed in the meantime are not overwritten
+    let fingerprint = storage.fingerprint()?;
+    let mut commands = initialize_commands!(storage);
+
+    let stored = commands.as_list();
+    let saved: HashSet<&str> = stored
+        .iter()
+        .map(|command| command.command.trim())
+        .collect();
+    let mut candidates = history::rank(&history);
+    candidates.retain(|candidate| !saved.contains(candidate.command.as_str()));
+    if candidates.is_empty() {
+        println!("No commands to import");
+        return Ok(());
+    }
+
+    let picked: Vec<&str> = match top {
+        Some(top) => candidates
+            .iter()
+            .take(top)
+            .map(|candidate| candidate.command.as_str())
+            .collect(),
+        None => {
+            if !io::stdin().is_terminal() {
+                bail!("Use `--top N` to import the history when the stdin is not a terminal")
+            }
+            let candidates = &candidates[..candidates.len().min(HISTORY_CANDIDATES)];
+            let items = candidates
+                .iter()
+                .map(|candidate| {
+                    format!(
+                        "({}x) {}",
+                        candidate.count,
+                        candidate.command.replace('\n', "; ")
+                    )
+                })
+                .collect::<Vec<_>>();
+            let mut prompt = Prompt::new(io::stdin().lock(), io::stderr());
+            prompt
+                .select("Commands to import", &items)?
+                .into_iter()
+                .map(|idx| candidates[idx].command.as_str())
+                .collect()
+        }
+    };
+
+    let mut taken = alias::taken_aliases(&stored, namespace);
+    let converted = picked
+        .into_iter()
+        .map(|command| {
+            CommandBuilder::default()
+                .alias(alias::generate_alias(command, &mut taken))
+                .namespace(namespace.to_owned())
+                .command(command.to_owned())
+                .shell(Some(shell.name()))
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    if dry_run {
+        for command in &converted {
+            println!(
+                "{} = {}",
+                command.alias,
+                command.command.replace('\n', "; ")
+            );
+        }
+        println!("{} commands would be imported", converted.len());
+        return Ok(());
+    }
+
+    let mut imported = 0;
+    for command in &converted {
+        match command
+            .validate()
+            .and_then(|_| commands.add(command).map(|_| ()))
+        {
+            Ok(_) => imported += 1,
+            Err(err) => println!("Skipped '{}': {err}", command.command),
+        }
+    }
+    if imported == 0 {
+        println!("No commands to import");
+        return Ok(());
+    }
+
+    storage
+        .save_if_unchanged(commands.as_map(), &fingerprint)
+        .context("Could not import the history")?;
+    info!(target: "cl::import", count = imported, shell = shell.name(), "history imported");
+    println!("Imported {imported} commands into the '{namespace}' namespace");
+
+    Ok(())
+}
+
+/// The history file of the shell in `$SHELL`, if it exists
+fn default_history_file() -> Option<PathBuf> {
+    let home = home_dir()?;
+    let shell = env::var("SHELL").unwrap_or_default();
+    let file = match shell.rsplit('/').next().unwrap_or_default() {
+        "zsh" => home.join(".zsh_history"),
+        "fish" => env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".local/share"))
+            .join("fish/fish_history"),
+        _ => home.join(".bash_history"),
+    };
+
+    file.is_file().then_some(file)
+}
+
+fn default_rc_files() -> Vec<PathBuf> {
+    let Some(home) = home_dir() else {
+        return vec![];
+    };
+
+    RC_FILES
+        .iter()
+        .map(|file| home.join(file))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// The imported commands run in the shell of their rc file, as they may use its syntax
+fn shell_of(file: &Path) -> Option<String> {
+    let name = file.file_name()?.to_string_lossy();
+    ["zsh", "bash"]
+        .into_iter()
+        .find(|shell| name.contains(shell))
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_find_the_shell_of_the_rc_file() {
+        assert_eq!(shell_of(Path::new("/home/me/.zshrc")), Some("zsh".into()));
+        assert_eq!(
+            shell_of(Path::new("/home/me/.bash_aliases")),
+            Some("bash".into())
+        );
+        assert_eq!(shell_of(Path::new("/home/me/aliases.sh")), None);
+    }
+}
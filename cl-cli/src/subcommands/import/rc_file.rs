@@ -0,0 +1,422 @@
+use regex::Regex;
+use std::fmt;
+use std::sync::LazyLock;
+
+/// `name() {`, `function name {` or `function name() {`, with the brace being optional
+static FUNCTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:function\s+([\w.:-]+)\s*(?:\(\s*\))?|([\w.:-]+)\s*\(\s*\))\s*(\{.*)?$")
+        .expect("Invalid regex pattern")
+});
+/// `$1`, `"$2"` or `${3}`, replaced by the `#{argN}` named parameters unless in single quotes
+static POSITIONAL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$(?:([1-9])|\{([1-9])\})").expect("Invalid regex pattern"));
+/// The arguments passed along at the end of a function, which `cl exec` appends anyway
+static TRAILING_ARGS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\s*"?\$[@*]"?;?$"#).expect("Invalid regex pattern"));
+/// What a function may use, but a command cannot do
+const UNSUPPORTED: [&str; 7] = ["local ", "return", "shift", "$#", "$0", "$@", "$*"];
+
+/// An alias or a function of a rc file
+#[derive(Debug, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    pub command: String,
+    pub line: usize,
+}
+
+/// A definition that could not be converted to a command
+#[derive(Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub line: usize,
+    pub name: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} '{name}': {}", self.line, self.reason),
+            None => write!(f, "{}: {}", self.line, self.reason),
+        }
+    }
+}
+
+impl Failure {
+    fn new(line: usize, name: Option<&str>, reason: impl Into<String>) -> Self {
+        Self {
+            line,
+            name: name.map(String::from),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Finds the aliases and the simple functions of a bash or zsh rc file
+///
+/// Everything else, e.g. exports and options, is ignored
+pub fn parse(content: &str) -> (Vec<Definition>, Vec<Failure>) {
+    let mut definitions = vec![];
+    let mut failures = vec![];
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx].trim();
+        let number = idx + 1;
+        idx += 1;
+
+        if let Some(rest) = line.strip_prefix("alias ") {
+            match parse_aliases(rest, number) {
+                Ok(aliases) => definitions.extend(aliases),
+                Err(failure) => failures.push(failure),
+            }
+        } else if let Some(captures) = FUNCTION_REGEX.captures(line) {
+            let name = captures
+                .get(1)
+                .or_else(|| captures.get(2))
+                .map_or("", |m| m.as_str());
+            let mut text = captures
+                .get(3)
+                .map_or(String::new(), |m| m.as_str().to_owned());
+            while !is_closed(&text) && idx < lines.len() {
+                text.push('\n');
+                text.push_str(lines[idx].trim());
+                idx += 1;
+            }
+
+            if !is_closed(&text) {
+                failures.push(Failure::new(
+                    number,
+                    Some(name),
+                    "the function is never closed",
+                ));
+                // the next definitions may still be valid
+                idx = number;
+                continue;
+            }
+            match function_command(&text) {
+                Ok(command) => definitions.push(Definition {
+                    name: name.to_owned(),
+                    command,
+                    line: number,
+                }),
+                Err(reason) => failures.push(Failure::new(number, Some(name), reason)),
+            }
+        }
+    }
+
+    (definitions, failures)
+}
+
+/// `alias a='...' b="..."`, i.e. the words after `alias`
+fn parse_aliases(line: &str, number: usize) -> Result<Vec<Definition>, Failure> {
+    let words = split_words(line).map_err(|reason| Failure::new(number, None, reason))?;
+
+    let mut definitions = vec![];
+    for word in words {
+        if word == "-g" || word == "-s" {
+            return Err(Failure::new(
+                number,
+                None,
+                "global and suffix aliases are not commands",
+            ));
+        }
+        if word.starts_with('-') {
+            continue;
+        }
+        match word.split_once('=') {
+            Some((name, command)) if !command.trim().is_empty() => definitions.push(Definition {
+                name: name.to_owned(),
+                command: command.to_owned(),
+                line: number,
+            }),
+            Some((name, _)) => {
+                return Err(Failure::new(number, Some(name), "the alias is empty"));
+            }
+            None => {
+                return Err(Failure::new(
+                    number,
+                    Some(&word),
+                    "prints an alias instead of defining it",
+                ));
+            }
+        }
+    }
+
+    Ok(definitions)
+}
+
+/// Splits a line in shell words, removing their quotes, until an unquoted comment
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let unterminated = || "the quotes are never closed".to_owned();
+    let mut words = vec![];
+    let mut word = String::new();
+    // quoted empty strings are words too
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or_else(unterminated)? {
+                            c @ ('"' | '\\' | '$' | '`') => word.push(c),
+                            c => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            '#' if !in_word => break,
+            ';' | '&' | '|' | '<' | '>' | '(' | ')' | '`' => {
+                return Err(format!("'{c}' outside of the quotes is not supported"));
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// Whether the body of the function has all its braces closed, ignoring the quoted ones
+fn is_closed(text: &str) -> bool {
+    let quoting = quoting(text);
+    let count = |brace: char| {
+        text.match_indices(brace)
+            .filter(|(idx, _)| quoting[*idx] == Quoting::Unquoted)
+            .count()
+    };
+    let opened = count('{');
+    opened > 0 && opened == count('}')
+}
+
+/// The body of a simple function, with its positional arguments as named parameters
+fn function_command(text: &str) -> Result<String, String> {
+    let body = text
+        .trim()
+        .strip_prefix('{')
+        .and_then(|body| body.strip_suffix('}'))
+        .ok_or_else(|| "the function body is not a single block".to_owned())?;
+    let lines: Vec<&str> = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.iter().any(|line| FUNCTION_REGEX.is_match(line)) {
+        return Err("the function defines other functions".to_owned());
+    }
+
+    let body = lines.join("\n");
+    let body = TRAILING_ARGS_REGEX.replace(&body, "");
+    let body = body.trim_end_matches(';').trim();
+    if body.is_empty() {
+        return Err("the function is empty".to_owned());
+    }
+    // e.g. the `$1` of `awk '{print $1}'` is not an argument of the function
+    let quoting = quoting(body);
+    let is_expanded = |idx: usize| quoting[idx] != Quoting::Literal;
+    let unsupported = UNSUPPORTED
+        .iter()
+        .find(|word| body.match_indices(*word).any(|(idx, _)| is_expanded(idx)));
+    if let Some(unsupported) = unsupported {
+        return Err(format!(
+            "'{}' is not supported, only simple functions are",
+            unsupported.trim()
+        ));
+    }
+
+    Ok(POSITIONAL_REGEX
+        .replace_all(body, |caps: &regex::Captures| {
+            let found = caps.get(0).expect("the match must exist");
+            if !is_expanded(found.start()) {
+                return found.as_str().to_owned();
+            }
+            let position = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map_or("", |m| m.as_str());
+            format!("#{{arg{position}}}")
+        })
+        .into_owned())
+}
+
+/// How a character of a shell text is quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quoting {
+    Unquoted,
+    /// In double quotes, where the variables are still expanded
+    Double,
+    /// In single quotes or escaped, i.e. taken as is
+    Literal,
+}
+
+/// The quoting of each byte of `text`
+fn quoting(text: &str) -> Vec<Quoting> {
+    let mut quoting = Vec::with_capacity(text.len());
+    let (mut single, mut double) = (false, false);
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        let current = match c {
+            '\\' if !single => {
+                let escaped = chars.next().map_or(0, char::len_utf8);
+                quoting.extend(std::iter::repeat_n(Quoting::Literal, 1 + escaped));
+                continue;
+            }
+            '\'' if !double => {
+                single = !single;
+                Quoting::Literal
+            }
+            '"' if !single => {
+                double = !double;
+                Quoting::Double
+            }
+            _ if single => Quoting::Literal,
+            _ if double => Quoting::Double,
+            _ => Quoting::Unquoted,
+        };
+        quoting.extend(std::iter::repeat_n(current, c.len_utf8()));
+    }
+
+    quoting
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn definition(name: &str, command: &str, line: usize) -> Definition {
+        Definition {
+            name: name.to_owned(),
+            command: command.to_owned(),
+            line,
+        }
+    }
+
+    #[test]
+    fn should_parse_the_aliases() {
+        let content = r#"
+# aliases
+alias ll='ls -la'  # long list
+alias gs="git status" gd='git diff'
+  alias please='sudo $(fc -ln -1)'
+alias quote='echo '\''quoted'\'''
+export PATH="$HOME/bin:$PATH"
+"#;
+
+        let (definitions, failures) = parse(content);
+
+        assert_eq!(
+            definitions,
+            vec![
+                definition("ll", "ls -la", 3),
+                definition("gs", "git status", 4),
+                definition("gd", "git diff", 4),
+                definition("please", "sudo $(fc -ln -1)", 5),
+                definition("quote", "echo 'quoted'", 6),
+            ]
+        );
+        assert!(failures.is_empty());
+    }
+
+    #[test]
+    fn should_parse_the_simple_functions() {
+        let content = r#"
+mkcd() { mkdir -p "$1" && cd "$1"; }
+function gcm {
+    git add -A
+    git commit -m "${1}" "$@"
+}
+function greet()
+{
+  echo "hello $1, $2"
+}
+ports() { netstat -tulpn | awk '{print $4}' | grep "$1"; }
+say() { echo "it's $1" '$2' \$3; }
+"#;
+
+        let (definitions, failures) = parse(content);
+
+        assert_eq!(
+            definitions,
+            vec![
+                definition("mkcd", r##"mkdir -p "#{arg1}" && cd "#{arg1}""##, 2),
+                definition("gcm", "git add -A\ngit commit -m \"#{arg1}\"", 3),
+                definition("greet", "echo \"hello #{arg1}, #{arg2}\"", 7),
+                definition(
+                    "ports",
+                    r##"netstat -tulpn | awk '{print $4}' | grep "#{arg1}""##,
+                    11
+                ),
+                definition("say", r##"echo "it's #{arg1}" '$2' \$3"##, 12),
+            ]
+        );
+        assert!(failures.is_empty());
+    }
+
+    #[test]
+    fn should_report_what_cannot_be_converted() {
+        let content = r#"alias -g G='| grep'
+alias broken='oops
+alias ll
+count() { local n=$#; echo $n; }
+alias ok=ls; alias other=pwd
+unclosed() {
+  echo "oops"
+br() { echo "}"; }
+alias ll='ls -la'
+"#;
+
+        let (definitions, failures) = parse(content);
+
+        assert_eq!(
+            definitions,
+            vec![
+                definition("br", r#"echo "}""#, 8),
+                definition("ll", "ls -la", 9),
+            ]
+        );
+        assert_eq!(
+            failures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "1: global and suffix aliases are not commands",
+                "2: the quotes are never closed",
+                "3 'll': prints an alias instead of defining it",
+                "4 'count': 'local' is not supported, only simple functions are",
+                "5: ';' outside of the quotes is not supported",
+                "6 'unclosed': the function is never closed",
+            ]
+        );
+    }
+}
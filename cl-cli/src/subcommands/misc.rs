@@ -20,7 +20,7 @@ pub struct Misc {
 
 impl Subcommand for Misc {
     fn run(&self, config: impl Config) -> Result<()> {
-        let commands = initialize_commands!(config.command_file_path());
+        let commands = initialize_commands!(config.storage());
         let command_vec = commands.as_list();
         let sorted_commands = command_vec
             .iter()
@@ -190,6 +190,13 @@ mod test {
             description: Some(Cow::Borrowed("description")),
             alias: Cow::Borrowed("alias"),
             tags: None,
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
         };
         let expected_output = "namespace.alias: description --> command".to_owned();
         assert_eq!(command.summarize(), expected_output);
@@ -203,6 +210,13 @@ mod test {
             description: None,
             alias: Cow::Borrowed("alias"),
             tags: None,
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
         };
         let expected_output = "namespace.alias --> command".to_owned();
         assert_eq!(command.summarize(), expected_output);
@@ -217,6 +231,13 @@ mod test {
             description: None,
             alias: Cow::Borrowed("al"),
             tags: None,
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
         };
         let result = command.summarize();
         assert!(
@@ -236,6 +257,13 @@ mod test {
             description: None,
             alias: Cow::Borrowed("al"),
             tags: None,
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
         };
         let result = command.summarize();
         assert!(result.ends_with("first line..."));
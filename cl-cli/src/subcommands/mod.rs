@@ -1,14 +1,32 @@
 mod add;
+mod alias;
+mod backup;
 mod config;
+mod doctor;
+mod edit;
 mod exec;
+mod history;
+mod import;
 mod misc;
+mod mv;
+mod prompt;
+mod rm;
 mod share;
+mod show;
 
 pub use add::Add;
+pub use backup::Backup;
 pub use config::Config;
+pub use doctor::Doctor;
+pub use edit::Edit;
 pub use exec::Exec;
+pub use history::History;
+pub use import::Import;
 pub use misc::Misc;
+pub use mv::Move;
+pub use rm::Remove;
 pub use share::Share;
+pub use show::Show;
 
 use anyhow::Result;
 use cl_core::Config as CoreConfig;
@@ -0,0 +1,77 @@
+use super::Subcommand;
+use anyhow::{Context, Result};
+use cl_core::{initialize_commands, Config};
+use clap::{ArgGroup, Parser};
+use std::borrow::Cow;
+use tracing::{debug_span, info};
+
+#[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("destination")
+        .required(true)
+        .multiple(true)
+        .args(["to", "rename"])
+))]
+pub struct Move {
+    #[clap(
+        required = true,
+        help = "The alias of the command to be moved or renamed"
+    )]
+    alias: String,
+    #[clap(
+        short,
+        long,
+        help = "The namespace of the command, in case of duplicate aliases"
+    )]
+    namespace: Option<String>,
+    #[clap(short, long, value_name = "NAMESPACE", help = "The new namespace")]
+    to: Option<String>,
+    #[clap(short, long, value_name = "ALIAS", help = "The new alias")]
+    rename: Option<String>,
+}
+
+impl Subcommand for Move {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let _span = debug_span!("move", alias = %self.alias, namespace = ?self.namespace).entered();
+        let storage = config.storage();
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let mut commands = initialize_commands!(storage);
+
+        let current = commands
+            .find(&self.alias, self.namespace.as_deref())
+            .context("Failed to find the command with the given alias and namespace")?;
+        let mut moved = current.clone();
+        if let Some(namespace) = &self.to {
+            moved.namespace = Cow::Owned(namespace.to_owned());
+        }
+        if let Some(alias) = &self.rename {
+            moved.alias = Cow::Owned(alias.to_owned());
+        }
+
+        let result = commands.edit(&moved, &current)?;
+        storage.save_if_unchanged(result, &fingerprint)?;
+        info!(
+            target: "cl::move",
+            from = %format!("{}.{}", current.namespace, current.alias),
+            to = %format!("{}.{}", moved.namespace, moved.alias),
+            "command moved"
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_require_a_destination() {
+        assert!(Move::try_parse_from(["mv", "st"]).is_err());
+
+        let mv = Move::parse_from(["mv", "st", "--to", "vcs", "--rename", "status"]);
+        assert_eq!(mv.to.as_deref(), Some("vcs"));
+        assert_eq!(mv.rename.as_deref(), Some("status"));
+    }
+}
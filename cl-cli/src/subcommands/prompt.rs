@@ -0,0 +1,309 @@
+use anyhow::{bail, Context, Result};
+use cl_core::Parameter;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// Interactively asks for the values of named parameters or for confirmations
+pub struct Prompt<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R, W> Prompt<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(reader: R, writer: W) -> Self {
+        Self { reader, writer }
+    }
+
+    /// Asks for each parameter in `names`, in order, returning them as `--name=value` args
+    ///
+    /// ## Arguments
+    /// * `names` - The named parameters to be asked for
+    /// * `parameters` - The parameters declarations of the command, used to describe and validate the values
+    /// * `recent` - The values given to each parameter in the previous executions, the most recent first.
+    ///   The most recent one is used when the answer is empty
+    pub fn ask(
+        &mut self,
+        names: &[String],
+        parameters: &[Parameter],
+        recent: &HashMap<String, Vec<String>>,
+    ) -> Result<Vec<String>> {
+        let mut args = Vec::with_capacity(names.len());
+
+        for name in names {
+            let parameter = parameters.iter().find(|p| &p.name == name);
+            let recent = recent.get(name).map(Vec::as_slice).unwrap_or_default();
+            let value = self.ask_one(name, parameter, recent)?;
+            args.push(format!("--{name}={value}"));
+        }
+
+        Ok(args)
+    }
+
+    /// Asks a yes or no `question`, answered with no by default
+    pub fn confirm(&mut self, question: &str) -> Result<bool> {
+        write!(self.writer, "{question} [y/N] ")?;
+        self.writer.flush()?;
+
+        let mut input = String::new();
+        self.reader
+            .read_line(&mut input)
+            .context("Cannot read the answer")?;
+
+        Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Asks to choose one of the `options`, answered with its first letter or its name, or with the
+    /// first option by default
+    ///
+    /// Returns the index of the chosen option
+    pub fn choose(&mut self, question: &str, options: &[&str]) -> Result<usize> {
+        let choices = options
+            .iter()
+            .map(|option| {
+                let (first, rest) =
+                    option.split_at(option.chars().next().map_or(0, char::len_utf8));
+                format!("[{first}]{rest}")
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        loop {
+            write!(self.writer, "{question} {choices} ")?;
+            self.writer.flush()?;
+
+            let mut input = String::new();
+            self.reader
+                .read_line(&mut input)
+                .context("Cannot read the answer")?;
+
+            let answer = input.trim().to_lowercase();
+            if answer.is_empty() {
+                return Ok(0);
+            }
+            let chosen = options.iter().position(|option| {
+                *option == answer || (answer.chars().count() == 1 && option.starts_with(&answer))
+            });
+            match chosen {
+                Some(idx) => return Ok(idx),
+                None => writeln!(
+                    self.writer,
+                    "'{answer}' is not one of {}",
+                    options.join(", ")
+                )?,
+            }
+        }
+    }
+
+    /// Lists the numbered `items`, asking which ones are picked, e.g. `1 3 5-7`
+    ///
+    /// Returns the indexes of the picked items, in order, or none if the answer is empty
+    pub fn select(&mut self, question: &str, items: &[String]) -> Result<Vec<usize>> {
+        let width = items.len().to_string().len();
+        for (idx, item) in items.iter().enumerate() {
+            writeln!(self.writer, "{:>width$}. {item}", idx + 1)?;
+        }
+
+        loop {
+            write!(self.writer, "{question} (e.g. 1 3 5-7, empty for none): ")?;
+            self.writer.flush()?;
+
+            let mut input = String::new();
+            self.reader
+                .read_line(&mut input)
+                .context("Cannot read the answer")?;
+
+            match parse_selection(&input, items.len()) {
+                Ok(selected) => return Ok(selected),
+                Err(err) => writeln!(self.writer, "{err}")?,
+            }
+        }
+    }
+
+    fn ask_one(
+        &mut self,
+        name: &str,
+        parameter: Option<&Parameter>,
+        recent: &[String],
+    ) -> Result<String> {
+        if let Some(parameter) = parameter {
+            writeln!(self.writer, "{}", parameter.summary())?;
+        }
+        if !recent.is_empty() {
+            writeln!(self.writer, "Recent values: {}", recent.join(", "))?;
+        }
+
+        loop {
+            match recent.first() {
+                Some(last) => write!(self.writer, "#{{{name}}} [{last}]: ")?,
+                None => write!(self.writer, "#{{{name}}}: ")?,
+            }
+            self.writer.flush()?;
+
+            let mut input = String::new();
+            let read = self
+                .reader
+                .read_line(&mut input)
+                .context("Cannot read the parameter value")?;
+            if read == 0 {
+                bail!("No value provided for the named parameter #{{{name}}}")
+            }
+
+            let mut value = input.trim_end_matches(['\r', '\n']).to_owned();
+            if let (true, Some(last)) = (value.is_empty(), recent.first()) {
+                value = last.to_owned();
+            }
+            match parameter.map(|p| p.validate_value(&value)) {
+                Some(Err(err)) => writeln!(self.writer, "{err}")?,
+                _ => return Ok(value),
+            }
+        }
+    }
+}
+
+/// The zero based indexes of a list of one based numbers and ranges
+fn parse_selection(input: &str, len: usize) -> Result<Vec<usize>> {
+    let mut selected = vec![];
+    for word in input
+        .split([' ', ','])
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+    {
+        let (start, end) = word.split_once('-').unwrap_or((word, word));
+        let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) else {
+            bail!("'{word}' is not a number or a range")
+        };
+        if start == 0 || start > end || end > len {
+            bail!("'{word}' is not between 1 and {len}")
+        }
+        for idx in start - 1..end {
+            if !selected.contains(&idx) {
+                selected.push(idx);
+            }
+        }
+    }
+
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::ParameterType;
+    use std::io::Cursor;
+
+    #[test]
+    fn should_ask_for_each_parameter_in_order() {
+        let input = Cursor::new("hello\nJohn Doe\n");
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let names = vec!["greeting".to_owned(), "name".to_owned()];
+        let args = prompt.ask(&names, &[], &HashMap::new()).unwrap();
+
+        assert_eq!(args, vec!["--greeting=hello", "--name=John Doe"]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("#{greeting}: "));
+        assert!(output.contains("#{name}: "));
+    }
+
+    #[test]
+    fn should_offer_the_recent_values() {
+        let input = Cursor::new("\nprod\n");
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let names = vec!["env".to_owned(), "region".to_owned()];
+        let recent = HashMap::from([
+            (
+                "env".to_owned(),
+                vec!["staging".to_owned(), "dev".to_owned()],
+            ),
+            ("region".to_owned(), vec!["us".to_owned()]),
+        ]);
+        let args = prompt.ask(&names, &[], &recent).unwrap();
+
+        assert_eq!(args, vec!["--env=staging", "--region=prod"]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Recent values: staging, dev\n#{env} [staging]: "));
+    }
+
+    #[test]
+    fn should_ask_again_when_the_value_is_invalid() {
+        let input = Cursor::new("ten\n10\n");
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let parameters = vec![Parameter {
+            name: "seconds".to_owned(),
+            type_: ParameterType::Int,
+            ..Default::default()
+        }];
+        let args = prompt
+            .ask(&["seconds".to_owned()], &parameters, &HashMap::new())
+            .unwrap();
+
+        assert_eq!(args, vec!["--seconds=10"]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("expected a valid int"));
+    }
+
+    #[test]
+    fn should_confirm_only_when_the_answer_is_yes() {
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(Cursor::new("y\n"), &mut output);
+        assert!(prompt.confirm("Remove?").unwrap());
+
+        let mut prompt = Prompt::new(Cursor::new("\n"), &mut output);
+        assert!(!prompt.confirm("Remove?").unwrap());
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Remove? [y/N] "));
+    }
+
+    #[test]
+    fn should_choose_an_option_by_its_first_letter_or_its_name() {
+        let options = ["skip", "overwrite", "rename"];
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(Cursor::new("x\nR\n\noverwrite\n"), &mut output);
+
+        assert_eq!(prompt.choose("Conflict", &options).unwrap(), 2);
+        assert_eq!(prompt.choose("Conflict", &options).unwrap(), 0);
+        assert_eq!(prompt.choose("Conflict", &options).unwrap(), 1);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Conflict [s]kip/[o]verwrite/[r]ename "));
+        assert!(output.contains("'x' is not one of skip, overwrite, rename"));
+    }
+
+    #[test]
+    fn should_select_the_numbers_and_ranges_of_the_items() {
+        let items = ["a", "b", "c", "d"].map(String::from);
+        let input = Cursor::new("9\n1, 3-4 3\n");
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let selected = prompt.select("Pick", &items).unwrap();
+
+        assert_eq!(selected, vec![0, 2, 3]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("1. a\n2. b\n"));
+        assert!(output.contains("'9' is not between 1 and 4"));
+    }
+
+    #[test]
+    fn should_fail_when_input_ends_before_all_values_are_given() {
+        let input = Cursor::new("hello\n");
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let names = vec!["greeting".to_owned(), "name".to_owned()];
+        let result = prompt.ask(&names, &[], &HashMap::new());
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("#{name}"));
+    }
+}
@@ -0,0 +1,63 @@
+use super::prompt::Prompt;
+use super::Subcommand;
+use anyhow::{bail, Context, Result};
+use cl_core::{initialize_commands, Config};
+use clap::Parser;
+use std::io::{self, IsTerminal};
+use tracing::{debug_span, info};
+
+#[derive(Parser)]
+pub struct Remove {
+    #[clap(required = true, help = "The alias of the command to be removed")]
+    alias: String,
+    #[clap(
+        short,
+        long,
+        help = "The namespace of the command, in case of duplicate aliases"
+    )]
+    namespace: Option<String>,
+    #[clap(
+        short,
+        long,
+        action,
+        help = "Remove the command without asking for confirmation.\n\
+            Required when the stdin is not a terminal"
+    )]
+    yes: bool,
+}
+
+impl Subcommand for Remove {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let _span =
+            debug_span!("remove", alias = %self.alias, namespace = ?self.namespace).entered();
+        let storage = config.storage();
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let mut commands = initialize_commands!(storage);
+
+        let command = commands
+            .find(&self.alias, self.namespace.as_deref())
+            .context("Failed to find the command with the given alias and namespace")?;
+
+        if !self.yes {
+            if !io::stdin().is_terminal() {
+                bail!("Use `--yes` to remove a command when the stdin is not a terminal")
+            }
+            let mut prompt = Prompt::new(io::stdin().lock(), io::stderr());
+            let question = format!(
+                "Remove '{}' from the '{}' namespace ({})?",
+                command.alias, command.namespace, command.command
+            );
+            if !prompt.confirm(&question)? {
+                info!(target: "cl::remove", "nothing removed");
+                return Ok(());
+            }
+        }
+
+        let result = commands.remove(&command)?;
+        storage.save_if_unchanged(result, &fingerprint)?;
+        info!(target: "cl::remove", alias = %command.alias, namespace = %command.namespace, "command removed");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use clap::ValueEnum;
+use std::fmt::Write;
+
+/// What is done with an imported command whose alias already exists in its namespace
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OnConflict {
+    /// Keep the stored command
+    #[default]
+    Skip,
+    /// Replace the stored command by the imported one
+    Overwrite,
+    /// Import the command under a new alias, e.g. `alias-2`
+    Rename,
+    /// Show the differences and ask what to do for each conflict
+    Ask,
+}
+
+/// How a conflict was resolved
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+impl Resolution {
+    /// The options of the question asked for each conflict, in the order of [Resolution::ALL]
+    pub const OPTIONS: [&'static str; 3] = ["skip", "overwrite", "rename"];
+    pub const ALL: [Resolution; 3] = [Resolution::Skip, Resolution::Overwrite, Resolution::Rename];
+}
+
+/// The lines of `old` and `new`, prefixed by `-` when only in `old`, `+` when only in `new`, or
+/// by a space when in both
+pub fn diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // the length of the longest common subsequence of the lines from old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            let _ = writeln!(diff, "  {}", old[i]);
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            let _ = writeln!(diff, "- {}", old[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(diff, "+ {}", new[j]);
+            j += 1;
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_show_the_changed_lines() {
+        let old = "alias = \"co\"\nnamespace = \"git\"\ncommand = \"git checkout\"\n";
+        let new =
+            "alias = \"co\"\nnamespace = \"git\"\ncommand = \"git switch\"\ntags = [\"vcs\"]\n";
+
+        assert_eq!(
+            diff(old, new),
+            "  alias = \"co\"\n  \
+            namespace = \"git\"\n\
+            - command = \"git checkout\"\n\
+            + command = \"git switch\"\n\
+            + tags = [\"vcs\"]\n"
+        );
+    }
+}
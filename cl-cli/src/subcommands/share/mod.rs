@@ -0,0 +1,499 @@
+mod conflict;
+mod script;
+
+use super::prompt::Prompt;
+use super::Subcommand;
+use anyhow::{bail, Context, Result};
+use cl_core::{
+    fs, initialize_commands, Command, CommandMapExt, CommandVecExt, Commands, Config, FileFormat,
+    Fingerprint, Storage, Toml,
+};
+use clap::{Parser, ValueEnum};
+use conflict::{OnConflict, Resolution};
+use script::Shell;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, info_span, warn};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Mode {
+    Export,
+    Import,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The commands file of cl
+    Toml,
+    /// The commands as JSON, e.g. to be read by other tools
+    Json,
+    /// The commands as YAML, e.g. to be read by other tools
+    Yaml,
+    /// A script of aliases and functions. Export only
+    Bash,
+    /// A script of aliases and functions. Export only
+    Zsh,
+    /// A script of aliases and functions. Export only
+    Fish,
+}
+
+/// What a format is written as
+enum Output {
+    Commands(FileFormat),
+    Script(Shell),
+}
+
+impl Format {
+    /// The format of a file, by its extension
+    fn of(path: &Path) -> Option<Format> {
+        if let Some(format) = FileFormat::of(path) {
+            return Some(format.into());
+        }
+        match path.extension()?.to_str()? {
+            "sh" | "bash" => Some(Format::Bash),
+            "zsh" => Some(Format::Zsh),
+            "fish" => Some(Format::Fish),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.output() {
+            Output::Commands(format) => format.extension(),
+            Output::Script(Shell::Bash) => "sh",
+            Output::Script(Shell::Zsh) => "zsh",
+            Output::Script(Shell::Fish) => "fish",
+        }
+    }
+
+    fn output(&self) -> Output {
+        match self {
+            Format::Toml => Output::Commands(FileFormat::Toml),
+            Format::Json => Output::Commands(FileFormat::Json),
+            Format::Yaml => Output::Commands(FileFormat::Yaml),
+            Format::Bash => Output::Script(Shell::Bash),
+            Format::Zsh => Output::Script(Shell::Zsh),
+            Format::Fish => Output::Script(Shell::Fish),
+        }
+    }
+}
+
+impl From<FileFormat> for Format {
+    fn from(format: FileFormat) -> Self {
+        match format {
+            FileFormat::Toml => Format::Toml,
+            FileFormat::Json => Format::Json,
+            FileFormat::Yaml => Format::Yaml,
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct Share {
+    #[clap(value_parser, help = "Export/Import mode")]
+    mode: Mode,
+    #[clap(
+        short,
+        long = "file_location",
+        required = false,
+        help = "If <MODE> is `export`, the location of the output file\n\
+        If `import`, the location of the source file\n\
+        (Default value is `shared.<format extension>` in the current directory)",
+        value_parser
+    )]
+    file_location: Option<PathBuf>,
+    #[clap(
+        long,
+        value_enum,
+        help = "The format of the file. Defaults to the one of its extension, or TOML"
+    )]
+    format: Option<Format>,
+    #[clap(
+        short,
+        long,
+        num_args(1..),
+        help = "The namespace(s) to be imported from/exported to file\nIf none, all aliases will be processed"
+    )]
+    namespace: Option<Vec<String>>,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t,
+        help = "What to do with the imported commands whose alias already exists in their namespace"
+    )]
+    on_conflict: OnConflict,
+    #[clap(
+        long,
+        help = "List the commands that would be added, overwritten or skipped, without importing them"
+    )]
+    dry_run: bool,
+}
+
+impl Subcommand for Share {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let storage = config.storage();
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let mut commands = initialize_commands!(storage);
+
+        match (self.mode, self.format().output()) {
+            (Mode::Import, Output::Script(_)) => {
+                bail!("Shell scripts cannot be imported. Use `cl import aliases` for them")
+            }
+            (Mode::Import, Output::Commands(format)) => {
+                self.handle_import(&mut commands, storage.as_ref(), &fingerprint, format)
+            }
+            (Mode::Export, Output::Script(shell)) => self.handle_script_export(&commands, shell),
+            (Mode::Export, Output::Commands(format)) => self.handle_export(&commands, format),
+        }
+    }
+}
+impl Share {
+    fn format(&self) -> Format {
+        self.format
+            .or_else(|| {
+                self.file_location
+                    .as_ref()
+                    .and_then(|path| Format::of(path))
+            })
+            .unwrap_or(Format::Toml)
+    }
+
+    fn file_location(&self) -> PathBuf {
+        self.file_location
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("shared.{}", self.format().extension())))
+    }
+
+    fn handle_import(
+        &self,
+        commands: &mut Commands,
+        storage: &dyn Storage,
+        fingerprint: &Fingerprint,
+        format: FileFormat,
+    ) -> Result<()> {
+        let _span = info_span!("share::import", file = ?self.file_location(), ?format, on_conflict = ?self.on_conflict, dry_run = self.dry_run).entered();
+        let mut commands_from_file = fs::load_as(self.file_location(), format)?.to_vec().sorted();
+
+        let namespace_filter = self.create_namespace_filter();
+        if !namespace_filter.is_empty() {
+            debug!(target: "cl::share", namespaces = ?namespace_filter, "namespace filter applied");
+        }
+        commands_from_file.retain(|cmd| {
+            namespace_filter.is_empty() || namespace_filter.contains(&cmd.namespace.to_string())
+        });
+
+        let mut prompt = None;
+        let mut summary = Summary::default();
+        for imported in &commands_from_file {
+            // the aliases differing only in case are the same
+            let stored = commands
+                .as_list()
+                .into_iter()
+                .find(|command| command == imported);
+            let Some(stored) = stored else {
+                self.apply(commands, &mut summary, imported, None);
+                continue;
+            };
+            let stored_entry = Toml::from_command(&stored)?;
+            let imported_entry = Toml::from_command(imported)?;
+            if stored_entry == imported_entry {
+                summary.unchanged += 1;
+                continue;
+            }
+
+            let resolution = match self.on_conflict {
+                OnConflict::Skip => Resolution::Skip,
+                OnConflict::Overwrite => Resolution::Overwrite,
+                OnConflict::Rename => Resolution::Rename,
+                OnConflict::Ask => {
+                    println!(
+                        "'{}' already exists in the '{}' namespace:\n{}",
+                        stored.alias,
+                        stored.namespace,
+                        conflict::diff(&stored_entry, &imported_entry)
+                    );
+                    if self.dry_run {
+                        println!("would ask what to do with '{}'", key_of(imported));
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    let prompt = match &mut prompt {
+                        Some(prompt) => prompt,
+                        None => {
+                            if !io::stdin().is_terminal() {
+                                bail!("Use another `--on-conflict` strategy when the stdin is not a terminal")
+                            }
+                            prompt.insert(Prompt::new(io::stdin().lock(), io::stderr()))
+                        }
+                    };
+                    let chosen = prompt.choose(
+                        &format!("What to do with '{}'?", key_of(imported)),
+                        &Resolution::OPTIONS,
+                    )?;
+                    Resolution::ALL[chosen]
+                }
+            };
+            self.apply(
+                commands,
+                &mut summary,
+                imported,
+                Some((&stored, resolution)),
+            );
+        }
+
+        if self.dry_run {
+            println!(
+                "{} commands would be added, {} overwritten and {} skipped ({} unchanged)",
+                summary.added, summary.overwritten, summary.skipped, summary.unchanged
+            );
+            return Ok(());
+        }
+        if summary.added + summary.overwritten == 0 {
+            info!(target: "cl::share", skipped = summary.skipped, unchanged = summary.unchanged, "no aliases to import");
+            return Ok(());
+        }
+
+        storage
+            .save_if_unchanged(commands.as_map(), fingerprint)
+            .context("Could not import the aliases")?;
+        info!(
+            target: "cl::share",
+            added = summary.added,
+            overwritten = summary.overwritten,
+            skipped = summary.skipped,
+            unchanged = summary.unchanged,
+            "aliases imported"
+        );
+
+        Ok(())
+    }
+
+    /// Adds, overwrites, renames or skips an imported command, printing what would be done on dry
+    /// runs
+    fn apply<'cmd>(
+        &self,
+        commands: &mut Commands<'cmd>,
+        summary: &mut Summary,
+        imported: &Command<'cmd>,
+        conflict: Option<(&Command<'cmd>, Resolution)>,
+    ) {
+        let result = match conflict {
+            None => commands.add(imported).map(|_| {
+                self.report(format!("add '{}'", key_of(imported)));
+                summary.added += 1;
+            }),
+            Some((stored, Resolution::Skip)) => {
+                self.report(format!("skip '{}', as it already exists", key_of(stored)));
+                summary.skipped += 1;
+                Ok(())
+            }
+            Some((stored, Resolution::Overwrite)) => {
+                let mut overwriting = imported.clone();
+                // kept in the file of the stored command
+                overwriting.source = stored.source.clone();
+                commands.edit(&overwriting, stored).map(|_| {
+                    self.report(format!("overwrite '{}'", key_of(stored)));
+                    summary.overwritten += 1;
+                })
+            }
+            Some((_, Resolution::Rename)) => {
+                let renamed = renamed(commands, imported);
+                commands.add(&renamed).map(|_| {
+                    self.report(format!(
+                        "add '{}' as '{}'",
+                        key_of(imported),
+                        key_of(&renamed)
+                    ));
+                    summary.added += 1;
+                })
+            }
+        };
+
+        if let Err(err) = result {
+            warn!(target: "cl::share", "skipped '{}': {err}", key_of(imported));
+            summary.skipped += 1;
+        }
+    }
+
+    /// Prints what would be done on dry runs, or logs what is done
+    fn report(&self, action: String) {
+        match self.dry_run {
+            true => println!("{action}"),
+            false => debug!(target: "cl::share", "{action}"),
+        }
+    }
+
+    fn handle_export(&self, commands: &Commands, format: FileFormat) -> Result<()> {
+        let _span = info_span!("share::export", file = ?self.file_location(), ?format).entered();
+        let filtered_commands = self.filter_for_export(commands);
+
+        fs::save_as(
+            &filtered_commands.to_command_map(),
+            self.file_location(),
+            format,
+        )
+        .context("Could not export the aliases")?;
+        info!(target: "cl::share", count = filtered_commands.len(), "aliases exported");
+
+        Ok(())
+    }
+
+    fn handle_script_export(&self, commands: &Commands, shell: Shell) -> Result<()> {
+        let file = self.file_location();
+        let _span = info_span!("share::export", file = ?file, ?shell).entered();
+        let filtered_commands = self.filter_for_export(commands);
+
+        let (script, skipped) = script::to_script(&filtered_commands, commands, shell);
+        for reason in &skipped {
+            warn!(target: "cl::share", "skipped {reason}");
+        }
+        std::fs::write(&file, script)
+            .with_context(|| format!("Could not export the aliases to {}", file.display()))?;
+        info!(target: "cl::share", count = filtered_commands.len() - skipped.len(), "aliases exported");
+
+        Ok(())
+    }
+
+    fn filter_for_export<'cmd>(&self, commands: &Commands<'cmd>) -> Vec<Command<'cmd>> {
+        let namespace_filter = self.create_namespace_filter();
+        if !namespace_filter.is_empty() {
+            debug!(target: "cl::share", namespaces = ?namespace_filter, "namespace filter applied");
+        }
+        commands
+            .as_list()
+            .into_iter()
+            .filter(|cmd| {
+                namespace_filter.is_empty() || namespace_filter.contains(&cmd.namespace.to_string())
+            })
+            .collect()
+    }
+
+    fn create_namespace_filter(&self) -> HashSet<String> {
+        self.namespace
+            .as_ref()
+            .map_or(HashSet::new(), |namespaces| {
+                namespaces.iter().cloned().collect()
+            })
+    }
+
+    /// The alias and namespace of the new commands that are already stored
+    pub(super) fn find_duplicates<'a>(
+        stored_commands: &'a [Command],
+        new_commands: &'a [Command],
+    ) -> Vec<(&'a str, &'a str)> {
+        let existing_keys: HashSet<(&str, &str)> = stored_commands
+            .iter()
+            .map(|cmd| (cmd.alias.as_ref(), cmd.namespace.as_ref()))
+            .collect();
+
+        new_commands
+            .iter()
+            .filter(|cmd| existing_keys.contains(&(cmd.alias.as_ref(), cmd.namespace.as_ref())))
+            .map(|cmd| (cmd.alias.as_ref(), cmd.namespace.as_ref()))
+            .collect()
+    }
+}
+
+/// What an import did, or would do on dry runs
+#[derive(Default)]
+struct Summary {
+    added: usize,
+    overwritten: usize,
+    skipped: usize,
+    /// The imported commands identical to the stored ones
+    unchanged: usize,
+}
+
+/// `namespace.alias`, as the commands are referenced
+fn key_of(command: &Command) -> String {
+    format!("{}.{}", command.namespace, command.alias)
+}
+
+/// The command with the first `<alias>-<n>` alias not taken in its namespace
+fn renamed<'cmd>(commands: &Commands<'cmd>, command: &Command<'cmd>) -> Command<'cmd> {
+    let stored = commands.as_list();
+    (2..)
+        .map(|n| {
+            let mut renamed = command.clone();
+            renamed.alias = format!("{}-{n}", command.alias).into();
+            renamed
+        })
+        .find(|renamed| !stored.contains(renamed))
+        .expect("an alias is always free")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::borrow::Cow;
+
+    fn cmd<'a>(alias: &'a str, namespace: &'a str) -> Command<'a> {
+        Command {
+            alias: Cow::Borrowed(alias),
+            namespace: Cow::Borrowed(namespace),
+            command: Cow::Borrowed("echo test"),
+            description: None,
+            tags: None,
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn should_find_the_format_by_the_extension() {
+        assert_eq!(Format::of(Path::new("shared.yml")), Some(Format::Yaml));
+        assert_eq!(Format::of(Path::new("shared.json")), Some(Format::Json));
+        assert_eq!(Format::of(Path::new("aliases.sh")), Some(Format::Bash));
+        assert_eq!(Format::of(Path::new("shared.txt")), None);
+        assert_eq!(Format::Yaml.extension(), "yaml");
+    }
+
+    #[test]
+    fn should_rename_to_the_first_free_alias_of_the_namespace() {
+        let commands = Commands::init(
+            vec![
+                cmd("foo", "bar"),
+                cmd("FOO-2", "bar"),
+                cmd("foo-3", "other"),
+            ]
+            .to_command_map(),
+        );
+
+        let renamed = renamed(&commands, &cmd("foo", "bar"));
+
+        assert_eq!(renamed.alias, "foo-3");
+        assert_eq!(renamed.namespace, "bar");
+    }
+
+    #[test]
+    fn should_detect_duplicate_by_alias_and_namespace() {
+        let stored = vec![cmd("foo", "bar")];
+        let incoming = vec![cmd("foo", "bar"), cmd("baz", "bar")];
+        let duplicates = Share::find_duplicates(&stored, &incoming);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].0, "foo");
+    }
+
+    #[test]
+    fn should_not_flag_same_alias_in_different_namespace() {
+        let stored = vec![cmd("foo", "bar")];
+        let incoming = vec![cmd("foo", "other")];
+        let duplicates = Share::find_duplicates(&stored, &incoming);
+        assert!(duplicates.is_empty());
+    }
+
+    #[test]
+    fn should_return_empty_when_no_duplicates() {
+        let stored = vec![cmd("foo", "bar")];
+        let incoming = vec![cmd("baz", "bar")];
+        let duplicates = Share::find_duplicates(&stored, &incoming);
+        assert!(duplicates.is_empty());
+    }
+}
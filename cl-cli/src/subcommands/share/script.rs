@@ -0,0 +1,422 @@
+use cl_core::{Command, Commands};
+use itertools::Itertools;
+use regex::{Captures, Regex};
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+static NAMED_PARAM_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#\{([^}]+)\}").expect("Invalid regex pattern"));
+/// The shells that can run the commands as they are written
+const COMPATIBLE_SHELLS: [&str; 4] = ["sh", "bash", "zsh", "fish"];
+
+/// The shells the commands can be exported to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+
+    fn quote(&self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("'{}'", value.replace('\'', r"'\''")),
+            Shell::Fish => format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'")),
+        }
+    }
+
+    /// Quotes a path, leaving a leading `~` to be expanded
+    fn quote_path(&self, path: &str) -> String {
+        match path.strip_prefix("~/") {
+            Some(rest) => format!("~/{}", self.quote(rest)),
+            None if path == "~" => path.to_owned(),
+            None => self.quote(path),
+        }
+    }
+
+    fn variable(&self, name: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("${{{name}}}"),
+            Shell::Fish => format!("${name}"),
+        }
+    }
+
+    /// The arguments of a function, appended to the commands without named parameters
+    fn arguments(&self) -> &'static str {
+        match self {
+            Shell::Bash | Shell::Zsh => "\"$@\"",
+            Shell::Fish => "$argv",
+        }
+    }
+}
+
+/// Writes the commands as aliases and functions, to be sourced where `cl` is not installed
+///
+/// The references to other commands are resolved with `all`, as `cl` is not there to do it
+///
+/// Returns the script and the reasons why some commands could not be exported
+pub fn to_script(commands: &[Command], all: &Commands, shell: Shell) -> (String, Vec<String>) {
+    let mut script = format!(
+        "# Commands exported by `cl share export --format {}`\n\
+        # Load them with `source <file>`\n",
+        shell.name()
+    );
+    let mut skipped = vec![];
+
+    for command in commands.iter().sorted() {
+        let exported = all
+            .expand(command)
+            .map_err(|err| format!("cannot be resolved: {err}"))
+            .and_then(|command| export(&command, shell));
+        match exported {
+            Ok(exported) => {
+                script.push('\n');
+                script.push_str(&exported);
+            }
+            Err(reason) => skipped.push(format!(
+                "'{}' of the '{}' namespace {reason}",
+                command.alias, command.namespace
+            )),
+        }
+    }
+
+    (script, skipped)
+}
+
+/// An alias, when the command has no named parameters and fits in a line, or a function
+fn export(command: &Command, shell: Shell) -> Result<String, String> {
+    if command.is_pipeline() {
+        return Err("is a pipeline".to_owned());
+    }
+    if let Some(program) = command
+        .shell
+        .as_deref()
+        .and_then(|shell| shell.split_whitespace().next())
+    {
+        if !COMPATIBLE_SHELLS.contains(&program) {
+            return Err(format!("runs with '{program}'"));
+        }
+    }
+
+    let name = function_name(command);
+    let parameters = command.named_parameters();
+    let env = command.env.iter().flatten().collect::<Vec<_>>();
+    let mut exported = String::new();
+    if let Some(description) = &command.description {
+        let _ = writeln!(exported, "# {}", description.replace('\n', " "));
+    }
+
+    let is_alias = parameters.is_empty()
+        && !command.command.contains('\n')
+        && command.cwd.is_none()
+        && env.is_empty();
+    if is_alias {
+        let _ = match shell {
+            Shell::Bash | Shell::Zsh => {
+                writeln!(exported, "alias {name}={}", shell.quote(&command.command))
+            }
+            Shell::Fish => writeln!(exported, "alias {name} {}", shell.quote(&command.command)),
+        };
+        return Ok(exported);
+    }
+
+    let body = NAMED_PARAM_REGEX.replace_all(&command.command, |caps: &Captures| {
+        shell.variable(&variable_name(&caps[1]))
+    });
+    let mut body = body.trim_end().to_owned();
+    if parameters.is_empty() {
+        body = format!("{body} {}", shell.arguments());
+    }
+    // the lines of a heredoc cannot be indented
+    let indent = if body.contains("<<") { "" } else { "    " };
+    let body = body
+        .lines()
+        .map(|line| format!("{indent}{line}"))
+        .join("\n");
+
+    match shell {
+        Shell::Bash | Shell::Zsh => posix_function(&mut exported, command, &name, &env, &body),
+        Shell::Fish => fish_function(&mut exported, command, &name, &env, &body),
+    }
+
+    Ok(exported)
+}
+
+/// A bash or zsh function, taking the named parameters as positional arguments
+fn posix_function(
+    exported: &mut String,
+    command: &Command,
+    name: &str,
+    env: &[(&String, &String)],
+    body: &str,
+) {
+    let shell = Shell::Bash;
+    let _ = writeln!(exported, "{name}() {{");
+    for (idx, parameter) in command.named_parameters().iter().enumerate() {
+        let position = idx + 1;
+        let value = match default_of(command, parameter) {
+            Some(default) => format!("${{{position}:-{}}}", escape_double_quoted(default)),
+            None => format!("${{{position}:?missing {parameter}}}"),
+        };
+        let _ = writeln!(
+            exported,
+            "    local {}=\"{value}\"",
+            variable_name(parameter)
+        );
+    }
+    if command.cwd.is_none() && env.is_empty() {
+        let _ = writeln!(exported, "{body}");
+    } else {
+        // a subshell keeps the directory and the variables of the caller
+        let _ = writeln!(exported, "    (");
+        if let Some(cwd) = &command.cwd {
+            let _ = writeln!(exported, "    cd {} || exit", shell.quote_path(cwd));
+        }
+        for (key, value) in env {
+            let _ = writeln!(exported, "    export {key}={}", shell.quote(value));
+        }
+        let _ = writeln!(exported, "{body}");
+        let _ = writeln!(exported, "    )");
+    }
+    let _ = writeln!(exported, "}}");
+}
+
+/// A fish function, taking the named parameters as its argument names
+fn fish_function(
+    exported: &mut String,
+    command: &Command,
+    name: &str,
+    env: &[(&String, &String)],
+    body: &str,
+) {
+    let shell = Shell::Fish;
+    let parameters = command.named_parameters();
+    match parameters.is_empty() {
+        true => {
+            let _ = writeln!(exported, "function {name}");
+        }
+        false => {
+            let names = parameters.iter().map(|p| variable_name(p)).join(" ");
+            let _ = writeln!(exported, "function {name} --argument-names {names}");
+        }
+    }
+    for parameter in &parameters {
+        let variable = variable_name(parameter);
+        let fallback = match default_of(command, parameter) {
+            Some(default) => format!("set {variable} {}", shell.quote(default)),
+            None => format!("echo 'missing {parameter}' >&2; return 1"),
+        };
+        let _ = writeln!(
+            exported,
+            "    test -n \"${variable}\"; or begin; {fallback}; end"
+        );
+    }
+    for (key, value) in env {
+        let _ = writeln!(exported, "    set -lx {key} {}", shell.quote(value));
+    }
+    match &command.cwd {
+        Some(cwd) => {
+            let _ = writeln!(exported, "    pushd {}; or return 1", shell.quote_path(cwd));
+            let _ = writeln!(exported, "{body}");
+            let _ = writeln!(exported, "    set -l exit_status $status");
+            let _ = writeln!(exported, "    popd");
+            let _ = writeln!(exported, "    return $exit_status");
+        }
+        None => {
+            let _ = writeln!(exported, "{body}");
+        }
+    }
+    let _ = writeln!(exported, "end");
+}
+
+fn default_of<'a>(command: &'a Command, parameter: &str) -> Option<&'a String> {
+    command
+        .parameter(parameter)
+        .and_then(|parameter| parameter.default.as_ref())
+}
+
+/// `<namespace>_<alias>`, with only the characters allowed in the names of all the shells
+fn function_name(command: &Command) -> String {
+    format!("{}_{}", command.namespace, command.alias)
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// The name of the shell variable of a named parameter
+fn variable_name(parameter: &str) -> String {
+    let name: String = parameter
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{name}"),
+        false => name,
+    }
+}
+
+fn escape_double_quoted(value: &str) -> String {
+    value
+        .chars()
+        .fold(String::with_capacity(value.len()), |mut escaped, c| {
+            // `}` would end the `${N:-default}` expansion the value is in
+            if matches!(c, '"' | '\\' | '$' | '`' | '}') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::{CommandBuilder, CommandVecExt, Parameter};
+    use std::collections::BTreeMap;
+
+    fn checkout() -> Command<'static> {
+        let mut branch = Parameter::new("branch");
+        branch.default = Some("main".to_owned());
+        CommandBuilder::default()
+            .alias("co")
+            .namespace("git")
+            .command("git checkout #{branch} && echo \"on #{branch} of #{repo}\"")
+            .description(Some("Checks out a branch"))
+            .parameters(Some(vec![branch]))
+            .build()
+    }
+
+    #[test]
+    fn should_export_the_simple_commands_as_aliases() {
+        let command = CommandBuilder::default()
+            .alias("st")
+            .namespace("git")
+            .command("git status --short 'it''s'")
+            .build();
+
+        let (bash, _) = to_script(
+            std::slice::from_ref(&command),
+            &Commands::default(),
+            Shell::Bash,
+        );
+        let (fish, _) = to_script(&[command], &Commands::default(), Shell::Fish);
+
+        assert!(bash.ends_with("\nalias git_st='git status --short '\\''it'\\'''\\''s'\\'''\n"));
+        assert!(fish.ends_with("\nalias git_st 'git status --short \\'it\\'\\'s\\''\n"));
+    }
+
+    #[test]
+    fn should_export_the_named_parameters_as_function_arguments() {
+        let (bash, skipped) = to_script(&[checkout()], &Commands::default(), Shell::Bash);
+        let (fish, _) = to_script(&[checkout()], &Commands::default(), Shell::Fish);
+
+        assert!(skipped.is_empty());
+        assert!(bash.ends_with(
+            "\n# Checks out a branch\n\
+            git_co() {\n    \
+                local branch=\"${1:-main}\"\n    \
+                local repo=\"${2:?missing repo}\"\n    \
+                git checkout ${branch} && echo \"on ${branch} of ${repo}\"\n\
+            }\n"
+        ));
+        assert!(fish.ends_with(
+            "\n# Checks out a branch\n\
+            function git_co --argument-names branch repo\n    \
+                test -n \"$branch\"; or begin; set branch 'main'; end\n    \
+                test -n \"$repo\"; or begin; echo 'missing repo' >&2; return 1; end\n    \
+                git checkout $branch && echo \"on $branch of $repo\"\n\
+            end\n"
+        ));
+    }
+
+    #[test]
+    fn should_run_the_functions_in_their_directory_and_environment() {
+        let command = CommandBuilder::default()
+            .alias("up")
+            .namespace("compose")
+            .command("docker compose up")
+            .cwd(Some("~/my app"))
+            .env(Some(BTreeMap::from([(
+                "COMPOSE_PROFILES".to_owned(),
+                "dev".to_owned(),
+            )])))
+            .build();
+
+        let (bash, _) = to_script(&[command], &Commands::default(), Shell::Zsh);
+
+        assert!(bash.ends_with(
+            "\ncompose_up() {\n    \
+                (\n    \
+                cd ~/'my app' || exit\n    \
+                export COMPOSE_PROFILES='dev'\n    \
+                docker compose up \"$@\"\n    \
+                )\n\
+            }\n"
+        ));
+    }
+
+    #[test]
+    fn should_skip_the_commands_that_cannot_be_exported() {
+        let command = CommandBuilder::default()
+            .alias("now")
+            .namespace("py")
+            .command("import datetime; print(datetime.datetime.now())")
+            .shell(Some("python3"))
+            .build();
+
+        let (_, skipped) = to_script(&[command], &Commands::default(), Shell::Bash);
+
+        assert_eq!(
+            skipped,
+            vec!["'now' of the 'py' namespace runs with 'python3'"]
+        );
+    }
+
+    #[test]
+    fn should_resolve_the_references_to_other_commands() {
+        let mut branch = Parameter::new("branch");
+        branch.default = Some("a}b".to_owned());
+        let push = CommandBuilder::default()
+            .alias("push")
+            .namespace("git")
+            .command("git push origin #{branch}")
+            .parameters(Some(vec![branch]))
+            .build();
+        let ship = CommandBuilder::default()
+            .alias("ship")
+            .namespace("git")
+            .command("@git.push && echo shipped")
+            .build();
+        let broken = CommandBuilder::default()
+            .alias("broken")
+            .namespace("git")
+            .command("@broken")
+            .build();
+        let all = Commands::init(vec![push, ship.clone(), broken.clone()].to_command_map());
+
+        let (bash, skipped) = to_script(&[ship, broken], &all, Shell::Bash);
+
+        assert!(bash.ends_with(
+            "\ngit_ship() {\n    \
+                local branch=\"${1:-a\\}b}\"\n    \
+                git push origin ${branch} && echo shipped\n\
+            }\n"
+        ));
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("'broken' of the 'git' namespace cannot be resolved: "));
+    }
+}
@@ -0,0 +1,115 @@
+use super::Subcommand;
+use anyhow::{Context, Result};
+use cl_core::{initialize_commands, Command, Config};
+use clap::Parser;
+use itertools::Itertools;
+use std::fmt::Write;
+
+#[derive(Parser)]
+pub struct Show {
+    #[clap(required = true, help = "The alias of the command to be shown")]
+    alias: String,
+    #[clap(
+        short,
+        long,
+        help = "The namespace of the command, in case of duplicate aliases"
+    )]
+    namespace: Option<String>,
+}
+
+impl Subcommand for Show {
+    fn run(&self, config: impl Config) -> Result<()> {
+        let commands = initialize_commands!(config.storage());
+        let command = commands
+            .find(&self.alias, self.namespace.as_deref())
+            .context("Failed to find the command with the given alias and namespace")?;
+
+        print!("{}", describe(&command));
+        Ok(())
+    }
+}
+
+/// Lists the fields of the command, skipping the empty ones
+fn describe(command: &Command) -> String {
+    let mut fields = vec![
+        ("alias", command.alias.to_string()),
+        ("namespace", command.namespace.to_string()),
+        ("description", command.description()),
+        ("tags", command.tags_as_string()),
+        (
+            "shell",
+            command.shell.as_deref().unwrap_or_default().to_owned(),
+        ),
+        ("cwd", command.cwd.as_deref().unwrap_or_default().to_owned()),
+        (
+            "env",
+            command
+                .env
+                .iter()
+                .flatten()
+                .map(|(key, value)| format!("{key}={value}"))
+                .join(" "),
+        ),
+        (
+            "parameters",
+            command
+                .parameters
+                .iter()
+                .flatten()
+                .map(|parameter| parameter.summary())
+                .join(", "),
+        ),
+    ];
+    if let Some(source) = &command.source {
+        let read_only = if command.read_only {
+            " (read-only)"
+        } else {
+            ""
+        };
+        fields.push(("file", format!("{}{read_only}", source.display())));
+    }
+
+    let mut description = String::new();
+    for (name, value) in fields.iter().filter(|(_, value)| !value.is_empty()) {
+        let _ = writeln!(description, "{:<12} {value}", format!("{name}:"));
+    }
+    if command.is_pipeline() {
+        let _ = writeln!(description, "steps:");
+        for (idx, step) in command.steps().iter().enumerate() {
+            let _ = writeln!(description, "  {}. {}", idx + 1, step.describe());
+        }
+    } else {
+        let _ = writeln!(description, "command:");
+        for line in command.command.lines() {
+            let _ = writeln!(description, "  {line}");
+        }
+    }
+
+    description
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cl_core::CommandBuilder;
+
+    #[test]
+    fn should_describe_the_command() {
+        let command = CommandBuilder::default()
+            .alias("st")
+            .namespace("git")
+            .command("git status\ngit log")
+            .tags(Some(["vcs", "git"]))
+            .build();
+
+        assert_eq!(
+            describe(&command),
+            "alias:       st\n\
+            namespace:   git\n\
+            tags:        git, vcs\n\
+            command:\n  \
+              git status\n  \
+              git log\n"
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use crate::CommandError;
+use crate::Parameter;
+use crate::Step;
 use anyhow::{ensure, Result};
 use itertools::Itertools;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::sync::LazyLock;
 
 static PARAM_REGEX: LazyLock<Regex> =
@@ -15,12 +19,36 @@ pub struct Command<'cmd> {
     pub alias: Cow<'cmd, str>,
     /// The command's namespace. Is a `required` field and should not have empty spaces in it
     pub namespace: Cow<'cmd, str>,
-    /// The command itself. Is a `required` field and can have multiple lines
+    /// The command itself. Is a `required` field, unless the command has `steps`, and can have multiple lines
+    #[serde(default)]
     pub command: Cow<'cmd, str>,
     /// The command's description. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<Cow<'cmd, str>>,
     /// The command's tags. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<Cow<'cmd, str>>>,
+    /// The command's named parameters declarations. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<Parameter>>,
+    /// The shell or interpreter that runs the command, e.g. `bash`, `python3` or `sh -eu`. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<Cow<'cmd, str>>,
+    /// The directory the command runs from. A leading `~` is expanded to the user's home. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<Cow<'cmd, str>>,
+    /// The environment variables set for the command. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    /// The steps of a pipeline command, run in order instead of `command`. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<Step>>,
+    /// The file the command was loaded from, when many files are layered. Not stored
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+    /// Whether the command comes from a read-only file, so it cannot be edited or removed. Not stored
+    #[serde(skip)]
+    pub read_only: bool,
 }
 
 impl<'cmd> Command<'cmd> {
@@ -51,11 +79,191 @@ impl<'cmd> Command<'cmd> {
             CommandError::NamespaceWithWhitespaces
         );
 
+        for parameter in self.parameters.as_deref().unwrap_or_default() {
+            if let Some(default) = &parameter.default {
+                parameter.validate_value(default)?;
+            }
+        }
+
+        for key in self.env.iter().flat_map(|env| env.keys()) {
+            ensure!(
+                is_valid_env_key(key),
+                CommandError::InvalidEnvVar {
+                    entry: key.to_owned()
+                }
+            );
+        }
+
+        for (idx, step) in self.steps().iter().enumerate() {
+            step.validate(idx + 1)?;
+        }
+
         Ok(())
     }
 
+    /// The working directory of the command, with a leading `~` expanded
+    pub fn working_dir(&self) -> Option<PathBuf> {
+        self.cwd.as_deref().map(expand_home)
+    }
+
+    /// Runs the command from `cwd`, if any, and with `env` over its own variables
+    ///
+    /// The steps of a pipeline are overridden as well, as their own values would win otherwise
+    pub fn override_context(&mut self, cwd: Option<&str>, env: &[(String, String)]) {
+        if let Some(cwd) = cwd {
+            self.cwd = Some(Cow::Owned(cwd.to_owned()));
+        }
+        if !env.is_empty() {
+            self.env.get_or_insert_default().extend(env.iter().cloned());
+        }
+
+        for step in self.steps.iter_mut().flatten() {
+            if let Some(cwd) = cwd {
+                step.cwd = Some(cwd.to_owned());
+            }
+            if !env.is_empty() {
+                step.env.get_or_insert_default().extend(env.iter().cloned());
+            }
+        }
+    }
+
+    /// Checks if the command is a pipeline, i.e. runs its `steps` instead of `command`
+    pub fn is_pipeline(&self) -> bool {
+        !self.steps().is_empty()
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        self.steps.as_deref().unwrap_or_default()
+    }
+
+    /// The text of the command: the command itself or, for pipelines, one step per line
+    ///
+    /// Steps referencing other commands are shown as `@namespace.alias`
+    pub fn script(&self) -> Cow<'_, str> {
+        if self.is_pipeline() {
+            Cow::Owned(self.steps().iter().map(Step::describe).join("\n"))
+        } else {
+            Cow::Borrowed(&self.command)
+        }
+    }
+
     pub fn has_named_parameter(&self) -> bool {
-        PARAM_REGEX.is_match(&self.command)
+        PARAM_REGEX.is_match(&self.script())
+    }
+
+    /// Returns the `#{name}` placeholders of the command, in order of appearance and without duplicates
+    pub fn named_parameters(&self) -> Vec<String> {
+        PARAM_REGEX
+            .find_iter(&self.script())
+            .map(|m| m.as_str().trim_start_matches("#{").trim_end_matches('}'))
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .unique()
+            .collect()
+    }
+
+    /// Returns the declaration of the named parameter `name`, if any
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// Returns a copy of the command with its named parameters replaced and `args` appended
+    ///
+    /// The named parameters are shared by all the steps of a pipeline, which accept no extra `args`
+    ///
+    /// ## Arguments
+    /// * `values` - The named parameters values. Missing or empty ones fall back to the declared default
+    /// * `args` - Extra arguments appended to the command
+    pub fn with_arguments(
+        &self,
+        values: &HashMap<String, String>,
+        args: &str,
+    ) -> Result<Command<'static>> {
+        let mut resolved = HashMap::new();
+        let mut missing = Vec::new();
+
+        for name in self.named_parameters() {
+            let parameter = self.parameter(&name);
+            let value = values
+                .get(&name)
+                .filter(|value| !value.is_empty())
+                .or_else(|| parameter.and_then(|p| p.default.as_ref()));
+
+            match value {
+                Some(value) => {
+                    if let Some(parameter) = parameter {
+                        parameter.validate_value(value)?;
+                    }
+                    resolved.insert(name, value.to_owned());
+                }
+                None => missing.push(format!("#{{{name}}}")),
+            }
+        }
+
+        ensure!(
+            missing.is_empty(),
+            CommandError::MissingNamedParameters {
+                names: missing.join(", ")
+            }
+        );
+
+        ensure!(
+            !self.is_pipeline() || args.trim().is_empty(),
+            CommandError::PipelineArguments {
+                args: args.trim().to_owned()
+            }
+        );
+
+        let replace = |text: &str| {
+            PARAM_REGEX
+                .replace_all(text, |caps: &regex::Captures| {
+                    let name = caps[0].trim_start_matches("#{").trim_end_matches('}');
+                    resolved
+                        .get(name)
+                        .cloned()
+                        .unwrap_or_else(|| caps[0].to_owned())
+                })
+                .into_owned()
+        };
+
+        let command = replace(&self.command);
+        let command = match args.trim() {
+            "" => command,
+            args => format!("{} {args}", command.trim_end()),
+        };
+
+        let mut resolved_command = self.to_static();
+        resolved_command.command = Cow::Owned(command);
+        for step in resolved_command.steps.iter_mut().flatten() {
+            step.run = step.run.as_deref().map(replace);
+        }
+
+        Ok(resolved_command)
+    }
+
+    /// Returns a copy of the command that owns all its fields
+    pub fn to_static(&self) -> Command<'static> {
+        Command {
+            alias: Cow::Owned(self.alias.to_string()),
+            namespace: Cow::Owned(self.namespace.to_string()),
+            command: Cow::Owned(self.command.to_string()),
+            description: self.description.as_ref().map(|d| Cow::Owned(d.to_string())),
+            tags: self
+                .tags
+                .as_ref()
+                .map(|tags| tags.iter().map(|t| Cow::Owned(t.to_string())).collect()),
+            parameters: self.parameters.clone(),
+            shell: self.shell.as_ref().map(|s| Cow::Owned(s.to_string())),
+            cwd: self.cwd.as_ref().map(|c| Cow::Owned(c.to_string())),
+            env: self.env.clone(),
+            steps: self.steps.clone(),
+            source: self.source.clone(),
+            read_only: self.read_only,
+        }
     }
 
     pub fn has_changes(&self, new: &Command) -> bool {
@@ -64,12 +272,41 @@ impl<'cmd> Command<'cmd> {
             || new.description != self.description
             || new.tags != self.tags
             || new.namespace != self.namespace
+            || new.parameters != self.parameters
+            || new.shell != self.shell
+            || new.cwd != self.cwd
+            || new.env != self.env
+            || new.steps != self.steps
     }
 
     fn is_incomplete(&self) -> bool {
         self.namespace.trim().is_empty()
             || self.alias.trim().is_empty()
-            || self.command.trim().is_empty()
+            || (self.command.trim().is_empty() && !self.is_pipeline())
+    }
+}
+
+/// Parses a `KEY=VALUE` environment variable definition
+pub fn parse_env_var(entry: &str) -> Result<(String, String)> {
+    let invalid = || CommandError::InvalidEnvVar {
+        entry: entry.to_owned(),
+    };
+    let (key, value) = entry.trim().split_once('=').ok_or_else(invalid)?;
+    ensure!(is_valid_env_key(key), invalid());
+
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+fn is_valid_env_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(|c: char| c == '=' || c.is_whitespace())
+}
+
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
     }
 }
 
@@ -89,6 +326,13 @@ impl Default for Command<'_> {
                 Cow::Borrowed("comma"),
                 Cow::Borrowed("separated"),
             ]),
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
         }
     }
 }
@@ -217,6 +461,171 @@ mod test {
         assert!(command.has_named_parameter())
     }
 
+    #[test]
+    fn should_list_named_parameters_in_order_without_duplicates() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("echo #{greeting} #{name} #{greeting} #{}");
+
+        assert_eq!(command.named_parameters(), vec!["greeting", "name"])
+    }
+
+    #[test]
+    fn should_find_a_parameter_declaration() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("echo #{name}");
+        command.parameters = Some(vec![Parameter::new("name")]);
+
+        assert!(command.parameter("name").is_some());
+        assert!(command.parameter("other").is_none());
+    }
+
+    #[test]
+    fn should_not_validate_a_command_with_an_invalid_parameter_default() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("sleep #{seconds}");
+        command.parameters = Some(vec![Parameter {
+            name: "seconds".to_owned(),
+            default: Some("ten".to_owned()),
+            type_: crate::ParameterType::Int,
+            ..Default::default()
+        }]);
+
+        assert!(command.validate().is_err());
+    }
+
+    #[test]
+    fn should_replace_named_parameters_and_append_args() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("echo #{greeting}, #{name}");
+        command.parameters = Some(vec![Parameter {
+            name: "greeting".to_owned(),
+            default: Some("hello".to_owned()),
+            ..Default::default()
+        }]);
+        let values = HashMap::from([("name".to_owned(), "John".to_owned())]);
+
+        let resolved = command.with_arguments(&values, "--verbose").unwrap();
+
+        assert_eq!(resolved.command, "echo hello, John --verbose");
+        assert_eq!(resolved.alias, command.alias);
+    }
+
+    #[test]
+    fn should_not_replace_named_parameters_when_values_are_missing() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("echo #{greeting}, #{name}");
+        let values = HashMap::from([("name".to_owned(), "".to_owned())]);
+
+        let result = command.with_arguments(&values, "");
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            CommandError::MissingNamedParameters {
+                names: "#{greeting}, #{name}".to_owned()
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn should_not_replace_named_parameters_with_invalid_values() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("sleep #{seconds}");
+        command.parameters = Some(vec![Parameter {
+            name: "seconds".to_owned(),
+            type_: crate::ParameterType::Int,
+            ..Default::default()
+        }]);
+        let values = HashMap::from([("seconds".to_owned(), "ten".to_owned())]);
+
+        assert!(command.with_arguments(&values, "").is_err());
+    }
+
+    #[test]
+    fn should_share_named_parameters_across_the_steps_of_a_pipeline() {
+        let mut command = build_default_command();
+        command.command = Cow::Borrowed("");
+        command.steps = Some(vec![
+            Step::run("git checkout #{branch}"),
+            Step::reference("build", None),
+            Step::run("git push origin #{branch}"),
+        ]);
+        let values = HashMap::from([("branch".to_owned(), "main".to_owned())]);
+
+        assert!(command.validate().is_ok());
+        assert_eq!(command.named_parameters(), vec!["branch"]);
+        assert_eq!(
+            command.script(),
+            "git checkout #{branch}\n@build\ngit push origin #{branch}"
+        );
+
+        let resolved = command.with_arguments(&values, "").unwrap();
+        assert_eq!(
+            resolved.script(),
+            "git checkout main\n@build\ngit push origin main"
+        );
+
+        assert_eq!(
+            command
+                .with_arguments(&values, "--force")
+                .unwrap_err()
+                .to_string(),
+            CommandError::PipelineArguments {
+                args: "--force".to_owned()
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn should_deserialize_a_pipeline_from_toml() {
+        let toml = r#"
+            alias = "ci"
+            namespace = "rust"
+
+            [[steps]]
+            run = "cargo fmt --check"
+            on_failure = "continue"
+
+            [[steps]]
+            alias = "test"
+            namespace = "rust"
+        "#;
+
+        let command: Command = toml::from_str(toml).expect("should deserialize");
+
+        assert!(command.is_pipeline());
+        assert!(command.validate().is_ok());
+        assert_eq!(
+            command.steps()[0].on_failure,
+            crate::FailurePolicy::Continue
+        );
+        assert!(command.steps()[1].is_reference());
+    }
+
+    #[test]
+    fn should_deserialize_parameters_from_toml() {
+        let toml = r#"
+            alias = "greet"
+            namespace = "ns"
+            command = "echo #{greeting}"
+
+            [[parameters]]
+            name = "greeting"
+            description = "how to greet"
+            default = "hello"
+            choices = ["hello", "hi"]
+        "#;
+
+        let command: Command = toml::from_str(toml).expect("should deserialize");
+        let parameter = command.parameter("greeting").expect("should be declared");
+
+        assert_eq!(parameter.default.as_deref(), Some("hello"));
+        assert_eq!(parameter.type_, crate::ParameterType::String);
+        assert_eq!(parameter.choices.as_ref().map(Vec::len), Some(2));
+    }
+
     #[test]
     fn equal_commands_have_same_hash() {
         use std::collections::hash_map::DefaultHasher;
@@ -316,4 +725,56 @@ mod test {
         assert_eq!(a.cmp(&c), Ordering::Less); // ns a < b
         assert_eq!(c.cmp(&b), Ordering::Greater); // ns b > a
     }
+
+    #[test]
+    fn should_parse_env_vars() {
+        assert_eq!(
+            parse_env_var("FOO=bar=baz").unwrap(),
+            ("FOO".to_owned(), "bar=baz".to_owned())
+        );
+        assert_eq!(
+            parse_env_var("EMPTY=").unwrap(),
+            ("EMPTY".to_owned(), String::new())
+        );
+
+        for invalid in ["FOO", "=bar", "MY VAR=1"] {
+            assert_eq!(
+                parse_env_var(invalid).unwrap_err().to_string(),
+                CommandError::InvalidEnvVar {
+                    entry: invalid.to_owned()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_env_var_names() {
+        let command = CommandBuilder::default()
+            .alias("alias")
+            .namespace("namespace")
+            .command("command")
+            .env(Some(BTreeMap::from([(
+                "MY VAR".to_owned(),
+                "1".to_owned(),
+            )])))
+            .build();
+
+        assert!(command.validate().is_err());
+    }
+
+    #[test]
+    fn should_expand_the_home_in_the_working_dir() {
+        let home = dirs::home_dir().unwrap();
+        let command = CommandBuilder::default()
+            .alias("alias")
+            .namespace("namespace")
+            .command("command")
+            .cwd(Some("~/projects"))
+            .build();
+
+        assert_eq!(command.working_dir(), Some(home.join("projects")));
+        assert_eq!(expand_home("/tmp"), PathBuf::from("/tmp"));
+        assert_eq!(expand_home("~user/tmp"), PathBuf::from("~user/tmp"));
+    }
 }
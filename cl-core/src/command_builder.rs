@@ -1,5 +1,6 @@
-use crate::Command;
+use crate::{Command, Parameter, Step};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 #[derive(Default)]
 pub struct CommandBuilder {
@@ -8,6 +9,11 @@ pub struct CommandBuilder {
     description: Option<String>,
     alias: String,
     tags: Option<Vec<String>>,
+    parameters: Option<Vec<Parameter>>,
+    shell: Option<String>,
+    cwd: Option<String>,
+    env: Option<BTreeMap<String, String>>,
+    steps: Option<Vec<Step>>,
 }
 
 impl CommandBuilder {
@@ -53,6 +59,37 @@ impl CommandBuilder {
         self
     }
 
+    pub fn parameters(mut self, parameters: Option<Vec<Parameter>>) -> CommandBuilder {
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn shell<T>(mut self, shell: Option<T>) -> CommandBuilder
+    where
+        T: Into<String>,
+    {
+        self.shell = shell.map(|s| s.into());
+        self
+    }
+
+    pub fn cwd<T>(mut self, cwd: Option<T>) -> CommandBuilder
+    where
+        T: Into<String>,
+    {
+        self.cwd = cwd.map(|c| c.into()).filter(|c| !c.trim().is_empty());
+        self
+    }
+
+    pub fn env(mut self, env: Option<BTreeMap<String, String>>) -> CommandBuilder {
+        self.env = env.filter(|env| !env.is_empty());
+        self
+    }
+
+    pub fn steps(mut self, steps: Option<Vec<Step>>) -> CommandBuilder {
+        self.steps = steps.filter(|steps| !steps.is_empty());
+        self
+    }
+
     pub fn build(self) -> Command<'static> {
         Command {
             namespace: Cow::Owned(self.namespace),
@@ -62,6 +99,13 @@ impl CommandBuilder {
             tags: self
                 .tags
                 .map(|vec| vec.into_iter().map(Cow::Owned).collect()),
+            parameters: self.parameters,
+            shell: self.shell.map(Cow::Owned),
+            cwd: self.cwd.map(Cow::Owned),
+            env: self.env,
+            steps: self.steps,
+            source: None,
+            read_only: false,
         }
     }
 }
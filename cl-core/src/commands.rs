@@ -4,11 +4,33 @@ use crate::CommandMap;
 use crate::CommandMapExt;
 use crate::CommandVec;
 use crate::CommandVecExt;
-
-use anyhow::{bail, Context, Result};
-use std::{borrow::Borrow, env};
+use crate::{CommandBuilder, FailurePolicy, Parameter, Step};
+use crate::{History, HistoryEntry};
+
+use anyhow::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::process::ExitStatus;
+use std::sync::LazyLock;
+use std::time::{Instant, SystemTime};
+use std::{
+    borrow::{Borrow, Cow},
+    env,
+};
 use tracing::{debug, trace, warn};
 
+/// A reference to another command, i.e. `@namespace.alias` or `@alias`, at the start of a line or
+/// after a whitespace or a shell operator
+///
+/// Only the matches ending a word and naming an existing command are references, see
+/// [Commands::expand]
+static REFERENCE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)(?:^|[\s;&|(])(?P<reference>@(?P<first>[\w-]+)(?:\.(?P<second>[\w-]+))?)")
+        .expect("Invalid regex pattern")
+});
+
 #[derive(Default)]
 pub struct Commands<'cmd> {
     commands: CommandMap<'cmd>,
@@ -43,6 +65,7 @@ impl<'cmd> Commands<'cmd> {
             new_alias = %new_command.alias, new_namespace = %new_command.namespace,
             "editing command"
         );
+        self.ensure_writable(old_command)?;
         new_command.validate()?;
         self.command_already_exists(new_command, old_command)?;
         let old_command_namespace: &str = old_command.namespace.borrow();
@@ -71,6 +94,7 @@ impl<'cmd> Commands<'cmd> {
     pub fn remove(&mut self, command: &Command) -> Result<&CommandMap<'cmd>> {
         let namespace: &str = command.namespace.borrow();
         debug!(target: "cl_core::commands", alias = %command.alias, namespace = %namespace, "removing command");
+        self.ensure_writable(command)?;
 
         if let Some(commands) = self.commands.get_mut(namespace) {
             commands.retain(|c| !c.eq(command));
@@ -86,6 +110,69 @@ impl<'cmd> Commands<'cmd> {
         Ok(&self.commands)
     }
 
+    /// Copies a read-only command to the personal commands, where it can be edited and removed
+    ///
+    /// The copy replaces the read-only command, which is kept untouched in its file
+    pub fn fork(&mut self, command: &Command<'cmd>) -> Result<&CommandMap<'cmd>> {
+        let namespace: &str = command.namespace.borrow();
+        debug!(target: "cl_core::commands", alias = %command.alias, namespace = %namespace, "forking command");
+
+        let Some(stored) = self
+            .commands
+            .get_mut(namespace)
+            .and_then(|commands| commands.iter_mut().find(|c| *c == command))
+        else {
+            bail!(CommandError::AliasNotFound {
+                alias: command.alias.to_string()
+            })
+        };
+
+        if stored.read_only {
+            stored.read_only = false;
+            stored.source = None;
+            debug!(target: "cl_core::commands", alias = %command.alias, namespace = %namespace, "command forked");
+        }
+
+        Ok(&self.commands)
+    }
+
+    /// Replaces the commands with `theirs`, keeping the changes made since `base`
+    ///
+    /// Each command added, edited or removed since `base` is applied over `theirs`, e.g. the commands
+    /// saved by another process in the meantime. When both sides changed the same command, the
+    /// current change wins
+    pub fn merge(
+        &mut self,
+        base: &CommandMap<'cmd>,
+        theirs: CommandMap<'cmd>,
+    ) -> &CommandMap<'cmd> {
+        let base: HashMap<&Command, &Command> = base.values().flatten().map(|c| (c, c)).collect();
+        let ours: HashMap<&Command, &Command> =
+            self.commands.values().flatten().map(|c| (c, c)).collect();
+
+        let mut merged = theirs.to_vec();
+        for key in base.keys().chain(ours.keys()).unique() {
+            let unchanged = match (base.get(key), ours.get(key)) {
+                (Some(base), Some(ours)) => same_definition(base, ours),
+                (None, None) => true,
+                _ => false,
+            };
+            if unchanged {
+                continue;
+            }
+
+            trace!(target: "cl_core::commands", alias = %key.alias, namespace = %key.namespace, "merging command");
+            merged.retain(|command| command != *key);
+            if let Some(ours) = ours.get(key) {
+                merged.push((*ours).clone());
+            }
+        }
+
+        debug!(target: "cl_core::commands", "commands merged");
+        self.commands = merged.to_command_map();
+        &self.commands
+    }
+
     pub fn find(&self, alias: &str, namespace: Option<&str>) -> Result<Command<'cmd>> {
         debug!(target: "cl_core::commands", alias = %alias, namespace = ?namespace, "finding command");
 
@@ -127,15 +214,195 @@ impl<'cmd> Commands<'cmd> {
         }
     }
 
-    fn check_same_alias(&self, new_command: &Command<'cmd>) -> bool {
-        let namespace: &str = new_command.namespace.borrow();
+    /// Resolves the references to other commands, i.e. `@namespace.alias` or `@alias`
+    ///
+    /// References inside a command line are replaced by the command of the referenced alias. An
+    /// `@word` that does not end a word or names no command is kept as is, e.g.
+    /// `curl -d @body.json` or `npm i -g @angular/cli`. Commands setting their own shell, working
+    /// directory or environment can only be referenced by steps
+    ///
+    /// A referenced step of a pipeline runs the command of the referenced alias, with its shell,
+    /// working directory and environment unless the step overrides them. The parameters
+    /// declarations of the referenced commands are shared with the referencing one
+    pub fn expand(&self, command: &Command) -> Result<Command<'static>> {
+        self.expand_within(command, &mut Vec::new())
+    }
 
-        if let Some(commands) = self.commands.get(namespace) {
-            commands
-                .iter()
-                .any(|command| command.alias.eq(&new_command.alias))
+    /// Expands `command`, where `visiting` is the chain of commands being expanded, used to detect cycles
+    fn expand_within(
+        &self,
+        command: &Command,
+        visiting: &mut Vec<String>,
+    ) -> Result<Command<'static>> {
+        let identity = format!("{}.{}", command.namespace, command.alias);
+        if visiting
+            .iter()
+            .any(|visited| visited.eq_ignore_ascii_case(&identity))
+        {
+            bail!(CommandError::CyclicReference {
+                cycle: visiting.iter().chain([&identity]).join(" -> ")
+            })
+        }
+        visiting.push(identity);
+
+        let mut expanded = command.to_static();
+        let mut parameters = command.parameters.clone().unwrap_or_default();
+
+        if command.is_pipeline() {
+            let mut steps = Vec::with_capacity(command.steps().len());
+
+            for step in command.steps() {
+                let Some(alias) = step.alias.as_deref().filter(|_| step.is_reference()) else {
+                    let run = step.run.as_deref().unwrap_or_default();
+                    steps.push(Step {
+                        run: Some(self.resolve_references(run, visiting, &mut parameters)?),
+                        ..step.clone()
+                    });
+                    continue;
+                };
+
+                let referenced = self.find_reference(alias, step.namespace.as_deref())?;
+                ensure!(
+                    !referenced.is_pipeline(),
+                    CommandError::NestedPipeline {
+                        reference: step.describe()
+                    }
+                );
+                trace!(target: "cl_core::commands", step = %step.describe(), namespace = %referenced.namespace, "expanding step");
+                let referenced = self.expand_within(&referenced, visiting)?;
+                merge_parameters(&mut parameters, &referenced);
+
+                let mut env = referenced.env.clone().unwrap_or_default();
+                env.extend(step.env.clone().unwrap_or_default());
+
+                steps.push(Step {
+                    run: Some(referenced.command.to_string()),
+                    alias: None,
+                    namespace: None,
+                    shell: step
+                        .shell
+                        .clone()
+                        .or_else(|| referenced.shell.as_deref().map(String::from)),
+                    cwd: step
+                        .cwd
+                        .clone()
+                        .or_else(|| referenced.cwd.as_deref().map(String::from)),
+                    env: Some(env).filter(|env| !env.is_empty()),
+                    ..step.clone()
+                });
+            }
+
+            expanded.steps = Some(steps);
         } else {
-            false
+            let resolved = self.resolve_references(&command.command, visiting, &mut parameters)?;
+            expanded.command = Cow::Owned(resolved);
+        }
+
+        expanded.parameters = Some(parameters).filter(|p| !p.is_empty());
+        visiting.pop();
+
+        Ok(expanded)
+    }
+
+    /// Replaces the references in `text` by the (expanded) command of the referenced aliases
+    fn resolve_references(
+        &self,
+        text: &str,
+        visiting: &mut Vec<String>,
+        parameters: &mut Vec<Parameter>,
+    ) -> Result<String> {
+        let mut resolved = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for caps in REFERENCE_REGEX.captures_iter(text) {
+            let reference = caps.name("reference").expect("reference group must exist");
+            let (namespace, alias) = match (caps.name("first"), caps.name("second")) {
+                (Some(namespace), Some(alias)) => (Some(namespace.as_str()), alias.as_str()),
+                (Some(alias), None) => (None, alias.as_str()),
+                _ => continue,
+            };
+            let ends_word = text[reference.end()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || ";&|)".contains(c));
+            if !ends_word {
+                continue;
+            }
+
+            let referenced = match self.find_reference(alias, namespace) {
+                Ok(referenced) => referenced,
+                Err(err) if is_reference_not_found(&err) => {
+                    trace!(target: "cl_core::commands", text = %reference.as_str(), "not a reference, keeping it as is");
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            ensure!(
+                !referenced.is_pipeline(),
+                CommandError::NestedPipeline {
+                    reference: reference.as_str().to_owned()
+                }
+            );
+            // pasted in a command line, the referenced command would lose them
+            ensure!(
+                referenced.shell.is_none()
+                    && referenced.cwd.is_none()
+                    && referenced.env.as_ref().is_none_or(|env| env.is_empty()),
+                CommandError::ReferenceWithContext {
+                    reference: reference.as_str().to_owned()
+                }
+            );
+            trace!(target: "cl_core::commands", reference = %reference.as_str(), "expanding reference");
+            let referenced = self.expand_within(&referenced, visiting)?;
+            merge_parameters(parameters, &referenced);
+
+            resolved.push_str(&text[last..reference.start()]);
+            resolved.push_str(referenced.command.trim_end());
+            last = reference.end();
+        }
+        resolved.push_str(&text[last..]);
+
+        Ok(resolved)
+    }
+
+    /// Finds the command referenced by a step or by an `@` reference
+    fn find_reference(&self, alias: &str, namespace: Option<&str>) -> Result<Command<'cmd>> {
+        self.find(alias, namespace)
+            .map_err(|err| match err.downcast_ref::<CommandError>() {
+                Some(CommandError::AliasNotFound { .. }) => CommandError::ReferenceNotFound {
+                    reference: match namespace {
+                        Some(namespace) => format!("@{namespace}.{alias}"),
+                        None => format!("@{alias}"),
+                    },
+                }
+                .into(),
+                _ => err,
+            })
+    }
+
+    fn check_same_alias(&self, new_command: &Command<'cmd>) -> bool {
+        // aliases and namespaces are case insensitive, as in `Command`'s `PartialEq`
+        self.commands
+            .values()
+            .flatten()
+            .any(|command| command == new_command)
+    }
+
+    /// Fails if the stored `command` is read-only
+    fn ensure_writable(&self, command: &Command) -> Result<()> {
+        let namespace: &str = command.namespace.borrow();
+        let stored = self
+            .commands
+            .get(namespace)
+            .and_then(|commands| commands.iter().find(|c| *c == command));
+
+        match stored {
+            Some(stored) if stored.read_only => bail!(CommandError::ReadOnlyCommand {
+                alias: stored.alias.to_string(),
+                namespace: stored.namespace.to_string(),
+                path: stored.source.clone().unwrap_or_default(),
+            }),
+            _ => Ok(()),
         }
     }
 
@@ -186,8 +453,90 @@ impl<'cmd> Commands<'cmd> {
     }
 }
 
+/// Whether all the fields are the same, as commands are equal when their aliases and namespaces are
+fn same_definition(a: &Command, b: &Command) -> bool {
+    toml::Value::try_from(a).ok() == toml::Value::try_from(b).ok()
+}
+
+/// Adds the parameters declarations of `referenced` that are not declared in `parameters` yet
+fn merge_parameters(parameters: &mut Vec<Parameter>, referenced: &Command) {
+    for parameter in referenced.parameters.iter().flatten() {
+        if !parameters.iter().any(|p| p.name == parameter.name) {
+            parameters.push(parameter.clone());
+        }
+    }
+}
+
+/// How an executed command finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecStatus {
+    /// The command was only printed, not executed
+    DryRun,
+    /// The command exited with the given code
+    Exited(i32),
+    /// The command was terminated by the given signal
+    Signaled(i32),
+}
+
+impl ExecStatus {
+    pub fn success(&self) -> bool {
+        matches!(self, ExecStatus::DryRun | ExecStatus::Exited(0))
+    }
+
+    /// The exit code `cl` should report for this status
+    ///
+    /// Follows the shell convention of `128 + signal` for terminated commands
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ExecStatus::DryRun => 0,
+            ExecStatus::Exited(code) => *code,
+            ExecStatus::Signaled(signal) => 128 + signal,
+        }
+    }
+}
+
+impl From<ExitStatus> for ExecStatus {
+    fn from(status: ExitStatus) -> Self {
+        match status.code() {
+            Some(code) => ExecStatus::Exited(code),
+            None => signal_of(&status)
+                .map(ExecStatus::Signaled)
+                .unwrap_or(ExecStatus::Exited(1)),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn signal_of(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn signal_of(_: &ExitStatus) -> Option<i32> {
+    None
+}
+
+impl Display for ExecStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecStatus::DryRun => write!(f, "not executed (dry run)"),
+            ExecStatus::Exited(code) => write!(f, "exited with code {code}"),
+            ExecStatus::Signaled(signal) => write!(f, "terminated by signal {signal}"),
+        }
+    }
+}
+
 pub trait CommandExec {
-    fn exec(&self, dry_run: bool, quiet_mode: bool) -> Result<()>;
+    fn exec(
+        &self,
+        dry_run: bool,
+        quiet_mode: bool,
+        shell: Option<&str>,
+        history: Option<&History>,
+    ) -> Result<ExecStatus>;
+
+    fn invocation(&self, shell: Option<&str>) -> Vec<String>;
 
     fn truncate_command(&self) -> String;
 }
@@ -195,16 +544,51 @@ pub trait CommandExec {
 impl CommandExec for Command<'_> {
     /// Executes a command
     ///
-    /// If no `$SHELL` is set, defaults to `sh`
+    /// The command runs with its own shell, then `shell`, then `$SHELL`, defaulting to `sh`
     ///
     /// ## Arguments
     /// * `command_item` - The command entity itself
-    /// * `dry_run` - A boolean flag representing if the command should be actually executed or just printed in the `stdout`
+    /// * `dry_run` - A boolean flag representing if the command should be actually executed or just its invocation printed in the `stdout`
     /// * `quiet_mode` - A boolean flag representing if the command string should be shown before the command output
-    fn exec(&self, dry_run: bool, quiet_mode: bool) -> Result<()> {
+    /// * `shell` - The preferred shell, used when the command does not set one
+    /// * `history` - Where the execution should be recorded, if any. Dry runs are never recorded
+    ///
+    /// Returns how the command finished. A non-zero exit is not an error
+    fn exec(
+        &self,
+        dry_run: bool,
+        quiet_mode: bool,
+        shell: Option<&str>,
+        history: Option<&History>,
+    ) -> Result<ExecStatus> {
+        if self.is_pipeline() {
+            return exec_pipeline(self, dry_run, quiet_mode, shell, history);
+        }
+
+        let invocation = self.invocation(shell);
+
+        let working_dir = self.working_dir();
+        let env = self.env.clone().unwrap_or_default();
+
         if dry_run {
-            println!("{}", self.command);
-            return Ok(());
+            let cd = working_dir
+                .as_ref()
+                .map(|dir| format!("cd {} && ", quote(&dir.to_string_lossy())))
+                .unwrap_or_default();
+            let env = env
+                .iter()
+                .map(|(key, value)| format!("{key}={} ", quote(value)))
+                .join("");
+            let invocation = invocation.iter().map(|arg| quote(arg)).join(" ");
+            println!("{cd}{env}{invocation}");
+            return Ok(ExecStatus::DryRun);
+        }
+
+        if let Some(dir) = &working_dir {
+            ensure!(
+                dir.is_dir(),
+                CommandError::WorkingDirectoryNotFound { path: dir.clone() }
+            );
         }
 
         if !quiet_mode {
@@ -215,14 +599,19 @@ impl CommandExec for Command<'_> {
             );
         }
 
-        let shell = env::var("SHELL").unwrap_or_else(|_| {
-            warn!("$SHELL not found! Using sh");
-            String::from("sh")
-        });
-
-        std::process::Command::new(shell)
-            .arg("-c")
-            .arg(self.command.borrow() as &str)
+        let (program, args) = invocation
+            .split_first()
+            .context("The command has no shell to run with")?;
+        trace!(target: "cl_core::commands", program, "running the command");
+
+        let started_at = SystemTime::now();
+        let start = Instant::now();
+        let mut process = std::process::Command::new(program);
+        process.args(args).envs(&env);
+        if let Some(dir) = &working_dir {
+            process.current_dir(dir);
+        }
+        let status = process
             .spawn()?
             .wait()
             .context("The command did not run")
@@ -231,7 +620,43 @@ impl CommandExec for Command<'_> {
                 cause: err.to_string(),
             })?;
 
-        Ok(())
+        if let Some(history) = history {
+            let entry = HistoryEntry::new(self, started_at, start.elapsed(), status.code())
+                .with_parameters(history.parameters().clone());
+            if let Err(err) = history.append(&entry) {
+                warn!(target: "cl_core::commands", path = %history.path().display(), "cannot record the execution: {err}");
+            }
+        }
+
+        let status = ExecStatus::from(status);
+        debug!(target: "cl_core::commands", alias = %self.alias, %status, "command finished");
+
+        Ok(status)
+    }
+
+    /// The program and arguments that run the command, e.g. `["bash", "-c", "<command>"]`
+    ///
+    /// ## Arguments
+    /// * `shell` - The preferred shell, used when the command does not set one
+    fn invocation(&self, shell: Option<&str>) -> Vec<String> {
+        let shell = self
+            .shell
+            .as_deref()
+            .or(shell)
+            .filter(|shell| !shell.trim().is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| {
+                env::var("SHELL").unwrap_or_else(|_| {
+                    warn!("$SHELL not found! Using sh");
+                    String::from("sh")
+                })
+            });
+
+        shell
+            .split_whitespace()
+            .map(String::from)
+            .chain([String::from("-c"), self.command.to_string()])
+            .collect()
     }
 
     fn truncate_command(&self) -> String {
@@ -245,12 +670,137 @@ impl CommandExec for Command<'_> {
     }
 }
 
+/// Runs the steps of a pipeline in order, applying the failure policy of each step
+///
+/// Each step inherits the shell, working directory and environment of the pipeline. The whole
+/// pipeline is recorded as a single execution in the `history`
+fn exec_pipeline(
+    pipeline: &Command,
+    dry_run: bool,
+    quiet_mode: bool,
+    shell: Option<&str>,
+    history: Option<&History>,
+) -> Result<ExecStatus> {
+    let steps = pipeline.steps();
+    let total = steps.len();
+    let started_at = SystemTime::now();
+    let start = Instant::now();
+    let mut status = ExecStatus::Exited(0);
+
+    for (idx, step) in steps.iter().enumerate() {
+        let position = format!("[{}/{total}]", idx + 1);
+        let command = step_command(pipeline, step)?;
+
+        if !quiet_mode && !dry_run {
+            eprintln!(
+                "{position} {}.{} --> {}",
+                pipeline.namespace,
+                pipeline.alias,
+                command.truncate_command()
+            );
+        }
+
+        let attempts = step.attempts();
+        let mut step_status = command.exec(dry_run, true, shell, None)?;
+        for attempt in 1..attempts {
+            if step_status.success() {
+                break;
+            }
+            if !quiet_mode {
+                eprintln!(
+                    "{position} {step_status}, retrying ({attempt}/{})",
+                    attempts - 1
+                );
+            }
+            step_status = command.exec(dry_run, true, shell, None)?;
+        }
+
+        debug!(target: "cl_core::commands", alias = %pipeline.alias, step = idx + 1, status = %step_status, "step finished");
+
+        if step_status.success() {
+            continue;
+        }
+
+        if step.on_failure == FailurePolicy::Continue {
+            if !quiet_mode {
+                eprintln!("{position} {step_status}, continuing");
+            }
+            continue;
+        }
+
+        if !quiet_mode {
+            eprintln!("{position} {step_status}, stopping the pipeline");
+        }
+        status = step_status;
+        break;
+    }
+
+    if dry_run {
+        return Ok(ExecStatus::DryRun);
+    }
+
+    if let Some(history) = history {
+        let exit_code = match status {
+            ExecStatus::Exited(code) => Some(code),
+            _ => None,
+        };
+        let entry = HistoryEntry::new(pipeline, started_at, start.elapsed(), exit_code)
+            .with_parameters(history.parameters().clone());
+        if let Err(err) = history.append(&entry) {
+            warn!(target: "cl_core::commands", path = %history.path().display(), "cannot record the execution: {err}");
+        }
+    }
+
+    Ok(status)
+}
+
+fn is_reference_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<CommandError>(),
+        Some(CommandError::ReferenceNotFound { .. })
+    )
+}
+
+/// The command that runs a single `step` of the `pipeline`
+fn step_command(pipeline: &Command, step: &Step) -> Result<Command<'static>> {
+    let run = step
+        .run
+        .as_deref()
+        .ok_or_else(|| CommandError::UnresolvedStep {
+            step: step.describe(),
+        })?;
+
+    let mut env = pipeline.env.clone().unwrap_or_default();
+    env.extend(step.env.clone().unwrap_or_default());
+
+    Ok(CommandBuilder::default()
+        .alias(pipeline.alias.to_string())
+        .namespace(pipeline.namespace.to_string())
+        .command(run)
+        .shell(step.shell.as_deref().or(pipeline.shell.as_deref()))
+        .cwd(step.cwd.as_deref().or(pipeline.cwd.as_deref()))
+        .env(Some(env))
+        .build())
+}
+
+/// Quotes `arg` so it can be copied to a POSIX shell as a single word
+fn quote(arg: &str) -> Cow<'_, str> {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        Cow::Borrowed(arg)
+    } else {
+        Cow::Owned(format!("'{}'", arg.replace('\'', r"'\''")))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::CommandVecExt;
+    use crate::Parameter;
     use core::panic;
     use std::borrow::Cow;
+    use std::collections::BTreeMap;
 
     macro_rules! create_command {
         ($alias:expr, $command:expr, $namespace:expr, $description:expr, $tags:expr) => {
@@ -260,6 +810,13 @@ mod test {
                 command: Cow::Borrowed($command),
                 description: $description,
                 tags: $tags,
+                parameters: None,
+                shell: None,
+                cwd: None,
+                env: None,
+                steps: None,
+                source: None,
+                read_only: false,
             }
         };
     }
@@ -325,7 +882,7 @@ mod test {
         let commands_list = commands.as_list();
         assert_eq!(1, commands_list.len());
 
-        let to_be_removed = commands_list.get(0).unwrap();
+        let to_be_removed = commands_list.first().unwrap();
         let commands_after_item_removed = commands.remove(to_be_removed);
 
         assert!(!commands_after_item_removed
@@ -333,6 +890,92 @@ mod test {
             .contains_key(&command.namespace.to_string()));
     }
 
+    #[test]
+    fn should_return_an_error_when_change_a_read_only_command() {
+        let mut command = create_command!("alias1", "command1", "namespace1", None, None);
+        command.source = Some("/team/commands.toml".into());
+        command.read_only = true;
+        let mut commands = commands!(command.to_owned());
+        let expected = CommandError::ReadOnlyCommand {
+            alias: "alias1".to_owned(),
+            namespace: "namespace1".to_owned(),
+            path: "/team/commands.toml".into(),
+        }
+        .to_string();
+
+        let mut edited = command.clone();
+        edited.command = Cow::Borrowed("edited");
+        let result = commands.edit(&edited, &command);
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some(expected.clone())
+        );
+
+        let result = commands.remove(&command);
+        assert_eq!(result.err().map(|err| err.to_string()), Some(expected));
+        assert_eq!(1, commands.as_list().len());
+    }
+
+    #[test]
+    fn should_fork_a_read_only_command() {
+        let mut command = create_command!("alias1", "command1", "namespace1", None, None);
+        command.source = Some("/team/commands.toml".into());
+        command.read_only = true;
+        let mut commands = commands!(command.to_owned());
+
+        let forked = commands.fork(&command).panic_if_error()["namespace1"][0].clone();
+        assert!(!forked.read_only);
+        assert_eq!(forked.source, None);
+
+        let mut edited = forked.clone();
+        edited.command = Cow::Borrowed("edited");
+        assert!(commands.edit(&edited, &forked).is_ok());
+    }
+
+    #[test]
+    fn should_merge_the_changes_of_both_sides() {
+        let base = vec![
+            create_command!("edited", "old", "namespace", None, None),
+            create_command!("removed", "command", "namespace", None, None),
+            create_command!("kept", "command", "namespace", None, None),
+        ]
+        .to_command_map();
+        let mut commands = Commands::init(base.clone());
+        commands
+            .edit(
+                &create_command!("edited", "ours", "namespace", None, None),
+                &create_command!("edited", "old", "namespace", None, None),
+            )
+            .panic_if_error();
+        commands
+            .remove(&create_command!(
+                "removed",
+                "command",
+                "namespace",
+                None,
+                None
+            ))
+            .panic_if_error();
+        let theirs = vec![
+            create_command!("edited", "theirs", "namespace", None, None),
+            create_command!("removed", "command", "namespace", None, None),
+            create_command!("kept", "theirs", "namespace", None, None),
+            create_command!("added", "theirs", "other", None, None),
+        ]
+        .to_command_map();
+
+        let merged = commands.merge(&base, theirs).to_vec().sorted();
+
+        let summary: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|c| (c.alias.as_ref(), c.command.as_ref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("edited", "ours"), ("kept", "theirs"), ("added", "theirs")]
+        );
+    }
+
     #[test]
     fn should_add_a_command() {
         let command = create_command!("old", "command", "namespace", None, None);
@@ -500,7 +1143,7 @@ mod test {
         let command1 = create_command!("alias", "command", "namespace1", None, None);
         let commands = commands!(command1);
         let invalid_alias = "invalid";
-        let result = commands.find(&invalid_alias, None);
+        let result = commands.find(invalid_alias, None);
 
         assert!(result.is_err());
         if let Err(error) = result {
@@ -532,28 +1175,345 @@ mod test {
         // dry run
         let dry_run = true;
         let quiet_mode = false;
-        let result = command.exec(dry_run, quiet_mode);
+        let result = command.exec(dry_run, quiet_mode, None, None);
         assert!(result.is_ok());
 
         // dry run & quiet
         let dry_run = true;
         let quiet_mode = true;
-        let result = command.exec(dry_run, quiet_mode);
+        let result = command.exec(dry_run, quiet_mode, None, None);
         assert!(result.is_ok());
 
         // quiet
         let dry_run = false;
         let quiet_mode = true;
-        let result = command.exec(dry_run, quiet_mode);
+        let result = command.exec(dry_run, quiet_mode, None, None);
         assert!(result.is_ok());
 
         // false dry run & false quiet
         let dry_run = false;
         let quiet_mode = false;
-        let result = command.exec(dry_run, quiet_mode);
+        let result = command.exec(dry_run, quiet_mode, None, None);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn should_return_the_exit_status_of_the_command() -> Result<()> {
+        let command = create_command!("alias", "exit 0", "namespace", None, None);
+        assert_eq!(
+            command.exec(true, true, Some("sh"), None)?,
+            ExecStatus::DryRun
+        );
+        assert!(command.exec(false, true, Some("sh"), None)?.success());
+
+        let command = create_command!("alias", "exit 42", "namespace", None, None);
+        let status = command.exec(false, true, Some("sh"), None)?;
+        assert!(!status.success());
+        assert_eq!(status.exit_code(), 42);
+
+        let command = create_command!("alias", "kill -9 $$", "namespace", None, None);
+        let status = command.exec(false, true, Some("sh"), None)?;
+        assert_eq!(status, ExecStatus::Signaled(9));
+        assert_eq!(status.exit_code(), 137);
+        assert_eq!(status.to_string(), "terminated by signal 9");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_the_command_with_its_own_shell() -> Result<()> {
+        let mut command = create_command!("alias", "echo $0", "namespace", None, None);
+        assert_eq!(
+            command.invocation(Some("bash")),
+            vec!["bash", "-c", "echo $0"]
+        );
+
+        command.shell = Some(Cow::Borrowed("sh -eu"));
+        assert_eq!(
+            command.invocation(Some("bash")),
+            vec!["sh", "-eu", "-c", "echo $0"]
+        );
+
+        command.command = Cow::Borrowed("test \"$0\" = sh");
+        assert!(command.exec(false, true, Some("bash"), None)?.success());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_the_command_with_its_working_dir_and_env() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let dir_path = dir.path().canonicalize()?;
+        let mut command = create_command!(
+            "alias",
+            "test \"$(pwd -P)\" = \"$EXPECTED_DIR\"",
+            "namespace",
+            None,
+            None
+        );
+        command.cwd = Some(Cow::Owned(dir_path.to_string_lossy().to_string()));
+        command.env = Some(
+            [(
+                "EXPECTED_DIR".to_owned(),
+                dir_path.to_string_lossy().to_string(),
+            )]
+            .into(),
+        );
+
+        assert!(command.exec(false, true, Some("sh"), None)?.success());
+
+        command.cwd = Some(Cow::Borrowed("/this/dir/does/not/exist"));
+        let result = command.exec(false, true, Some("sh"), None);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            CommandError::WorkingDirectoryNotFound {
+                path: "/this/dir/does/not/exist".into()
+            }
+            .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_quote_the_arguments_of_the_invocation() {
+        assert_eq!(quote("sh"), "sh");
+        assert_eq!(quote("-eu"), "-eu");
+        assert_eq!(quote("echo hi"), "'echo hi'");
+        assert_eq!(quote("echo 'hi'"), r"'echo '\''hi'\'''");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    fn should_record_the_execution_in_the_history() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let history = History::new(dir.path().join("history.toml"));
+        let command = create_command!("alias", "exit 3", "namespace", None, None);
+
+        command.exec(true, true, None, Some(&history))?;
+        assert!(
+            history.load()?.is_empty(),
+            "dry runs should not be recorded"
+        );
+
+        assert_eq!(
+            command.exec(false, true, None, Some(&history))?,
+            ExecStatus::Exited(3)
+        );
+        let entries = history.load()?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].command, "exit 3");
+        assert_eq!(entries[0].exit_code, Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_expand_the_steps_referencing_other_commands() -> Result<()> {
+        let mut build = create_command!("build", "cargo build", "rust", None, None);
+        build.env = Some(BTreeMap::from([("A".to_owned(), "1".to_owned())]));
+        build.parameters = Some(vec![Parameter::new("profile")]);
+        let mut pipeline = create_command!("ci", "", "rust", None, None);
+        let mut reference = Step::reference("build", Some("rust".to_owned()));
+        reference.env = Some(BTreeMap::from([("B".to_owned(), "2".to_owned())]));
+        pipeline.steps = Some(vec![reference, Step::run("cargo test")]);
+        let commands = commands!(build.clone(), pipeline.clone());
+
+        let expanded = commands.expand(&pipeline)?;
+
+        assert_eq!(expanded.script(), "cargo build\ncargo test");
+        assert_eq!(
+            expanded.steps()[0].env,
+            Some(BTreeMap::from([
+                ("A".to_owned(), "1".to_owned()),
+                ("B".to_owned(), "2".to_owned())
+            ]))
+        );
+        assert!(expanded.parameter("profile").is_some());
+
+        let mut nested = create_command!("release", "", "rust", None, None);
+        nested.steps = Some(vec![Step::reference("ci", None)]);
+        assert_eq!(
+            commands.expand(&nested).unwrap_err().to_string(),
+            CommandError::NestedPipeline {
+                reference: "@ci".to_owned()
+            }
+            .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_expand_the_references_to_other_commands() -> Result<()> {
+        let mut push = create_command!("push", "git push origin #{branch}", "git", None, None);
+        push.parameters = Some(vec![Parameter::new("branch")]);
+        let add = create_command!("add", "git add -A", "git", None, None);
+        let commit = create_command!(
+            "commit",
+            "@add && git commit -m '#{message}'",
+            "git",
+            None,
+            None
+        );
+        let ship = create_command!(
+            "ship",
+            "@git.commit && @git.push; echo me@example.com $@ '@git.push'",
+            "release",
+            None,
+            None
+        );
+        let commands = commands!(push, add, commit, ship.clone());
+
+        let expanded = commands.expand(&ship)?;
+
+        assert_eq!(
+            expanded.command,
+            "git add -A && git commit -m '#{message}' && git push origin #{branch}; \
+            echo me@example.com $@ '@git.push'"
+        );
+        assert!(expanded.parameter("branch").is_some());
+        assert_eq!(expanded.named_parameters(), vec!["message", "branch"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_the_words_that_are_not_references() -> Result<()> {
+        let curl = create_command!("payload", "echo not used", "curl", None, None);
+        let angular = create_command!("angular", "echo not used", "npm", None, None);
+        let commands = commands!(curl, angular);
+
+        for text in [
+            "curl -d @payload.json https://example.com",
+            "curl --data-binary @body.json https://example.com",
+            "npm i -g @angular/cli",
+            "git show HEAD@{1}",
+            "echo @nothing; echo @missing.alias",
+        ] {
+            let command = create_command!("alias", text, "namespace", None, None);
+            assert_eq!(commands.expand(&command)?.command, text);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_only_reference_commands_with_their_own_context_from_steps() -> Result<()> {
+        let mut build = create_command!("build", "cargo build", "rust", None, None);
+        build.cwd = Some(Cow::Borrowed("~/project"));
+        let inline = create_command!("inline", "@rust.build && echo done", "ns", None, None);
+        let mut pipeline = create_command!("pipeline", "", "ns", None, None);
+        pipeline.steps = Some(vec![Step::reference("build", None)]);
+        let commands = commands!(build, inline.clone(), pipeline.clone());
+
+        assert_eq!(
+            commands.expand(&inline).unwrap_err().to_string(),
+            CommandError::ReferenceWithContext {
+                reference: "@rust.build".to_owned()
+            }
+            .to_string()
+        );
+        let expanded = commands.expand(&pipeline)?;
+        assert_eq!(expanded.steps()[0].cwd.as_deref(), Some("~/project"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_an_error_when_a_referenced_step_does_not_exist() {
+        let mut command = create_command!("alias", "", "namespace", None, None);
+        command.steps = Some(vec![Step::reference("alias", Some("missing".to_owned()))]);
+        let commands = commands!(command.clone());
+
+        assert_eq!(
+            commands.expand(&command).unwrap_err().to_string(),
+            CommandError::ReferenceNotFound {
+                reference: "@missing.alias".to_owned()
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn should_return_an_error_when_commands_reference_each_other() {
+        let a = create_command!("a", "echo a && @ns.b", "ns", None, None);
+        let b = create_command!("b", "echo b\n@a", "ns", None, None);
+        let itself = create_command!("itself", "@itself", "ns", None, None);
+        let commands = commands!(a.clone(), b, itself.clone());
+
+        assert_eq!(
+            commands.expand(&a).unwrap_err().to_string(),
+            CommandError::CyclicReference {
+                cycle: "ns.a -> ns.b -> ns.a".to_owned()
+            }
+            .to_string()
+        );
+        assert!(commands.expand(&itself).is_err());
+    }
+
+    #[test]
+    fn should_run_the_steps_of_a_pipeline_with_their_failure_policy() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let log = dir.path().join("log");
+        let log = log.to_string_lossy();
+        let mut pipeline = create_command!("pipeline", "", "namespace", None, None);
+        let failing = |policy| Step {
+            run: Some(format!("echo fail >> {log}; exit 2")),
+            on_failure: policy,
+            retries: Some(1),
+            ..Default::default()
+        };
+
+        pipeline.steps = Some(vec![
+            failing(FailurePolicy::Continue),
+            Step::run(format!("echo ok >> {log}")),
+        ]);
+        assert!(pipeline.exec(false, true, Some("sh"), None)?.success());
+        assert_eq!(std::fs::read_to_string(&*log)?, "fail\nok\n");
+
+        std::fs::remove_file(&*log)?;
+        pipeline.steps = Some(vec![
+            failing(FailurePolicy::Retry),
+            Step::run(format!("echo ok >> {log}")),
+        ]);
+        assert_eq!(
+            pipeline.exec(false, true, Some("sh"), None)?,
+            ExecStatus::Exited(2)
+        );
+        assert_eq!(std::fs::read_to_string(&*log)?, "fail\nfail\n");
+
+        pipeline.steps = Some(vec![Step::reference("other", None)]);
+        assert!(pipeline.exec(false, true, Some("sh"), None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_override_the_directory_and_environment_of_the_steps() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let log = dir.path().join("log");
+        let mut pipeline = create_command!("pipeline", "", "namespace", None, None);
+        pipeline.steps = Some(vec![Step {
+            cwd: Some("/not/a/directory".to_owned()),
+            env: Some(BTreeMap::from([("STAGE".to_owned(), "step".to_owned())])),
+            ..Step::run(format!("pwd > {0}; echo $STAGE >> {0}", log.display()))
+        }]);
+
+        pipeline.override_context(
+            Some(&dir.path().to_string_lossy()),
+            &[("STAGE".to_owned(), "cli".to_owned())],
+        );
+
+        assert!(pipeline.exec(false, true, Some("sh"), None)?.success());
+        assert_eq!(
+            std::fs::read_to_string(&log)?,
+            format!("{}\ncli\n", dir.path().display())
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn should_reject_add_with_invalid_alias() {
         let invalid = create_command!("invalid alias", "command", "namespace", None, None);
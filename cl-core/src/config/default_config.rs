@@ -3,7 +3,9 @@ use crate::{
     resource::fs::{read_to_string, write},
 };
 
-use super::{get_config_path, Config, CONFIG_ROOT_DIR};
+use super::{get_config_path, Config, StorageKind, CONFIG_ROOT_DIR};
+use crate::command::expand_home;
+use crate::{BackedUpStorage, Layer, NamespaceDirStorage, Storage, TomlFileStorage};
 use anyhow::{bail, Context, Result};
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
@@ -20,6 +22,50 @@ pub struct DefaultConfig {
     commands_file_path: PathBuf,
     #[serde(alias = "preferences")]
     preferences: Preferences,
+    /// How the commands are stored. With the `directory` storage, `commands-file-path` is the
+    /// directory of the namespace files
+    #[serde(default, skip_serializing_if = "is_default_storage")]
+    storage: StorageKind,
+    /// Other files of commands, e.g. shared by a team, layered under the personal ones
+    ///
+    /// When many files have a command with the same alias and namespace, the first one wins, from
+    /// the highest precedence: the `.cl.toml` project file found in the current directory or in
+    /// its parents, the personal file, then the sources in the order they are listed. A warning
+    /// is shown when the project file is loaded and when one of its commands hides a personal one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<Source>,
+}
+
+/// A file of commands layered under the personal ones, either its path or a table with its path
+/// and whether it is read-only
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Source {
+    Path(String),
+    File {
+        path: String,
+        #[serde(default, rename = "read-only")]
+        read_only: bool,
+    },
+}
+
+impl Source {
+    fn to_layer(&self) -> Layer {
+        match self {
+            Source::Path(path) => Layer::toml(expand_home(path)),
+            Source::File { path, read_only } => {
+                let layer = Layer::toml(expand_home(path));
+                match read_only {
+                    true => layer.read_only(),
+                    false => layer,
+                }
+            }
+        }
+    }
+}
+
+fn is_default_storage(storage: &StorageKind) -> bool {
+    *storage == StorageKind::default()
 }
 
 impl DefaultConfig {
@@ -29,6 +75,8 @@ impl DefaultConfig {
         let config = Self {
             commands_file_path: home_dir.join(CONFIG_ROOT_DIR).join(COMMAND_FILE),
             preferences: Preferences::default(),
+            storage: StorageKind::default(),
+            sources: Vec::new(),
         };
 
         config.save().context("Cannot save the config file")?;
@@ -81,6 +129,19 @@ impl Config for DefaultConfig {
         self.commands_file_path.to_path_buf()
     }
 
+    fn file_storage(&self) -> Box<dyn Storage> {
+        let storage: Box<dyn Storage> = match self.storage {
+            StorageKind::Toml => Box::new(TomlFileStorage::new(self.command_file_path())),
+            StorageKind::Directory => Box::new(NamespaceDirStorage::new(self.command_file_path())),
+        };
+
+        Box::new(BackedUpStorage::new(storage, self.backups()))
+    }
+
+    fn sources(&self) -> Vec<Layer> {
+        self.sources.iter().map(Source::to_layer).collect()
+    }
+
     fn log_dir_path(&self) -> Result<PathBuf> {
         let home = home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
         Ok(home.join(CONFIG_ROOT_DIR))
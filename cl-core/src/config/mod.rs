@@ -1,13 +1,20 @@
 pub mod default_config;
 
 use crate::preferences::Preferences;
+use crate::{Backups, Layer, LayeredStorage, Storage, TomlFileStorage};
 use anyhow::Result;
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::env;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
 pub const CONFIG_ROOT_DIR: &str = ".config/cl";
 pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+pub const HISTORY_FILE: &str = "history.toml";
+pub const BACKUP_DIR: &str = "backups";
+/// The commands of a project, discovered in the current directory or in its parents
+pub const PROJECT_FILE: &str = ".cl.toml";
 
 #[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
 pub enum LogLevel {
@@ -27,6 +34,17 @@ impl From<&LogLevel> for String {
     }
 }
 
+/// How the commands are stored
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageKind {
+    /// A single TOML file
+    #[default]
+    Toml,
+    /// A directory with one TOML file per namespace
+    Directory,
+}
+
 pub trait Config {
     fn load() -> Result<Self>
     where
@@ -40,10 +58,102 @@ pub trait Config {
 
     fn command_file_path(&self) -> PathBuf;
 
+    /// Where the personal commands are loaded from and saved to. Defaults to the TOML file at
+    /// `command_file_path`
+    fn file_storage(&self) -> Box<dyn Storage> {
+        Box::new(TomlFileStorage::new(self.command_file_path()))
+    }
+
+    /// Other files of commands, e.g. shared by a team, in order of precedence
+    fn sources(&self) -> Vec<Layer> {
+        Vec::new()
+    }
+
+    /// Where all the commands are loaded from and saved to
+    ///
+    /// The personal commands are layered with the ones of the project file found in the current
+    /// directory or in its parents, and with the other sources. From the highest to the lowest
+    /// precedence: the project file, the personal file and then the sources, in order
+    fn storage(&self) -> Box<dyn Storage> {
+        let personal = self.command_file_path();
+        let project_file = env::current_dir()
+            .ok()
+            .and_then(|dir| find_project_file(&dir))
+            .filter(|file| *file != personal);
+        let sources: Vec<Layer> = self
+            .sources()
+            .into_iter()
+            .filter(|source| source.path() != personal)
+            .collect();
+
+        if project_file.is_none() && sources.is_empty() {
+            return self.file_storage();
+        }
+
+        let mut storage = LayeredStorage::new(Layer::new(personal, self.file_storage()));
+        if let Some(project_file) = project_file {
+            // a cloned repository may replace the personal commands, so it should never go unnoticed
+            warn!(target: "cl_core::config", "loading the project commands of {}, which take precedence over yours", project_file.display());
+            storage = storage.over(Layer::toml(project_file));
+        }
+        for source in sources {
+            storage = storage.under(source);
+        }
+
+        Box::new(storage)
+    }
+
     fn log_dir_path(&self) -> Result<PathBuf>;
+
+    /// The execution history file, stored next to the commands file
+    fn history_file_path(&self) -> PathBuf {
+        self.command_file_path().with_file_name(HISTORY_FILE)
+    }
+
+    /// The backups of the commands, stored next to the commands file
+    fn backups(&self) -> Backups {
+        Backups::new(
+            self.command_file_path().with_file_name(BACKUP_DIR),
+            self.preferences().backups(),
+        )
+    }
 }
 
 pub fn get_config_path() -> Result<PathBuf> {
     let home = home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
     Ok(home.join(CONFIG_ROOT_DIR).join(DEFAULT_CONFIG_FILE))
 }
+
+/// The closest project file to `dir`, looking in it and in its parents
+pub fn find_project_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(PROJECT_FILE))
+        .find(|file| file.is_file())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn should_find_the_closest_project_file() -> Result<()> {
+        let dir = TempDir::new()?;
+        let nested = dir.path().join("project/src");
+        std::fs::create_dir_all(&nested)?;
+        std::fs::write(dir.path().join(PROJECT_FILE), "")?;
+
+        assert_eq!(
+            find_project_file(&nested),
+            Some(dir.path().join(PROJECT_FILE))
+        );
+
+        std::fs::write(dir.path().join("project").join(PROJECT_FILE), "")?;
+        assert_eq!(
+            find_project_file(&nested),
+            Some(dir.path().join("project").join(PROJECT_FILE))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{Command, CommandError, CommandMap, CommandVecExt};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+use tracing::{debug, trace};
+
+/// Where a command is defined: the `[[table]]` of its file and its position in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub file: Option<PathBuf>,
+    pub table: String,
+    /// The position of the command in the table, starting at 1
+    pub position: usize,
+    pub alias: String,
+}
+
+impl Location {
+    /// The location without its file
+    pub fn in_file(&self) -> String {
+        format!("[[{}]] #{} '{}'", self.table, self.position, self.alias)
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        write!(f, "{}", self.in_file())
+    }
+}
+
+/// A problem of a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    AliasWithWhitespaces,
+    NamespaceWithWhitespaces,
+    /// The command is in the table of another namespace
+    WrongTable {
+        namespace: String,
+    },
+    EmptyCommand,
+    /// The command has the same alias and namespace of another one, ignoring the case
+    Duplicated {
+        of: Location,
+    },
+    /// A `#{` without its closing `}`
+    UnbalancedPlaceholder,
+    /// Any other error of [Command::validate]
+    Invalid(String),
+}
+
+impl Problem {
+    /// Whether [fix] solves the problem
+    pub fn is_fixable(&self) -> bool {
+        matches!(
+            self,
+            Problem::AliasWithWhitespaces
+                | Problem::NamespaceWithWhitespaces
+                | Problem::WrongTable { .. }
+                | Problem::Duplicated { .. }
+        )
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::AliasWithWhitespaces => write!(f, "the alias contains whitespace"),
+            Problem::NamespaceWithWhitespaces => write!(f, "the namespace contains whitespace"),
+            Problem::WrongTable { namespace } => {
+                write!(f, "the command belongs to the '{namespace}' namespace")
+            }
+            Problem::EmptyCommand => write!(f, "{}", CommandError::EmptyCommand),
+            Problem::Duplicated { of } => write!(f, "duplicate of {of}"),
+            Problem::UnbalancedPlaceholder => write!(f, "a '#{{' placeholder is never closed"),
+            Problem::Invalid(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// A problem found in a command
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub location: Location,
+    pub problem: Problem,
+    /// Read-only commands are never fixed
+    pub read_only: bool,
+}
+
+impl Diagnostic {
+    pub fn is_fixable(&self) -> bool {
+        !self.read_only && self.problem.is_fixable()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            // no need to repeat the file of a duplicate in the same file
+            Problem::Duplicated { of } if of.file == self.location.file => {
+                write!(f, "{}: duplicate of {}", self.location, of.in_file())
+            }
+            problem => write!(f, "{}: {problem}", self.location),
+        }
+    }
+}
+
+/// Checks the commands, beyond [Command::validate]. The diagnostics are sorted by location
+pub fn diagnose(commands: &CommandMap) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut seen: HashMap<(String, String), Location> = HashMap::new();
+
+    for (table, command, location) in located(commands) {
+        trace!(target: "cl_core::doctor", %location, "checking command");
+        let mut problems = Vec::new();
+
+        if command.alias.chars().any(char::is_whitespace) {
+            problems.push(Problem::AliasWithWhitespaces);
+        }
+        if command.namespace.chars().any(char::is_whitespace) {
+            problems.push(Problem::NamespaceWithWhitespaces);
+        }
+        if command.namespace != table {
+            problems.push(Problem::WrongTable {
+                namespace: command.namespace.to_string(),
+            });
+        }
+        let mut texts = std::iter::once(command.command.as_ref()).chain(
+            command
+                .steps()
+                .iter()
+                .filter_map(|step| step.run.as_deref()),
+        );
+        if texts.any(has_unbalanced_placeholder) {
+            problems.push(Problem::UnbalancedPlaceholder);
+        }
+
+        // the whitespaces are already reported, so they do not hide the other errors
+        let mut sanitized = command.clone();
+        sanitized.alias = sanitize(&command.alias).into();
+        sanitized.namespace = sanitize(&command.namespace).into();
+        if let Err(error) = sanitized.validate() {
+            match error.downcast_ref::<CommandError>() {
+                Some(CommandError::EmptyCommand) => problems.push(Problem::EmptyCommand),
+                _ => problems.push(Problem::Invalid(error.to_string())),
+            }
+        }
+
+        match seen.get(&key(command)) {
+            Some(first) => problems.push(Problem::Duplicated { of: first.clone() }),
+            None => {
+                seen.insert(key(command), location.clone());
+            }
+        }
+
+        diagnostics.extend(problems.into_iter().map(|problem| Diagnostic {
+            location: location.clone(),
+            problem,
+            read_only: command.read_only,
+        }));
+    }
+
+    debug!(target: "cl_core::doctor", problems = diagnostics.len(), "commands checked");
+    diagnostics
+}
+
+/// Fixes the problems that can be solved without losing commands
+///
+/// The whitespaces of aliases and namespaces are replaced by dashes, the commands are moved to the
+/// table of their namespace, and duplicates are removed if they are identical or renamed otherwise.
+/// Read-only commands are kept as they are
+pub fn fix<'cmd>(commands: &CommandMap<'cmd>) -> CommandMap<'cmd> {
+    let mut fixed: Vec<Command<'cmd>> = Vec::new();
+
+    let (read_only, writable): (Vec<_>, Vec<_>) = located(commands)
+        .into_iter()
+        .map(|(_, command, _)| command)
+        .partition(|command| command.read_only);
+    fixed.extend(read_only.into_iter().cloned());
+
+    for command in writable {
+        let mut command = command.clone();
+        command.alias = sanitize(&command.alias).into();
+        command.namespace = sanitize(&command.namespace).into();
+
+        if let Some(existing) = fixed.iter().find(|existing| **existing == command) {
+            if same_definition(existing, &command) {
+                trace!(target: "cl_core::doctor", alias = %command.alias, namespace = %command.namespace, "removing identical duplicate");
+                continue;
+            }
+            let alias = (2..)
+                .map(|n| format!("{}-{n}", command.alias))
+                .find(|alias| {
+                    !fixed.iter().any(|existing| {
+                        existing.alias.eq_ignore_ascii_case(alias)
+                            && existing.namespace.eq_ignore_ascii_case(&command.namespace)
+                    })
+                })
+                .unwrap_or_default();
+            trace!(target: "cl_core::doctor", alias = %command.alias, renamed = %alias, "renaming duplicate");
+            command.alias = alias.into();
+        }
+        fixed.push(command);
+    }
+
+    fixed.to_command_map()
+}
+
+/// The commands with their tables and locations, sorted by table and position
+fn located<'a, 'cmd>(
+    commands: &'a CommandMap<'cmd>,
+) -> Vec<(&'a str, &'a Command<'cmd>, Location)> {
+    commands
+        .iter()
+        .sorted_by_key(|(table, _)| table.as_str())
+        .flat_map(|(table, commands)| {
+            commands.iter().enumerate().map(move |(idx, command)| {
+                let location = Location {
+                    file: command.source.clone(),
+                    table: table.to_owned(),
+                    position: idx + 1,
+                    alias: command.alias.to_string(),
+                };
+                (table.as_str(), command, location)
+            })
+        })
+        .collect()
+}
+
+/// Aliases and namespaces are case insensitive
+fn key(command: &Command) -> (String, String) {
+    (
+        sanitize(&command.namespace).to_lowercase(),
+        sanitize(&command.alias).to_lowercase(),
+    )
+}
+
+/// Replaces the whitespaces by dashes
+fn sanitize(value: &str) -> String {
+    value.split_whitespace().join("-")
+}
+
+/// Ignoring the case of the alias and the namespace
+fn same_definition(a: &Command, b: &Command) -> bool {
+    let mut b = b.clone();
+    b.alias = a.alias.clone();
+    b.namespace = a.namespace.clone();
+    toml::Value::try_from(a).ok() == toml::Value::try_from(&b).ok()
+}
+
+fn has_unbalanced_placeholder(text: &str) -> bool {
+    let mut rest = text;
+    while let Some(start) = rest.find("#{") {
+        let placeholder = &rest[start + 2..];
+        match (placeholder.find('}'), placeholder.find("#{")) {
+            (Some(end), Some(next)) if next < end => return true,
+            (Some(end), _) => rest = &placeholder[end + 1..],
+            (None, _) => return true,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CommandBuilder, CommandMapExt};
+
+    fn command(alias: &str, namespace: &str, command: &str) -> Command<'static> {
+        CommandBuilder::default()
+            .alias(alias)
+            .namespace(namespace)
+            .command(command)
+            .build()
+    }
+
+    #[test]
+    fn should_report_the_problems_with_their_location() {
+        let mut commands = vec![
+            command("st atus", "git", "git status"),
+            command("Log", "git", "git log"),
+            command("log", "git", "git log --oneline"),
+            command("empty", "git", ""),
+            command("echo", "git", "echo #{name"),
+        ]
+        .to_command_map();
+        commands.insert(
+            "docker".to_owned(),
+            vec![command("ps", "dock", "docker ps")],
+        );
+
+        let diagnostics = diagnose(&commands)
+            .into_iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                "[[docker]] #1 'ps': the command belongs to the 'dock' namespace",
+                "[[git]] #1 'st atus': the alias contains whitespace",
+                "[[git]] #3 'log': duplicate of [[git]] #2 'Log'",
+                "[[git]] #4 'empty': Namespace, command and alias field cannot be empty!",
+                "[[git]] #5 'echo': a '#{' placeholder is never closed",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_fix_the_problems() {
+        let mut commands = vec![
+            command("st atus", "git", "git status"),
+            command("Log", "git", "git log"),
+            command("log", "git", "git log --oneline"),
+            command("LOG", "git", "git log"),
+        ]
+        .to_command_map();
+        commands.insert(
+            "docker".to_owned(),
+            vec![command("ps", "dock", "docker ps")],
+        );
+
+        let fixed = fix(&commands);
+
+        assert!(diagnose(&fixed).is_empty());
+        let aliases = fixed.to_vec().sorted().aliases();
+        assert_eq!(aliases, vec!["ps", "Log", "log-2", "st-atus"]);
+        assert_eq!(fixed["dock"].len(), 1);
+    }
+
+    #[test]
+    fn should_not_fix_the_read_only_commands() {
+        let mut read_only = command("st atus", "git", "git status");
+        read_only.read_only = true;
+        let commands = vec![read_only].to_command_map();
+
+        let diagnostics = diagnose(&commands);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].is_fixable());
+        assert_eq!(fix(&commands), commands);
+    }
+
+    #[test]
+    fn should_detect_unbalanced_placeholders() {
+        assert!(!has_unbalanced_placeholder("echo #{a} #{b}"));
+        assert!(!has_unbalanced_placeholder("echo {a}"));
+        assert!(has_unbalanced_placeholder("echo #{a"));
+        assert!(has_unbalanced_placeholder("echo #{a #{b}"));
+    }
+}
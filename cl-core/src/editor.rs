@@ -0,0 +1,266 @@
+use crate::{Command, Toml};
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Used when neither `$VISUAL` nor `$EDITOR` are set
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The text editor of the user, e.g. to change long commands outside of the forms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Editor {
+    program: String,
+}
+
+impl Editor {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    /// The editor set in `$VISUAL` or `$EDITOR`, in this order, falling back to `vi`
+    pub fn from_env() -> Self {
+        let program = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|key| env::var(key).ok())
+            .find(|program| !program.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_EDITOR.to_owned());
+
+        Self::new(program)
+    }
+
+    /// Opens the whole command as a TOML entry, returning the parsed one, or `None` if it was not
+    /// changed
+    ///
+    /// When the entry cannot be parsed or is not a valid command, the file is kept and `reopen` is
+    /// asked whether to open it again, so the changes are not lost. If not, the error tells where
+    /// the file is
+    ///
+    /// The edited command keeps the file it comes from, so it is saved back to it
+    pub fn edit_command<F>(
+        &self,
+        command: &Command,
+        mut reopen: F,
+    ) -> Result<Option<Command<'static>>>
+    where
+        F: FnMut(&anyhow::Error) -> bool,
+    {
+        let entry = Toml::from_command(command)?;
+        let path = create_temp_file(&entry, "toml")?;
+
+        loop {
+            let edited = self.open(&path).and_then(|_| {
+                fs::read_to_string(&path).with_context(|| format!("Cannot read {}", path.display()))
+            });
+            let edited = match edited {
+                Ok(edited) if edited == entry => {
+                    let _ = fs::remove_file(&path);
+                    return Ok(None);
+                }
+                Ok(edited) => edited,
+                Err(err) => {
+                    let _ = fs::remove_file(&path);
+                    return Err(err);
+                }
+            };
+
+            let parsed = Toml::to_command(&edited)
+                .context("Cannot parse the edited command")
+                .and_then(|parsed| parsed.validate().map(|_| parsed));
+            match parsed {
+                Ok(mut parsed) => {
+                    let _ = fs::remove_file(&path);
+                    parsed.source = command.source.clone();
+                    parsed.read_only = command.read_only;
+
+                    return Ok(Some(parsed));
+                }
+                Err(err) if reopen(&err) => {
+                    debug!(target: "cl_core::editor", path = %path.display(), "reopening the invalid command");
+                }
+                Err(err) => {
+                    return Err(err.context(format!("The changes were kept in {}", path.display())))
+                }
+            }
+        }
+    }
+
+    /// Opens the content in a temporary file with the given extension, returning it once the
+    /// editor exits
+    ///
+    /// The editor runs through the shell, so it may have arguments, e.g. `code --wait`
+    pub fn edit(&self, content: &str, extension: &str) -> Result<String> {
+        let path = create_temp_file(content, extension)?;
+        let edited = self.open(&path).and_then(|_| {
+            fs::read_to_string(&path).with_context(|| format!("Cannot read {}", path.display()))
+        });
+        let _ = fs::remove_file(&path);
+
+        edited
+    }
+
+    fn open(&self, path: &Path) -> Result<()> {
+        debug!(target: "cl_core::editor", editor = %self.program, path = %path.display(), "opening the editor");
+        let status = process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", self.program))
+            .arg("cl")
+            .arg(path)
+            .status()
+            .with_context(|| format!("Cannot open the editor '{}'", self.program))?;
+
+        if !status.success() {
+            bail!("The editor '{}' exited with {status}", self.program)
+        }
+
+        Ok(())
+    }
+}
+
+/// A new temporary file with the given content and extension
+///
+/// Only the user can read it, as the commands may have secrets, e.g. tokens in their environment
+fn create_temp_file(content: &str, extension: &str) -> Result<PathBuf> {
+    let path = temp_file_path(extension);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("Cannot create {}", path.display()))?;
+    file.write_all(content.as_bytes())?;
+
+    Ok(path)
+}
+
+fn temp_file_path(extension: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+
+    env::temp_dir().join(format!("cl-{}-{nanos}.{extension}", process::id()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CommandBuilder;
+
+    #[test]
+    fn should_return_the_content_changed_by_the_editor() -> Result<()> {
+        let editor = Editor::new("sed -i 's/status/log/'");
+
+        let edited = editor.edit("command = \"git status\"\n", "toml")?;
+
+        assert_eq!(edited, "command = \"git log\"\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_an_error_when_the_editor_fails() {
+        let editor = Editor::new("false");
+
+        let result = editor.edit("command = \"git status\"\n", "toml");
+
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("The editor 'false' exited with exit status: 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn should_parse_the_edited_command() -> Result<()> {
+        let command = CommandBuilder::default()
+            .alias("st")
+            .namespace("git")
+            .command("git status")
+            .build();
+
+        let unchanged = Editor::new("true").edit_command(&command, |_| false)?;
+        let edited =
+            Editor::new("sed -i 's/\"st\"/\"status\"/'").edit_command(&command, |_| false)?;
+
+        assert_eq!(unchanged, None);
+        assert_eq!(edited.map(|command| command.alias), Some("status".into()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_the_invalid_command_and_reopen_it() -> Result<()> {
+        let command = CommandBuilder::default()
+            .alias("st")
+            .namespace("git")
+            .command("git status")
+            .build();
+        // the first edit breaks the entry and the second one fixes it, keeping the first change
+        let editor = Editor::new(
+            "grep -q 'command = \\[' \"$1\" \
+                && sed -i 's/command = \\[/command = /' \"$1\" \
+                || sed -i 's/command = \"git status\"/command = [\"git log\"/' \"$1\"; true",
+        );
+        let mut errors = vec![];
+
+        let edited = editor.edit_command(&command, |err| {
+            errors.push(format!("{err:#}"));
+            true
+        })?;
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Cannot parse the edited command"));
+        assert_eq!(
+            edited.map(|command| command.command),
+            Some("git log".into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_tell_where_the_invalid_command_was_kept() -> Result<()> {
+        let command = CommandBuilder::default()
+            .alias("st")
+            .namespace("git")
+            .command("git status")
+            .build();
+        let editor = Editor::new("sed -i 's/command = .*/command = \"\"/'");
+
+        let err = editor
+            .edit_command(&command, |_| false)
+            .err()
+            .map(|err| err.to_string())
+            .unwrap_or_default();
+        let path = err.trim_start_matches("The changes were kept in ");
+
+        assert!(err.starts_with("The changes were kept in "));
+        assert!(fs::read_to_string(path)?.contains("command = \"\""));
+        fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_create_the_file_only_readable_by_the_user() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = create_temp_file("[env]\nTOKEN = \"secret\"\n", "toml")?;
+        let mode = fs::metadata(&path)?.permissions().mode();
+        fs::remove_file(&path)?;
+
+        assert_eq!(mode & 0o777, 0o600);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{Command, HistoryEntry};
+use std::collections::HashMap;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+
+/// Usage scores of the commands, blending how often and how recently they were run
+#[derive(Default, Debug, Clone)]
+pub struct Frecency {
+    scores: HashMap<(String, String), f64>,
+    max_score: f64,
+}
+
+impl Frecency {
+    /// Scores the commands found in `entries`
+    ///
+    /// ## Arguments
+    /// * `entries` - The execution history
+    /// * `now` - The current time, in seconds since the Unix epoch
+    pub fn from_history(entries: &[HistoryEntry], now: u64) -> Frecency {
+        let mut scores: HashMap<(String, String), f64> = HashMap::new();
+        for entry in entries {
+            let key = (entry.namespace.to_lowercase(), entry.alias.to_lowercase());
+            *scores.entry(key).or_default() += recency_weight(now.saturating_sub(entry.timestamp));
+        }
+        let max_score = scores.values().copied().fold(0.0, f64::max);
+
+        Self { scores, max_score }
+    }
+
+    /// The score of `command`. Zero if it was never run
+    pub fn score(&self, command: &Command) -> f64 {
+        let key = (
+            command.namespace.to_lowercase(),
+            command.alias.to_lowercase(),
+        );
+        self.scores.get(&key).copied().unwrap_or_default()
+    }
+
+    /// The score of `command` relative to the most used one, between `0.0` and `1.0`
+    pub fn relative_score(&self, command: &Command) -> f64 {
+        if self.max_score == 0.0 {
+            0.0
+        } else {
+            self.score(command) / self.max_score
+        }
+    }
+}
+
+/// Recent executions count more than old ones
+fn recency_weight(age: u64) -> f64 {
+    match age {
+        age if age < 4 * HOUR => 100.0,
+        age if age < DAY => 80.0,
+        age if age < 7 * DAY => 60.0,
+        age if age < 30 * DAY => 40.0,
+        age if age < 90 * DAY => 20.0,
+        _ => 10.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CommandBuilder;
+
+    fn entry(alias: &str, timestamp: u64) -> HistoryEntry {
+        HistoryEntry {
+            alias: alias.to_owned(),
+            namespace: "ns".to_owned(),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    fn command(alias: &str) -> Command<'static> {
+        CommandBuilder::default()
+            .alias(alias.to_owned())
+            .namespace("NS")
+            .command("echo")
+            .build()
+    }
+
+    #[test]
+    fn should_score_frequent_commands_higher() {
+        let now = 100 * DAY;
+        let entries = vec![entry("often", now), entry("often", now), entry("once", now)];
+
+        let frecency = Frecency::from_history(&entries, now);
+
+        assert!(frecency.score(&command("often")) > frecency.score(&command("once")));
+        assert_eq!(frecency.relative_score(&command("often")), 1.0);
+        assert_eq!(frecency.score(&command("never")), 0.0);
+    }
+
+    #[test]
+    fn should_score_recent_commands_higher() {
+        let now = 100 * DAY;
+        let entries = vec![
+            entry("old", now - 60 * DAY),
+            entry("old", now - 60 * DAY),
+            entry("recent", now - HOUR),
+        ];
+
+        let frecency = Frecency::from_history(&entries, now);
+
+        assert!(frecency.score(&command("recent")) > frecency.score(&command("old")));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::resource::errors::FileError;
+use crate::{Command, Step};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, trace};
+
+/// A single execution of a command
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The alias of the executed command
+    pub alias: String,
+    /// The namespace of the executed command
+    pub namespace: String,
+    /// The final command line, with its named parameters and arguments already replaced
+    ///
+    /// For pipelines, the description of each step, one per line
+    pub command: String,
+    /// The shell set by the command, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// When the command was started, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// The working directory the command was started from. Not a required field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// The exit code of the command. Empty if it was terminated by a signal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// How long the command took to run, in milliseconds
+    pub duration_ms: u64,
+    /// The environment variables set by the command. Not a required field
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// The steps of a pipeline, as they were run. Not a required field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<Step>>,
+    /// The values of the named parameters of the command. Not a required field
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        command: &Command,
+        started_at: SystemTime,
+        duration: Duration,
+        exit_code: Option<i32>,
+    ) -> HistoryEntry {
+        Self {
+            alias: command.alias.to_string(),
+            namespace: command.namespace.to_string(),
+            command: command.script().into_owned(),
+            shell: command.shell.as_ref().map(|s| s.to_string()),
+            timestamp: started_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            cwd: command
+                .working_dir()
+                .or_else(|| std::env::current_dir().ok()),
+            exit_code,
+            duration_ms: duration.as_millis() as u64,
+            env: command.env.clone().unwrap_or_default(),
+            steps: command.steps.clone(),
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_parameters(mut self, parameters: BTreeMap<String, String>) -> HistoryEntry {
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Checks if the entry refers to the command `alias` in `namespace`
+    pub fn is_for(&self, alias: &str, namespace: &str) -> bool {
+        self.alias.eq_ignore_ascii_case(alias) && self.namespace.eq_ignore_ascii_case(namespace)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct HistoryFile {
+    #[serde(default)]
+    entry: Vec<HistoryEntry>,
+}
+
+/// Append-only store of the executed commands
+pub struct History {
+    path: PathBuf,
+    /// The values of the named parameters, recorded with the executions
+    parameters: BTreeMap<String, String>,
+}
+
+impl History {
+    pub fn new(path: impl Into<PathBuf>) -> History {
+        Self {
+            path: path.into(),
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// Records the values of the named parameters with the executions
+    pub fn with_parameters(mut self, parameters: BTreeMap<String, String>) -> History {
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn parameters(&self) -> &BTreeMap<String, String> {
+        &self.parameters
+    }
+
+    /// The last `limit` distinct values of each named parameter of the command `alias` in
+    /// `namespace`, the most recent first
+    pub fn recent_parameters(
+        &self,
+        alias: &str,
+        namespace: &str,
+        limit: usize,
+    ) -> Result<HashMap<String, Vec<String>>> {
+        let mut recent: HashMap<String, Vec<String>> = HashMap::new();
+        for entry in self.load()?.iter().rev() {
+            if !entry.is_for(alias, namespace) {
+                continue;
+            }
+            for (name, value) in &entry.parameters {
+                let values = recent.entry(name.to_owned()).or_default();
+                if values.len() < limit && !values.contains(value) {
+                    values.push(value.to_owned());
+                }
+            }
+        }
+
+        Ok(recent)
+    }
+
+    /// Appends `entry` to the end of the history file, creating it if needed
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), FileError> {
+        trace!(target: "cl_core::history", path = %self.path.display(), alias = %entry.alias, "appending history entry");
+        let record = toml::to_string(&HistoryFile {
+            entry: vec![entry.to_owned()],
+        })?;
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{record}"))
+            .map_err(|cause| FileError::WriteFile {
+                path: self.path.to_path_buf(),
+                cause: cause.into(),
+            })
+    }
+
+    /// Loads all entries, oldest first. A missing file means an empty history
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        if !self.path.exists() {
+            debug!(target: "cl_core::history", path = %self.path.display(), "history file not found");
+            return Ok(vec![]);
+        }
+
+        let content = crate::read_to_string!(&self.path)?;
+        let history: HistoryFile = toml::from_str(&content)?;
+        debug!(target: "cl_core::history", entries = history.entry.len(), "history loaded");
+
+        Ok(history.entry)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CommandBuilder;
+    use tempfile::TempDir;
+
+    fn entry(alias: &str, exit_code: Option<i32>) -> HistoryEntry {
+        let command = CommandBuilder::default()
+            .alias(alias)
+            .namespace("ns")
+            .command("echo hello")
+            .build();
+        HistoryEntry::new(
+            &command,
+            SystemTime::now(),
+            Duration::from_millis(42),
+            exit_code,
+        )
+    }
+
+    #[test]
+    fn should_load_an_empty_history_when_the_file_does_not_exist() -> Result<()> {
+        let dir = TempDir::new()?;
+        let history = History::new(dir.path().join("history.toml"));
+
+        assert!(history.load()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_append_entries_in_order() -> Result<()> {
+        let dir = TempDir::new()?;
+        let history = History::new(dir.path().join("history.toml"));
+
+        history.append(&entry("first", Some(0)))?;
+        history.append(&entry("second", None))?;
+
+        let entries = history.load()?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].alias, "first");
+        assert_eq!(entries[1].alias, "second");
+        assert!(entries[0].succeeded());
+        assert!(!entries[1].succeeded());
+        assert_eq!(entries[1].duration_ms, 42);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_record_the_steps_of_a_pipeline() -> Result<()> {
+        let dir = TempDir::new()?;
+        let history = History::new(dir.path().join("history.toml"));
+        let steps = vec![
+            Step::run("cargo build"),
+            Step {
+                cwd: Some("docs".to_owned()),
+                on_failure: crate::FailurePolicy::Continue,
+                ..Step::run("mdbook build")
+            },
+        ];
+        let pipeline = CommandBuilder::default()
+            .alias("release")
+            .namespace("ns")
+            .steps(Some(steps.clone()))
+            .build();
+
+        history.append(&HistoryEntry::new(
+            &pipeline,
+            SystemTime::now(),
+            Duration::ZERO,
+            Some(0),
+        ))?;
+
+        assert_eq!(history.load()?[0].steps, Some(steps));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_list_the_recent_values_of_the_parameters() -> Result<()> {
+        let dir = TempDir::new()?;
+        let history = History::new(dir.path().join("history.toml"));
+        for (alias, env) in [
+            ("deploy", "dev"),
+            ("deploy", "prod"),
+            ("other", "qa"),
+            ("deploy", "dev"),
+            ("deploy", "staging"),
+        ] {
+            let parameters = BTreeMap::from([("env".to_owned(), env.to_owned())]);
+            history.append(&entry(alias, Some(0)).with_parameters(parameters))?;
+        }
+
+        let recent = history.recent_parameters("deploy", "ns", 2)?;
+
+        assert_eq!(
+            recent.get("env"),
+            Some(&vec!["staging".to_owned(), "dev".to_owned()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_match_entries_ignoring_case() {
+        let entry = entry("Alias", Some(0));
+
+        assert!(entry.is_for("alias", "NS"));
+        assert!(!entry.is_for("alias", "other"));
+    }
+}
@@ -1,22 +1,42 @@
 mod command;
 mod command_builder;
 mod commands;
+mod editor;
+mod frecency;
+mod history;
+mod parameter;
 mod preferences;
 mod resource;
+mod step;
 
 pub mod config;
+pub mod doctor;
 pub mod logger;
 
-pub use command::Command;
+pub use command::{parse_env_var, Command};
 pub use command_builder::CommandBuilder;
-pub use commands::CommandExec;
 pub use commands::Commands;
+pub use commands::{CommandExec, ExecStatus};
 pub use config::default_config::DefaultConfig;
 pub use config::Config;
 pub use config::LogLevel;
-pub use preferences::Preferences;
-pub use resource::errors::CommandError;
+pub use config::StorageKind;
+pub use editor::Editor;
+pub use frecency::Frecency;
+pub use history::{History, HistoryEntry};
+pub use parameter::{Parameter, ParameterType};
+pub use preferences::{Preferences, SortMode};
+pub use resource::backup::{Backup, Backups};
+pub use resource::errors::{CommandError, FileError};
+pub use resource::format::FileFormat;
 pub use resource::fs;
+pub use resource::fs::Fingerprint;
+pub use resource::storage::{
+    BackedUpStorage, InMemoryStorage, Layer, LayeredStorage, NamespaceDirStorage, Storage,
+    TomlFileStorage,
+};
+pub use resource::toml::Toml;
+pub use step::{FailurePolicy, Step};
 
 use std::collections::HashMap;
 
@@ -27,6 +47,8 @@ pub type CommandMap<'cmd> = HashMap<Namespace, CommandVec<'cmd>>;
 pub trait CommandVecExt<'cmd> {
     fn sorted(&mut self) -> CommandVec<'cmd>;
 
+    fn sorted_by_frecency(&mut self, frecency: &Frecency) -> CommandVec<'cmd>;
+
     fn to_command_map(&self) -> CommandMap<'cmd>;
 
     fn filter(&self, predicate: impl Fn(&Command) -> bool) -> Vec<&Command<'cmd>>;
@@ -50,6 +72,14 @@ impl<'cmd> CommandVecExt<'cmd> for CommandVec<'cmd> {
         sorted_commands
     }
 
+    /// Sorts by the `frecency` score, most used first, falling back to the natural order on ties
+    fn sorted_by_frecency(&mut self, frecency: &Frecency) -> CommandVec<'cmd> {
+        let mut sorted_commands = self.sorted();
+        sorted_commands.sort_by(|a, b| frecency.score(b).total_cmp(&frecency.score(a)));
+
+        sorted_commands
+    }
+
     fn to_command_map(&self) -> CommandMap<'cmd> {
         let mut command_map = CommandMap::new();
 
@@ -93,10 +123,7 @@ pub trait CommandMapExt<'cmd> {
 
 impl<'cmd> CommandMapExt<'cmd> for CommandMap<'cmd> {
     fn to_vec(&self) -> CommandVec<'cmd> {
-        self.iter()
-            .flat_map(|(_, commands)| commands)
-            .cloned()
-            .collect()
+        self.values().flatten().cloned().collect()
     }
 }
 
@@ -116,6 +143,17 @@ macro_rules! hashmap {
         }};
     }
 
+#[macro_export]
+macro_rules! initialize_commands {
+    ($storage:expr) => {{
+        use anyhow::Context;
+        use $crate::Commands;
+
+        let command_list = $storage.load().context("Cannot load the commands")?;
+        Commands::init(command_list)
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,17 +179,23 @@ mod tests {
         let vec: CommandVec<'static> = vec![cmd.clone()];
         assert_eq!(vec.first_command(), Some(cmd));
     }
-}
-
-#[macro_export]
-macro_rules! initialize_commands {
-    ($command_file_path:expr) => {{
-        use anyhow::Context;
-        use $crate::fs;
-        use $crate::Commands;
 
-        let command_list =
-            fs::load_from($command_file_path).context("Cannot load the command file")?;
-        Commands::init(command_list)
-    }};
+    #[test]
+    fn sorted_by_frecency_puts_the_most_used_commands_first() {
+        let entries = vec![HistoryEntry {
+            alias: "b".to_owned(),
+            namespace: "ns".to_owned(),
+            ..Default::default()
+        }];
+        let frecency = Frecency::from_history(&entries, 0);
+        let mut vec: CommandVec<'static> = vec![
+            make_cmd("c", "ns"),
+            make_cmd("b", "ns"),
+            make_cmd("a", "ns"),
+        ];
+
+        let aliases = vec.sorted_by_frecency(&frecency).aliases();
+
+        assert_eq!(aliases, vec!["b", "a", "c"]);
+    }
 }
@@ -0,0 +1,193 @@
+use crate::CommandError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The type of value a named parameter accepts
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    #[default]
+    String,
+    Int,
+    Path,
+    Bool,
+}
+
+impl fmt::Display for ParameterType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterType::String => write!(f, "string"),
+            ParameterType::Int => write!(f, "int"),
+            ParameterType::Path => write!(f, "path"),
+            ParameterType::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+/// Declaration of a `#{name}` placeholder used by a command
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Parameter {
+    /// The parameter name, as used in the `#{name}` placeholder
+    pub name: String,
+    /// What the parameter means. Not a required field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Value used when the parameter is not provided. Not a required field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// The only values accepted by the parameter. Not a required field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<String>>,
+    /// The parameter type. Defaults to `string`
+    #[serde(rename = "type", default)]
+    pub type_: ParameterType,
+}
+
+impl Parameter {
+    pub fn new(name: impl Into<String>) -> Parameter {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Checks if `value` matches the parameter type and choices
+    pub fn validate_value(&self, value: &str) -> Result<(), CommandError> {
+        let valid_type = match self.type_ {
+            ParameterType::String => true,
+            ParameterType::Int => value.parse::<i64>().is_ok(),
+            ParameterType::Path => !value.trim().is_empty(),
+            ParameterType::Bool => value.parse::<bool>().is_ok(),
+        };
+
+        if !valid_type {
+            return Err(CommandError::InvalidParameterType {
+                name: self.name.to_owned(),
+                value: value.to_owned(),
+                expected: self.type_.to_string(),
+            });
+        }
+
+        if let Some(choices) = &self.choices {
+            if !choices.iter().any(|choice| choice == value) {
+                return Err(CommandError::InvalidParameterChoice {
+                    name: self.name.to_owned(),
+                    value: value.to_owned(),
+                    choices: choices.join(", "),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a one-line, human readable summary of the parameter
+    pub fn summary(&self) -> String {
+        let mut details = vec![self.type_.to_string()];
+        if let Some(default) = &self.default {
+            details.push(format!("default: {default}"));
+        }
+        if let Some(choices) = &self.choices {
+            details.push(format!("choices: {}", choices.join("|")));
+        }
+
+        let mut summary = format!("{} ({})", self.name, details.join(", "));
+        if let Some(description) = &self.description {
+            summary.push_str(&format!(": {description}"));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parameter(type_: ParameterType, choices: Option<Vec<&str>>) -> Parameter {
+        Parameter {
+            name: "param".to_owned(),
+            type_,
+            choices: choices.map(|c| c.into_iter().map(String::from).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_accept_any_value_for_string_parameters() {
+        let param = parameter(ParameterType::String, None);
+
+        assert!(param.validate_value("anything goes").is_ok());
+    }
+
+    #[test]
+    fn should_validate_int_parameters() {
+        let param = parameter(ParameterType::Int, None);
+
+        assert!(param.validate_value("42").is_ok());
+        assert!(param.validate_value("-1").is_ok());
+
+        let result = param.validate_value("forty two");
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            CommandError::InvalidParameterType {
+                name: "param".to_owned(),
+                value: "forty two".to_owned(),
+                expected: "int".to_owned()
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn should_validate_bool_parameters() {
+        let param = parameter(ParameterType::Bool, None);
+
+        assert!(param.validate_value("true").is_ok());
+        assert!(param.validate_value("false").is_ok());
+        assert!(param.validate_value("yes").is_err());
+    }
+
+    #[test]
+    fn should_not_accept_empty_paths() {
+        let param = parameter(ParameterType::Path, None);
+
+        assert!(param.validate_value("~/projects").is_ok());
+        assert!(param.validate_value(" ").is_err());
+    }
+
+    #[test]
+    fn should_validate_choices() {
+        let param = parameter(ParameterType::String, Some(vec!["dev", "prod"]));
+
+        assert!(param.validate_value("dev").is_ok());
+
+        let result = param.validate_value("staging");
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            CommandError::InvalidParameterChoice {
+                name: "param".to_owned(),
+                value: "staging".to_owned(),
+                choices: "dev, prod".to_owned()
+            }
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn should_summarize_a_parameter() {
+        let param = Parameter {
+            name: "env".to_owned(),
+            description: Some("target environment".to_owned()),
+            default: Some("dev".to_owned()),
+            choices: Some(vec!["dev".to_owned(), "prod".to_owned()]),
+            type_: ParameterType::String,
+        };
+
+        assert_eq!(
+            param.summary(),
+            "env (string, default: dev, choices: dev|prod): target environment"
+        )
+    }
+}
@@ -5,6 +5,28 @@ use serde::{Deserialize, Serialize};
 const DEFAULT_LOG_LEVEL: &LogLevel = &LogLevel::Error;
 const DEFAULT_QUIET_MODE: bool = false;
 const DEFAULT_HIGHLIGHT_MATCHES: bool = true;
+const DEFAULT_SORT_MODE: SortMode = SortMode::Alphabetical;
+const DEFAULT_BACKUPS: usize = 10;
+
+/// How the commands are ordered in the list and in the search results
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// By namespace and alias
+    #[default]
+    Alphabetical,
+    /// The most frequently and recently executed commands first
+    Frecency,
+}
+
+impl From<&SortMode> for String {
+    fn from(sort_mode: &SortMode) -> Self {
+        match sort_mode {
+            SortMode::Alphabetical => String::from("alphabetical"),
+            SortMode::Frecency => String::from("frecency"),
+        }
+    }
+}
 
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -15,6 +37,12 @@ pub struct Preferences {
     log_level: Option<LogLevel>,
     #[serde(skip_serializing_if = "Option::is_none", alias = "highlight_matches")]
     highlight_matches: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_mode: Option<SortMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shell: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backups: Option<usize>,
 }
 
 impl Preferences {
@@ -23,6 +51,9 @@ impl Preferences {
             quiet_mode: None,
             log_level: None,
             highlight_matches: None,
+            sort_mode: None,
+            shell: None,
+            backups: None,
         }
     }
 
@@ -52,21 +83,50 @@ impl Preferences {
     pub fn set_quiet_mode(&mut self, quiet_mode: bool) {
         self.quiet_mode = Some(quiet_mode);
     }
+
+    pub fn sort_mode(&self) -> SortMode {
+        self.sort_mode.unwrap_or(DEFAULT_SORT_MODE)
+    }
+
+    pub fn set_sort_mode(&mut self, sort_mode: SortMode) {
+        self.sort_mode = Some(sort_mode);
+    }
+
+    /// The shell used to run the commands that do not set their own. Defaults to `$SHELL`
+    pub fn shell(&self) -> Option<&str> {
+        self.shell.as_deref()
+    }
+
+    pub fn set_shell(&mut self, shell: Option<String>) {
+        self.shell = shell.filter(|s| !s.trim().is_empty());
+    }
+
+    /// How many backups of the commands are kept. `0` disables them
+    pub fn backups(&self) -> usize {
+        self.backups.unwrap_or(DEFAULT_BACKUPS)
+    }
+
+    pub fn set_backups(&mut self, backups: usize) {
+        self.backups = Some(backups);
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{config::LogLevel, preferences::Preferences};
+    use crate::{
+        config::LogLevel,
+        preferences::{Preferences, SortMode},
+    };
 
     #[test]
     fn should_set_default_quiet_mode() {
         let mut preferences = Preferences::default();
 
-        assert_eq!(preferences.quiet_mode(), false);
+        assert!(!preferences.quiet_mode());
 
         preferences.set_quiet_mode(true);
 
-        assert_eq!(preferences.quiet_mode(), true);
+        assert!(preferences.quiet_mode());
     }
 
     #[test]
@@ -80,14 +140,49 @@ mod test {
         assert_eq!(preferences.log_level(), LogLevel::Debug);
     }
 
+    #[test]
+    fn should_set_sort_mode() {
+        let mut preferences = Preferences::default();
+
+        assert_eq!(preferences.sort_mode(), SortMode::Alphabetical);
+
+        preferences.set_sort_mode(SortMode::Frecency);
+
+        assert_eq!(preferences.sort_mode(), SortMode::Frecency);
+    }
+
+    #[test]
+    fn should_set_shell() {
+        let mut preferences = Preferences::default();
+
+        assert_eq!(preferences.shell(), None);
+
+        preferences.set_shell(Some("bash".to_owned()));
+        assert_eq!(preferences.shell(), Some("bash"));
+
+        preferences.set_shell(Some(" ".to_owned()));
+        assert_eq!(preferences.shell(), None);
+    }
+
+    #[test]
+    fn should_set_backups() {
+        let mut preferences = Preferences::default();
+
+        assert_eq!(preferences.backups(), 10);
+
+        preferences.set_backups(0);
+
+        assert_eq!(preferences.backups(), 0);
+    }
+
     #[test]
     fn should_set_highlight() {
         let mut preferences = Preferences::default();
 
-        assert_eq!(preferences.highlight(), true);
+        assert!(preferences.highlight());
 
         preferences.set_highlight(false);
 
-        assert_eq!(preferences.highlight(), false);
+        assert!(!preferences.highlight());
     }
 }
@@ -0,0 +1,187 @@
+use super::errors::FileError;
+use super::fs::{create_dir_all, load_from, save_at};
+use crate::CommandMap;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, trace};
+
+const BACKUP_PREFIX: &str = "commands-";
+const BACKUP_EXTENSION: &str = ".toml";
+
+/// A snapshot of the commands, taken before they were overwritten
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When the backup was taken, in milliseconds since the Unix epoch
+    pub timestamp_ms: u128,
+}
+
+impl Backup {
+    fn from_path(path: PathBuf) -> Option<Backup> {
+        let timestamp_ms = path
+            .file_name()?
+            .to_str()?
+            .strip_prefix(BACKUP_PREFIX)?
+            .strip_suffix(BACKUP_EXTENSION)?
+            .parse()
+            .ok()?;
+
+        Some(Self { path, timestamp_ms })
+    }
+
+    /// When the backup was taken, in seconds since the Unix epoch
+    pub fn timestamp(&self) -> u64 {
+        (self.timestamp_ms / 1000) as u64
+    }
+}
+
+/// Rolling, timestamped backups of the commands, as TOML files in a directory
+pub struct Backups {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl Backups {
+    /// ## Arguments
+    /// * `dir` - Where the backups are stored
+    /// * `keep` - How many backups are kept. The oldest ones are removed. `0` disables the backups
+    pub fn new(dir: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            keep,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Saves a new backup of `commands`, removing the oldest ones
+    ///
+    /// Returns the new backup, if the backups are enabled
+    pub fn create(&self, commands: &CommandMap) -> Result<Option<Backup>> {
+        if self.keep == 0 {
+            return Ok(None);
+        }
+
+        create_dir_all!(&self.dir)?;
+
+        let mut timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        // backups taken in the same millisecond must not overwrite each other
+        while self.path_of(timestamp_ms).exists() {
+            timestamp_ms += 1;
+        }
+
+        let path = self.path_of(timestamp_ms);
+        debug!(target: "cl_core::backup", path = %path.display(), "backing up the commands");
+        save_at(commands, &path)?;
+
+        self.prune()?;
+
+        Ok(Some(Backup { path, timestamp_ms }))
+    }
+
+    /// All the backups, most recent first
+    pub fn list(&self) -> Result<Vec<Backup>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let read_dir = std::fs::read_dir(&self.dir).map_err(|cause| FileError::ReadFile {
+            path: self.dir.clone(),
+            cause: cause.into(),
+        })?;
+
+        let mut backups = Vec::new();
+        for entry in read_dir {
+            if let Some(backup) = Backup::from_path(entry?.path()) {
+                backups.push(backup);
+            }
+        }
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.timestamp_ms));
+
+        Ok(backups)
+    }
+
+    /// Loads the commands of `backup`
+    pub fn load(&self, backup: &Backup) -> Result<CommandMap<'static>> {
+        load_from(&backup.path)
+    }
+
+    fn path_of(&self, timestamp_ms: u128) -> PathBuf {
+        self.dir
+            .join(format!("{BACKUP_PREFIX}{timestamp_ms}{BACKUP_EXTENSION}"))
+    }
+
+    fn prune(&self) -> Result<()> {
+        for backup in self.list()?.into_iter().skip(self.keep) {
+            trace!(target: "cl_core::backup", path = %backup.path.display(), "removing old backup");
+            std::fs::remove_file(&backup.path).map_err(|cause| FileError::WriteFile {
+                path: backup.path.clone(),
+                cause: cause.into(),
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CommandBuilder, CommandMapExt, CommandVecExt};
+    use tempfile::TempDir;
+
+    fn commands(alias: &str) -> CommandMap<'static> {
+        vec![CommandBuilder::default()
+            .alias(alias)
+            .namespace("namespace")
+            .command("echo")
+            .build()]
+        .to_command_map()
+    }
+
+    #[test]
+    fn should_keep_only_the_most_recent_backups() -> Result<()> {
+        let dir = TempDir::new()?;
+        let backups = Backups::new(dir.path().join("backups"), 2);
+
+        for alias in ["first", "second", "third"] {
+            backups.create(&commands(alias))?;
+        }
+
+        let list = backups.list()?;
+        assert_eq!(list.len(), 2);
+        assert!(list[0].timestamp_ms > list[1].timestamp_ms);
+        assert_eq!(backups.load(&list[0])?.to_vec()[0].alias, "third");
+        assert_eq!(backups.load(&list[1])?.to_vec()[0].alias, "second");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_create_backups_when_disabled() -> Result<()> {
+        let dir = TempDir::new()?;
+        let backups = Backups::new(dir.path().join("backups"), 0);
+
+        assert!(backups.create(&commands("alias"))?.is_none());
+        assert!(backups.list()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_ignore_unknown_files_in_the_backup_dir() -> Result<()> {
+        let dir = TempDir::new()?;
+        std::fs::write(dir.path().join("notes.txt"), "")?;
+        std::fs::write(dir.path().join("commands-latest.toml"), "")?;
+
+        assert!(Backups::new(dir.path(), 5).list()?.is_empty());
+
+        Ok(())
+    }
+}
@@ -21,6 +21,50 @@ pub enum CommandError {
     CannotRunCommand { command: String, cause: String },
     #[error("Namespace, command and alias field cannot be empty!")]
     EmptyCommand,
+    #[error("Invalid value \'{value}\' for parameter \'{name}\': expected a valid {expected}")]
+    InvalidParameterType {
+        name: String,
+        value: String,
+        expected: String,
+    },
+    #[error("Invalid value \'{value}\' for parameter \'{name}\'. Valid choices are: {choices}")]
+    InvalidParameterChoice {
+        name: String,
+        value: String,
+        choices: String,
+    },
+    #[error("Missing named parameters: {names}")]
+    MissingNamedParameters { names: String },
+    #[error("Invalid environment variable \'{entry}\': expected KEY=VALUE")]
+    InvalidEnvVar { entry: String },
+    #[error("The working directory \'{path}\' does not exist")]
+    WorkingDirectoryNotFound { path: PathBuf },
+    #[error("The step {index} must have either a command to run or the alias of a command")]
+    InvalidStep { index: usize },
+    #[error("\'{reference}\' references a pipeline. Pipelines cannot be nested")]
+    NestedPipeline { reference: String },
+    #[error(
+        "\'{reference}\' references a command with its own shell, working directory or environment. \
+    Reference it from a step of a pipeline instead"
+    )]
+    ReferenceWithContext { reference: String },
+    #[error("The command referenced by \'{reference}\' was not found")]
+    ReferenceNotFound { reference: String },
+    #[error("The commands reference each other in a cycle: {cycle}")]
+    CyclicReference { cycle: String },
+    #[error("The step \'{step}\' must be resolved before running the pipeline")]
+    UnresolvedStep { step: String },
+    #[error("Pipelines only accept named parameters, but got: {args}")]
+    PipelineArguments { args: String },
+    #[error(
+        "The command \'{alias}\' of the \'{namespace}\' namespace comes from the read-only file \
+    \'{path}\'. Fork it to change it"
+    )]
+    ReadOnlyCommand {
+        alias: String,
+        namespace: String,
+        path: PathBuf,
+    },
 }
 
 /// File related errors
@@ -28,6 +72,10 @@ pub enum CommandError {
 pub enum FileError {
     #[error("Cannot create a String of TOML")]
     CreateTomlFromString(#[from] toml::ser::Error),
+    #[error("Cannot create a String of JSON")]
+    CreateJsonFromString(#[from] serde_json::Error),
+    #[error("Cannot create a String of YAML")]
+    CreateYamlFromString(#[from] serde_yaml::Error),
     #[error("Cannot read {path}")]
     ReadFile {
         path: PathBuf,
@@ -40,6 +88,27 @@ pub enum FileError {
         #[source]
         cause: anyhow::Error,
     },
+    #[error("Cannot store the namespace \'{namespace}\' in a file: it is not a valid file name")]
+    InvalidNamespaceFile { namespace: String },
+    #[error("Cannot lock {path}")]
+    Lock {
+        path: PathBuf,
+        #[source]
+        cause: std::io::Error,
+    },
+    #[error("The commands were changed by another process since they were loaded")]
+    ChangedByAnotherProcess,
+    #[error(
+        "{path} was written by a newer version of cl (schema version {version}, but up to \
+    {supported} is supported). Please update cl"
+    )]
+    NewerSchemaVersion {
+        path: PathBuf,
+        version: i64,
+        supported: i64,
+    },
+    #[error("The namespace \'{namespace}\' is reserved")]
+    ReservedNamespace { namespace: String },
     #[error("Cannot create dirs at {path}")]
     CreateDirs {
         path: PathBuf,
@@ -0,0 +1,76 @@
+use super::errors::FileError;
+use super::json::Json;
+use super::toml::Toml;
+use super::yaml::Yaml;
+use crate::CommandMap;
+use anyhow::Result;
+use std::path::Path;
+
+/// The formats the commands can be written in, e.g. to be shared with other tools
+///
+/// The commands files of cl are always TOML
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FileFormat {
+    #[default]
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl FileFormat {
+    /// The format of a file, by its extension
+    pub fn of<P>(path: P) -> Option<FileFormat>
+    where
+        P: AsRef<Path>,
+    {
+        match path.as_ref().extension()?.to_str()? {
+            "toml" => Some(FileFormat::Toml),
+            "json" => Some(FileFormat::Json),
+            "yaml" | "yml" => Some(FileFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Toml => "toml",
+            FileFormat::Json => "json",
+            FileFormat::Yaml => "yaml",
+        }
+    }
+
+    /// Writes the commands in this format
+    pub fn serialize(&self, commands: &CommandMap) -> Result<String, FileError> {
+        match self {
+            FileFormat::Toml => Toml::from_map(commands),
+            FileFormat::Json => Json::from_map(commands),
+            FileFormat::Yaml => Yaml::from_map(commands),
+        }
+    }
+
+    /// Loads the commands of a file in this format
+    pub fn load<'f, P>(&self, path: P) -> Result<CommandMap<'f>>
+    where
+        P: AsRef<Path>,
+    {
+        match self {
+            FileFormat::Toml => Toml::from_file(path),
+            FileFormat::Json => Json::from_file(path),
+            FileFormat::Yaml => Yaml::from_file(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_find_the_format_of_a_file() {
+        assert_eq!(FileFormat::of("shared.toml"), Some(FileFormat::Toml));
+        assert_eq!(FileFormat::of("out/shared.json"), Some(FileFormat::Json));
+        assert_eq!(FileFormat::of("shared.yml"), Some(FileFormat::Yaml));
+        assert_eq!(FileFormat::of(PathBuf::from("shared")), None);
+    }
+}
@@ -1,18 +1,70 @@
 use super::errors::FileError;
-use crate::{resource::toml::Toml, CommandMap};
+use super::format::FileFormat;
+use crate::resource::toml::{Toml, SCHEMA_VERSION};
+use crate::CommandMap;
 use anyhow::Result;
-use std::{fs, path::Path};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use tracing::{debug, trace};
 
+/// The directory of the lock files, in the cache directory of cl
+const LOCK_DIR: &str = "locks";
+
 pub fn save_at<P>(commands: &CommandMap, path: P) -> Result<(), FileError>
 where
     P: AsRef<Path>,
 {
     debug!(target: "cl_core::fs", path = %path.as_ref().display(), "saving commands to file");
     let toml = Toml::from_map(commands)?;
+    back_up_outdated(path.as_ref())?;
     write!(path.as_ref(), toml)
 }
 
+/// Writes the commands in the given format, e.g. to share them with other tools
+pub fn save_as<P>(commands: &CommandMap, path: P, format: FileFormat) -> Result<(), FileError>
+where
+    P: AsRef<Path>,
+{
+    if format == FileFormat::Toml {
+        return save_at(commands, path);
+    }
+
+    debug!(target: "cl_core::fs", path = %path.as_ref().display(), ?format, "saving commands to file");
+    let content = format.serialize(commands)?;
+    write!(path.as_ref(), content)
+}
+
+/// Copies a file with an older schema version to `<name>.v<version>.bak` before it is upgraded,
+/// and refuses to overwrite a file with a newer one
+fn back_up_outdated(path: &Path) -> Result<(), FileError> {
+    let Some(version) = Toml::version_of_file(path) else {
+        return Ok(());
+    };
+    if version > SCHEMA_VERSION {
+        return Err(FileError::NewerSchemaVersion {
+            path: path.to_path_buf(),
+            version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let backup = path.with_file_name(format!("{file_name}.v{version}.bak"));
+    debug!(target: "cl_core::fs", path = %path.display(), backup = %backup.display(), "backing up the commands before upgrading them");
+    fs::copy(path, &backup).map_err(|cause| FileError::WriteFile {
+        path: backup,
+        cause: cause.into(),
+    })?;
+
+    Ok(())
+}
+
 pub fn load_from<'f, P>(path: P) -> Result<CommandMap<'f>>
 where
     P: AsRef<Path>,
@@ -21,18 +73,234 @@ where
     Toml::from_file(path)
 }
 
+/// Loads the commands of a file in the given format
+pub fn load_as<'f, P>(path: P, format: FileFormat) -> Result<CommandMap<'f>>
+where
+    P: AsRef<Path>,
+{
+    debug!(target: "cl_core::fs", path = %path.as_ref().display(), ?format, "loading commands from file");
+    format.load(path)
+}
+
+/// Writes `contents` to `path` atomically
+///
+/// The contents are written and synced to a temporary file next to `path`, which then replaces it,
+/// so a crash or a full disk never leaves a truncated file behind. Symlinks are followed and the
+/// permissions of an existing file are kept
 pub fn write<P, C>(path: P, contents: C) -> Result<(), FileError>
 where
     P: AsRef<Path>,
     C: AsRef<[u8]>,
 {
     trace!(target: "cl_core::fs", path = %path.as_ref().display(), "writing file");
-    fs::write(&path, contents).map_err(|cause| FileError::WriteFile {
-        path: path.as_ref().to_path_buf(),
+    let path = fs::canonicalize(&path).unwrap_or_else(|_| path.as_ref().to_path_buf());
+    let temp_path = temp_path_of(&path);
+
+    let result = write_and_replace(&path, &temp_path, contents.as_ref());
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result.map_err(|cause| FileError::WriteFile {
+        path,
         cause: cause.into(),
     })
 }
 
+fn write_and_replace(path: &Path, temp_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(temp_path, path)?;
+    sync_parent_dir(path)
+}
+
+/// A hidden, per process, temporary file in the same directory of `path`, so it can be renamed over it
+fn temp_path_of(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()))
+}
+
+/// Makes the rename durable
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> std::io::Result<()> {
+    match path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        Some(parent) => File::open(parent)?.sync_all(),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// How a file is locked by [lock]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockKind {
+    /// Many processes can hold the lock, e.g. to read the file
+    Shared,
+    /// Only one process can hold the lock, e.g. to write the file
+    Exclusive,
+}
+
+/// An advisory lock on a file, released when dropped
+///
+/// A shared lock that could not be taken holds no file, see [lock]
+#[derive(Debug)]
+pub struct FileLock {
+    _file: Option<File>,
+}
+
+/// Locks `path`, waiting for the other processes holding a conflicting lock
+///
+/// The lock is taken on a file of the cache directory named after `path`, as `path` itself is
+/// replaced on every write and its directory may be read-only or versioned, e.g. a project or a
+/// file shared by a team. `path` can be a file or a directory
+///
+/// A shared lock does not need to write anything: when it cannot be taken, the file is read
+/// without it
+pub fn lock<P>(path: P, kind: LockKind) -> Result<FileLock, FileError>
+where
+    P: AsRef<Path>,
+{
+    let dir = dirs::cache_dir()
+        .map(|dir| dir.join("cl").join(LOCK_DIR))
+        .unwrap_or_else(|| std::env::temp_dir().join(format!("cl-{LOCK_DIR}")));
+    lock_in(&dir, path, kind)
+}
+
+/// Locks `path` with a lock file in `dir`
+fn lock_in<P>(dir: &Path, path: P, kind: LockKind) -> Result<FileLock, FileError>
+where
+    P: AsRef<Path>,
+{
+    let path = fs::canonicalize(&path).unwrap_or_else(|_| path.as_ref().to_path_buf());
+    let lock_path = dir.join(lock_file_name(&path));
+    trace!(target: "cl_core::fs", path = %lock_path.display(), ?kind, "locking file");
+
+    let file = fs::create_dir_all(dir)
+        .and_then(|_| {
+            OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&lock_path)
+        })
+        .and_then(|file| {
+            match kind {
+                LockKind::Shared => file.lock_shared()?,
+                LockKind::Exclusive => file.lock()?,
+            }
+            Ok(file)
+        });
+
+    match (file, kind) {
+        (Ok(file), _) => Ok(FileLock { _file: Some(file) }),
+        (Err(cause), LockKind::Shared) => {
+            debug!(target: "cl_core::fs", path = %lock_path.display(), "reading without a lock: {cause}");
+            Ok(FileLock { _file: None })
+        }
+        (Err(cause), LockKind::Exclusive) => Err(FileError::Lock {
+            path: lock_path,
+            cause,
+        }),
+    }
+}
+
+/// The name of the file `path` is locked with, e.g. `commands.toml-<hash>.lock`
+///
+/// The hash of the whole path tells the files with the same name apart. It must not change
+/// between the versions of cl, so it is computed with FNV-1a
+fn lock_file_name(path: &Path) -> String {
+    let hash = path
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+        });
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    format!("{}-{hash:016x}.lock", file_name.trim_start_matches('.'))
+}
+
+/// Identifies the contents of a file or a directory at some point in time, to detect the changes
+/// made by other processes
+///
+/// Only the contents are hashed: the modification time is too coarse on some file systems to tell
+/// quick successive writes apart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    hash: u64,
+}
+
+impl Fingerprint {
+    pub fn new(contents: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        Self {
+            hash: hasher.finish(),
+        }
+    }
+
+    /// A single fingerprint for many contents, e.g. the files layered together
+    pub fn combine(fingerprints: &[Fingerprint]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        fingerprints.hash(&mut hasher);
+        Self {
+            hash: hasher.finish(),
+        }
+    }
+}
+
+/// The fingerprint of a file or of the files of a directory. Missing paths have the default one
+pub fn fingerprint<P>(path: P) -> Result<Fingerprint, FileError>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    trace!(target: "cl_core::fs", path = %path.display(), "fingerprinting");
+    let read_error = |cause: std::io::Error| FileError::ReadFile {
+        path: path.to_path_buf(),
+        cause: cause.into(),
+    };
+
+    if path.is_file() {
+        let contents = fs::read(path).map_err(read_error)?;
+        return Ok(Fingerprint::new(&contents));
+    }
+
+    if path.is_dir() {
+        let mut files = fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|e| e.path()))
+                    .collect::<std::io::Result<Vec<_>>>()
+            })
+            .map_err(read_error)?;
+        files.retain(|file| file.is_file());
+        files.sort();
+
+        let mut contents = Vec::new();
+        for file in files {
+            contents.extend(file.file_name().unwrap_or_default().as_encoded_bytes());
+            contents.extend(fs::read(&file).map_err(read_error)?);
+        }
+        return Ok(Fingerprint::new(&contents));
+    }
+
+    Ok(Fingerprint::default())
+}
+
 pub fn read_to_string<P>(path: P) -> Result<String, FileError>
 where
     P: AsRef<Path>,
@@ -87,7 +355,7 @@ pub use macros::{create_dir_all, read_to_string, write};
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{command::Command, CommandVecExt};
+    use crate::{command::Command, CommandMapExt, CommandVecExt};
     use anyhow::Result;
     use tempfile::TempDir;
 
@@ -105,6 +373,142 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn should_keep_the_working_dir_and_env_of_the_commands() -> Result<()> {
+        let command = Command {
+            cwd: Some("~/projects".into()),
+            env: Some([("FOO".to_owned(), "bar baz".to_owned())].into()),
+            ..Default::default()
+        };
+        let dir = TempDir::new()?;
+        let path = dir.path().join("test.toml");
+
+        save_at(&vec![command].to_command_map(), &path)?;
+        let loaded = load_from(&path)?.to_vec();
+
+        assert_eq!(loaded[0].cwd.as_deref(), Some("~/projects"));
+        assert_eq!(
+            loaded[0].env.as_ref().and_then(|env| env.get("FOO")),
+            Some(&"bar baz".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_replace_the_file_without_leaving_temporary_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("test.toml");
+        std::fs::write(&path, "old content")?;
+
+        write(&path, "new content")?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "new content");
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_write_through_symlinks() -> Result<()> {
+        let dir = TempDir::new()?;
+        let target = dir.path().join("target.toml");
+        let link = dir.path().join("link.toml");
+        std::fs::write(&target, "old content")?;
+        std::os::unix::fs::symlink(&target, &link)?;
+
+        write(&link, "new content")?;
+
+        assert!(std::fs::symlink_metadata(&link)?.file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target)?, "new content");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_back_up_an_outdated_file_before_upgrading_it() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.toml");
+        let outdated =
+            "[[git]]\nalias = \"status\"\nnamespace = \"git\"\ncommand = \"git status\"\n";
+        fs::write(&path, outdated)?;
+
+        let commands = load_from(&path)?;
+        save_at(&commands, &path)?;
+        save_at(&commands, &path)?;
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("commands.toml.v0.bak"))?,
+            outdated
+        );
+        assert_eq!(Toml::version_of_file(&path), Some(SCHEMA_VERSION));
+        assert_eq!(fs::read_dir(dir.path())?.count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_detect_changes_in_the_fingerprint() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("test.toml");
+        assert_eq!(fingerprint(&path)?, Fingerprint::default());
+
+        std::fs::write(&path, "old content")?;
+        let old = fingerprint(&path)?;
+        assert_eq!(fingerprint(&path)?, old);
+
+        std::fs::write(&path, "new content")?;
+        assert_ne!(fingerprint(&path)?, old);
+
+        assert_ne!(fingerprint(dir.path())?, Fingerprint::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_lock_files_in_the_lock_dir() -> Result<()> {
+        let dir = TempDir::new()?;
+        let locks = dir.path().join("locks");
+        let path = dir.path().join("test.toml");
+        let lock_file = locks.join(lock_file_name(&path));
+
+        let shared = lock_in(&locks, &path, LockKind::Shared)?;
+        let other_shared = lock_in(&locks, &path, LockKind::Shared)?;
+        assert!(lock_file.is_file());
+        assert!(!dir.path().join(".test.toml.lock").exists());
+        drop((shared, other_shared));
+
+        let _exclusive = lock_in(&locks, &path, LockKind::Exclusive)?;
+        let other = File::open(&lock_file)?;
+        assert!(other.try_lock_shared().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_read_without_a_lock_when_the_lock_file_cannot_be_created() -> Result<()> {
+        let dir = TempDir::new()?;
+        // a file where the lock dir should be
+        let locks = dir.path().join("locks");
+        std::fs::write(&locks, "")?;
+        let path = dir.path().join("test.toml");
+
+        assert!(lock_in(&locks, &path, LockKind::Shared).is_ok());
+        assert!(lock_in(&locks, &path, LockKind::Exclusive).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_name_the_lock_files_after_the_whole_path() {
+        let name = lock_file_name(Path::new("/project/.cl.toml"));
+
+        assert!(name.starts_with("cl.toml-") && name.ends_with(".lock"));
+        assert_eq!(name, lock_file_name(Path::new("/project/.cl.toml")));
+        assert_ne!(name, lock_file_name(Path::new("/other/.cl.toml")));
+    }
+
     #[test]
     fn should_return_an_error_on_nonexistent_path() -> Result<()> {
         let dir = TempDir::new()?;
@@ -0,0 +1,90 @@
+use super::errors::FileError;
+use super::toml::{Versioned, SCHEMA_VERSION, VERSION_KEY};
+use crate::CommandMap;
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::{fs::read_to_string, path::Path};
+
+/// The commands as a JSON object of namespaces, as in the TOML files
+///
+/// Only the TOML files are migrated: the JSON ones are read as the current schema version, unless
+/// they were written by a newer one
+pub struct Json;
+
+impl Json {
+    pub fn from_file<'f, P>(path: P) -> Result<CommandMap<'f>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let string_data = read_to_string(path)?;
+        let mut object = serde_json::from_str::<Map<String, Value>>(&string_data)?;
+
+        let version = object
+            .remove(VERSION_KEY)
+            .and_then(|version| version.as_i64())
+            .unwrap_or_default();
+        if version > SCHEMA_VERSION {
+            return Err(FileError::NewerSchemaVersion {
+                path: path.to_path_buf(),
+                version,
+                supported: SCHEMA_VERSION,
+            }
+            .into());
+        }
+
+        let commands = serde_json::from_value::<CommandMap>(Value::Object(object))?;
+        Ok(commands)
+    }
+
+    pub fn from_map(commands: &CommandMap) -> Result<String, FileError> {
+        let versioned = Versioned::new(commands)?;
+        serde_json::to_string_pretty(&versioned).map_err(FileError::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CommandBuilder, CommandMapExt, CommandVecExt};
+    use tempfile::TempDir;
+
+    #[test]
+    fn should_write_and_load_the_commands() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.json");
+        let command = CommandBuilder::default()
+            .alias("log")
+            .namespace("git")
+            .command("git log --oneline")
+            .tags(Some(["vcs"]))
+            .build();
+
+        let json = Json::from_map(&vec![command.clone()].to_command_map())?;
+        std::fs::write(&path, &json)?;
+        let loaded = Json::from_file(&path)?.to_vec();
+
+        assert!(json.starts_with(&format!("{{\n  \"{VERSION_KEY}\": {SCHEMA_VERSION},")));
+        assert_eq!(loaded, vec![command.clone()]);
+        assert_eq!(loaded[0].tags, command.tags);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_load_the_commands_written_by_other_tools() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.json");
+        std::fs::write(
+            &path,
+            r#"{"docker": [{"alias": "ps", "namespace": "docker", "command": "docker ps", "description": null}]}"#,
+        )?;
+
+        let loaded = Json::from_file(&path)?.to_vec();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].command, "docker ps");
+
+        Ok(())
+    }
+}
@@ -1,3 +1,8 @@
+pub mod backup;
 pub mod errors;
+pub mod format;
 pub mod fs;
+pub mod json;
+pub mod storage;
 pub mod toml;
+pub mod yaml;
@@ -0,0 +1,1004 @@
+use super::backup::Backups;
+use super::errors::FileError;
+use super::fs::{self, create_dir_all, load_from, lock, save_at, FileLock, Fingerprint, LockKind};
+use super::toml::Toml;
+use crate::{CommandMap, CommandMapExt, CommandVec, CommandVecExt};
+use anyhow::Result;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, trace, warn};
+
+const NAMESPACE_FILE_EXTENSION: &str = "toml";
+
+/// Where the commands are loaded from and saved to
+pub trait Storage: Send + Sync {
+    fn load(&self) -> Result<CommandMap<'static>>;
+
+    /// Replaces the stored commands with `commands`
+    fn save(&self, commands: &CommandMap) -> Result<()>;
+
+    /// Identifies the stored commands, to detect the changes made by other processes
+    fn fingerprint(&self) -> Result<Fingerprint>;
+
+    /// Loads the commands without locking them, e.g. from a read-only file cl never writes
+    fn load_unlocked(&self) -> Result<CommandMap<'static>> {
+        self.load()
+    }
+
+    /// Identifies the stored commands without locking them, see [Storage::load_unlocked]
+    fn fingerprint_unlocked(&self) -> Result<Fingerprint> {
+        self.fingerprint()
+    }
+
+    /// Replaces the stored commands without locking them, e.g. while [Storage::lock_exclusive]
+    /// is held
+    fn save_unlocked(&self, commands: &CommandMap) -> Result<()> {
+        self.save(commands)
+    }
+
+    /// Locks the stored commands for writing, so they can be checked and saved with the unlocked
+    /// methods, e.g. together with other storages. `None` when they are not stored in files
+    fn lock_exclusive(&self) -> Result<Option<FileLock>> {
+        Ok(None)
+    }
+
+    /// Replaces the stored commands with `commands`, unless they were changed since `expected`
+    /// was taken, failing with [FileError::ChangedByAnotherProcess]
+    ///
+    /// Returns the fingerprint of the saved commands
+    fn save_if_unchanged(
+        &self,
+        commands: &CommandMap,
+        expected: &Fingerprint,
+    ) -> Result<Fingerprint>;
+}
+
+fn ensure_unchanged(current: &Fingerprint, expected: &Fingerprint) -> Result<(), FileError> {
+    if current != expected {
+        return Err(FileError::ChangedByAnotherProcess);
+    }
+
+    Ok(())
+}
+
+/// Stores all the commands in a single TOML file. The default storage
+pub struct TomlFileStorage {
+    path: PathBuf,
+}
+
+impl TomlFileStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Storage for TomlFileStorage {
+    fn load(&self) -> Result<CommandMap<'static>> {
+        let _lock = lock(&self.path, LockKind::Shared)?;
+        self.load_unlocked()
+    }
+
+    fn save(&self, commands: &CommandMap) -> Result<()> {
+        let _lock = lock(&self.path, LockKind::Exclusive)?;
+        self.save_unlocked(commands)
+    }
+
+    fn fingerprint(&self) -> Result<Fingerprint> {
+        let _lock = lock(&self.path, LockKind::Shared)?;
+        self.fingerprint_unlocked()
+    }
+
+    fn load_unlocked(&self) -> Result<CommandMap<'static>> {
+        load_from(&self.path)
+    }
+
+    fn fingerprint_unlocked(&self) -> Result<Fingerprint> {
+        Ok(fs::fingerprint(&self.path)?)
+    }
+
+    fn save_unlocked(&self, commands: &CommandMap) -> Result<()> {
+        save_at(commands, &self.path)?;
+        Ok(())
+    }
+
+    fn lock_exclusive(&self) -> Result<Option<FileLock>> {
+        Ok(Some(lock(&self.path, LockKind::Exclusive)?))
+    }
+
+    fn save_if_unchanged(
+        &self,
+        commands: &CommandMap,
+        expected: &Fingerprint,
+    ) -> Result<Fingerprint> {
+        let _lock = lock(&self.path, LockKind::Exclusive)?;
+        ensure_unchanged(&fs::fingerprint(&self.path)?, expected)?;
+        save_at(commands, &self.path)?;
+        Ok(fs::fingerprint(&self.path)?)
+    }
+}
+
+/// Stores each namespace in its own TOML file, named after the namespace, in a directory
+pub struct NamespaceDirStorage {
+    dir: PathBuf,
+}
+
+impl NamespaceDirStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn namespace_files(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let read_dir = std::fs::read_dir(&self.dir).map_err(|cause| FileError::ReadFile {
+            path: self.dir.clone(),
+            cause: cause.into(),
+        })?;
+
+        let mut files = Vec::new();
+        for entry in read_dir {
+            let path = entry?.path();
+            if path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|e| e == NAMESPACE_FILE_EXTENSION)
+            {
+                files.push(path);
+            }
+        }
+
+        Ok(files)
+    }
+
+    fn namespace_file(&self, namespace: &str) -> Result<PathBuf, FileError> {
+        let is_valid = !namespace.is_empty()
+            && !namespace.starts_with('.')
+            && Path::new(namespace).file_name() == Some(namespace.as_ref());
+        if !is_valid {
+            return Err(FileError::InvalidNamespaceFile {
+                namespace: namespace.to_owned(),
+            });
+        }
+
+        Ok(self
+            .dir
+            .join(format!("{namespace}.{NAMESPACE_FILE_EXTENSION}")))
+    }
+}
+
+impl Storage for NamespaceDirStorage {
+    fn load(&self) -> Result<CommandMap<'static>> {
+        let _lock = lock(&self.dir, LockKind::Shared)?;
+        self.load_unlocked()
+    }
+
+    fn save(&self, commands: &CommandMap) -> Result<()> {
+        let _lock = lock(&self.dir, LockKind::Exclusive)?;
+        self.save_unlocked(commands)
+    }
+
+    fn fingerprint(&self) -> Result<Fingerprint> {
+        let _lock = lock(&self.dir, LockKind::Shared)?;
+        self.fingerprint_unlocked()
+    }
+
+    fn save_if_unchanged(
+        &self,
+        commands: &CommandMap,
+        expected: &Fingerprint,
+    ) -> Result<Fingerprint> {
+        let _lock = lock(&self.dir, LockKind::Exclusive)?;
+        ensure_unchanged(&fs::fingerprint(&self.dir)?, expected)?;
+        self.save_unlocked(commands)?;
+        Ok(fs::fingerprint(&self.dir)?)
+    }
+
+    fn fingerprint_unlocked(&self) -> Result<Fingerprint> {
+        Ok(fs::fingerprint(&self.dir)?)
+    }
+
+    fn save_unlocked(&self, commands: &CommandMap) -> Result<()> {
+        debug!(target: "cl_core::storage", dir = %self.dir.display(), "saving commands to dir");
+        create_dir_all!(&self.dir)?;
+
+        let mut saved = Vec::with_capacity(commands.len());
+        for (namespace, namespace_commands) in commands {
+            let file = self.namespace_file(namespace)?;
+            let content = CommandMap::from([(namespace.to_owned(), namespace_commands.clone())]);
+            save_at(&content, &file)?;
+            saved.push(file);
+        }
+
+        for file in self.namespace_files()? {
+            if !saved.contains(&file) {
+                trace!(target: "cl_core::storage", path = %file.display(), "removing empty namespace file");
+                std::fs::remove_file(&file).map_err(|cause| FileError::WriteFile {
+                    path: file.clone(),
+                    cause: cause.into(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lock_exclusive(&self) -> Result<Option<FileLock>> {
+        Ok(Some(lock(&self.dir, LockKind::Exclusive)?))
+    }
+
+    fn load_unlocked(&self) -> Result<CommandMap<'static>> {
+        debug!(target: "cl_core::storage", dir = %self.dir.display(), "loading commands from dir");
+        let mut commands = CommandMap::new();
+
+        for file in self.namespace_files()? {
+            trace!(target: "cl_core::storage", path = %file.display(), "loading namespace file");
+            for (namespace, namespace_commands) in load_from(&file)? {
+                commands
+                    .entry(namespace)
+                    .or_default()
+                    .extend(namespace_commands);
+            }
+        }
+
+        Ok(commands)
+    }
+}
+
+/// Backs up the stored commands before replacing them
+pub struct BackedUpStorage {
+    inner: Box<dyn Storage>,
+    backups: Backups,
+}
+
+impl BackedUpStorage {
+    pub fn new(inner: Box<dyn Storage>, backups: Backups) -> Self {
+        Self { inner, backups }
+    }
+
+    /// Backs up the `previous` commands, loaded with or without a lock
+    fn back_up(&self, previous: Result<CommandMap<'static>>) {
+        // nothing to back up on the first save
+        let previous = previous.ok().filter(|previous| !previous.is_empty());
+        if let Some(previous) = previous {
+            if let Err(err) = self.backups.create(&previous) {
+                warn!(target: "cl_core::storage", dir = %self.backups.dir().display(), "cannot back up the commands: {err}");
+            }
+        }
+    }
+}
+
+impl Storage for BackedUpStorage {
+    fn load(&self) -> Result<CommandMap<'static>> {
+        self.inner.load()
+    }
+
+    fn save(&self, commands: &CommandMap) -> Result<()> {
+        self.back_up(self.inner.load());
+        self.inner.save(commands)
+    }
+
+    fn fingerprint(&self) -> Result<Fingerprint> {
+        self.inner.fingerprint()
+    }
+
+    fn load_unlocked(&self) -> Result<CommandMap<'static>> {
+        self.inner.load_unlocked()
+    }
+
+    fn fingerprint_unlocked(&self) -> Result<Fingerprint> {
+        self.inner.fingerprint_unlocked()
+    }
+
+    fn save_unlocked(&self, commands: &CommandMap) -> Result<()> {
+        self.back_up(self.inner.load_unlocked());
+        self.inner.save_unlocked(commands)
+    }
+
+    fn lock_exclusive(&self) -> Result<Option<FileLock>> {
+        self.inner.lock_exclusive()
+    }
+
+    fn save_if_unchanged(
+        &self,
+        commands: &CommandMap,
+        expected: &Fingerprint,
+    ) -> Result<Fingerprint> {
+        // no backup is needed if the save is going to be rejected anyway
+        ensure_unchanged(&self.inner.fingerprint()?, expected)?;
+        self.back_up(self.inner.load());
+        self.inner.save_if_unchanged(commands, expected)
+    }
+}
+
+/// A file of commands layered by a [LayeredStorage]
+pub struct Layer {
+    path: PathBuf,
+    storage: Box<dyn Storage>,
+    read_only: bool,
+}
+
+impl Layer {
+    /// ## Arguments
+    /// * `path` - The file (or directory) of the commands, remembered as their `source`
+    /// * `storage` - How the commands are stored at `path`
+    pub fn new(path: impl Into<PathBuf>, storage: Box<dyn Storage>) -> Self {
+        Self {
+            path: path.into(),
+            storage,
+            read_only: false,
+        }
+    }
+
+    /// A layer stored in a single TOML file
+    pub fn toml(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self::new(path.clone(), Box::new(TomlFileStorage::new(path)))
+    }
+
+    /// The commands of the layer cannot be edited or removed, and the layer is never saved nor
+    /// locked, as its file may not be writable
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Missing files are empty layers, e.g. a project without commands
+    ///
+    /// ## Arguments
+    /// * `locked` - Whether the layer is already locked, see [LayeredStorage::save_if_unchanged]
+    fn load(&self, locked: bool) -> Result<CommandMap<'static>> {
+        if !self.path.exists() {
+            return Ok(CommandMap::new());
+        }
+        match self.read_only || locked {
+            true => self.storage.load_unlocked(),
+            false => self.storage.load(),
+        }
+    }
+
+    fn fingerprint(&self, locked: bool) -> Result<Fingerprint> {
+        if !self.path.exists() {
+            return Ok(Fingerprint::default());
+        }
+        match self.read_only || locked {
+            true => self.storage.fingerprint_unlocked(),
+            false => self.storage.fingerprint(),
+        }
+    }
+}
+
+/// Layers many files of commands into a single view, e.g. a project file over the personal file
+/// over a file shared by a team
+///
+/// The layers are in order of precedence: when many layers have a command with the same alias and
+/// namespace, the one of the first layer is used, and the others are kept untouched in their files.
+/// Each loaded command remembers its layer in `source` and is saved back to it. The commands
+/// without a known `source`, e.g. the new ones, are saved to the personal layer. The read-only
+/// layers are never saved
+pub struct LayeredStorage {
+    layers: Vec<Layer>,
+    personal: PathBuf,
+}
+
+impl LayeredStorage {
+    pub fn new(personal: Layer) -> Self {
+        Self {
+            personal: personal.path.clone(),
+            layers: vec![personal],
+        }
+    }
+
+    /// Adds a layer with precedence over the current ones
+    pub fn over(mut self, layer: Layer) -> Self {
+        self.layers.insert(0, layer);
+        self
+    }
+
+    /// Adds a layer with lower precedence than the current ones
+    pub fn under(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Locks the writable layers for writing, in the order of their paths, so two processes
+    /// saving the same layers never wait for each other
+    fn lock_writable_layers(&self) -> Result<Vec<FileLock>> {
+        let mut locks = Vec::new();
+        for layer in self
+            .layers
+            .iter()
+            .filter(|layer| !layer.read_only)
+            .sorted_by(|a, b| a.path.cmp(&b.path))
+        {
+            trace!(target: "cl_core::storage", path = %layer.path.display(), "locking layer");
+            locks.extend(layer.storage.lock_exclusive()?);
+        }
+
+        Ok(locks)
+    }
+
+    fn fingerprint_layers(&self, locked: bool) -> Result<Fingerprint> {
+        let fingerprints = self
+            .layers
+            .iter()
+            .map(|layer| layer.fingerprint(locked))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Fingerprint::combine(&fingerprints))
+    }
+
+    /// Saves each command to its layer, see [LayeredStorage]
+    ///
+    /// ## Arguments
+    /// * `locked` - Whether the writable layers are already locked
+    fn save_layers(&self, commands: &CommandMap, locked: bool) -> Result<()> {
+        let visible: HashMap<_, _> = commands
+            .values()
+            .flatten()
+            .map(|command| {
+                (
+                    command_key(command),
+                    self.layer_of(command.source.as_deref()),
+                )
+            })
+            .collect();
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            if layer.read_only {
+                trace!(target: "cl_core::storage", path = %layer.path.display(), "skipping read-only layer");
+                continue;
+            }
+            let stored = layer.load(locked)?;
+            let mut content: CommandVec = commands
+                .values()
+                .flatten()
+                .filter(|command| self.layer_of(command.source.as_deref()) == idx)
+                .cloned()
+                .collect();
+            // the shadowed commands were not loaded, so they are kept as they are
+            content.extend(stored.to_vec().into_iter().filter(|command| {
+                visible
+                    .get(&command_key(command))
+                    .is_some_and(|visible_idx| *visible_idx < idx)
+            }));
+
+            if same_commands(&stored.to_vec(), &content) {
+                continue;
+            }
+            debug!(target: "cl_core::storage", path = %layer.path.display(), "saving layer");
+            match locked {
+                true => layer.storage.save_unlocked(&content.to_command_map())?,
+                false => layer.storage.save(&content.to_command_map())?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The position of the layer the command is saved to
+    fn layer_of(&self, source: Option<&Path>) -> usize {
+        let path = source
+            .filter(|source| self.layers.iter().any(|layer| layer.path == *source))
+            .unwrap_or(&self.personal);
+        self.layers
+            .iter()
+            .position(|layer| layer.path == path)
+            .unwrap_or_default()
+    }
+}
+
+impl Storage for LayeredStorage {
+    fn load(&self) -> Result<CommandMap<'static>> {
+        let mut commands = CommandVec::new();
+        let mut keys: HashMap<_, &Path> = HashMap::new();
+
+        for layer in &self.layers {
+            trace!(target: "cl_core::storage", path = %layer.path.display(), "loading layer");
+            for mut command in layer.load(false)?.to_vec() {
+                let key = command_key(&command);
+                if let Some(path) = keys.get(&key) {
+                    // a command that runs instead of a personal one should never go unnoticed
+                    match layer.path == self.personal {
+                        true => {
+                            warn!(target: "cl_core::storage", "'{}/{}' of {} hides your command with the same alias and namespace", command.namespace, command.alias, path.display())
+                        }
+                        false => {
+                            debug!(target: "cl_core::storage", alias = %command.alias, namespace = %command.namespace, path = %layer.path.display(), "command shadowed by {}", path.display())
+                        }
+                    }
+                    continue;
+                }
+                keys.insert(key, &layer.path);
+                command.source = Some(layer.path.clone());
+                command.read_only = layer.read_only;
+                commands.push(command);
+            }
+        }
+
+        Ok(commands.to_command_map())
+    }
+
+    fn save(&self, commands: &CommandMap) -> Result<()> {
+        self.save_layers(commands, false)
+    }
+
+    fn fingerprint(&self) -> Result<Fingerprint> {
+        self.fingerprint_layers(false)
+    }
+
+    /// All the writable layers are locked during the check and the save, so no other process
+    /// changes them in between
+    fn save_if_unchanged(
+        &self,
+        commands: &CommandMap,
+        expected: &Fingerprint,
+    ) -> Result<Fingerprint> {
+        let _locks = self.lock_writable_layers()?;
+        ensure_unchanged(&self.fingerprint_layers(true)?, expected)?;
+        self.save_layers(commands, true)?;
+        self.fingerprint_layers(true)
+    }
+}
+
+/// Aliases and namespaces are case insensitive
+fn command_key(command: &crate::Command) -> (String, String) {
+    (
+        command.namespace.to_lowercase(),
+        command.alias.to_lowercase(),
+    )
+}
+
+fn same_commands(a: &CommandVec, b: &CommandVec) -> bool {
+    let serialize = |commands: &CommandVec| -> Vec<Option<toml::Value>> {
+        commands
+            .iter()
+            .sorted_by_key(|command| command_key(command))
+            .map(|command| toml::Value::try_from(command).ok())
+            .collect()
+    };
+    serialize(a) == serialize(b)
+}
+
+/// Keeps the commands in memory only. Useful for tests
+#[derive(Default)]
+pub struct InMemoryStorage {
+    commands: Mutex<CommandMap<'static>>,
+}
+
+impl InMemoryStorage {
+    pub fn new(commands: CommandMap<'static>) -> Self {
+        Self {
+            commands: Mutex::new(commands),
+        }
+    }
+}
+
+impl Storage for InMemoryStorage {
+    fn load(&self) -> Result<CommandMap<'static>> {
+        Ok(self.lock()?.clone())
+    }
+
+    fn save(&self, commands: &CommandMap) -> Result<()> {
+        *self.lock()? = to_static(commands);
+        Ok(())
+    }
+
+    fn fingerprint(&self) -> Result<Fingerprint> {
+        in_memory_fingerprint(&*self.lock()?)
+    }
+
+    fn save_if_unchanged(
+        &self,
+        commands: &CommandMap,
+        expected: &Fingerprint,
+    ) -> Result<Fingerprint> {
+        let mut stored = self.lock()?;
+        ensure_unchanged(&in_memory_fingerprint(&stored)?, expected)?;
+        *stored = to_static(commands);
+        in_memory_fingerprint(&stored)
+    }
+}
+
+impl InMemoryStorage {
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, CommandMap<'static>>> {
+        self.commands
+            .lock()
+            .map_err(|_| anyhow::anyhow!("The in-memory storage is poisoned"))
+    }
+}
+
+fn to_static(commands: &CommandMap) -> CommandMap<'static> {
+    commands
+        .iter()
+        .map(|(namespace, commands)| {
+            let commands: CommandVec<'static> = commands.iter().map(|c| c.to_static()).collect();
+            (namespace.to_owned(), commands)
+        })
+        .collect()
+}
+
+fn in_memory_fingerprint(commands: &CommandMap) -> Result<Fingerprint> {
+    let toml = Toml::from_map(commands)?;
+    Ok(Fingerprint::new(toml.as_bytes()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CommandBuilder, CommandMapExt, CommandVecExt};
+    use tempfile::TempDir;
+
+    fn commands() -> CommandMap<'static> {
+        vec![
+            CommandBuilder::default()
+                .alias("status")
+                .namespace("git")
+                .command("git status")
+                .build(),
+            CommandBuilder::default()
+                .alias("ps")
+                .namespace("docker")
+                .command("docker ps")
+                .build(),
+        ]
+        .to_command_map()
+    }
+
+    #[test]
+    fn should_save_and_load_the_commands_from_a_toml_file() -> Result<()> {
+        let dir = TempDir::new()?;
+        let storage = TomlFileStorage::new(dir.path().join("commands.toml"));
+
+        storage.save(&commands())?;
+
+        assert_eq!(
+            storage.load()?.to_vec().sorted(),
+            commands().to_vec().sorted()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_save_each_namespace_in_its_own_file() -> Result<()> {
+        let dir = TempDir::new()?;
+        let storage = NamespaceDirStorage::new(dir.path().join("commands"));
+        assert!(storage.load()?.is_empty());
+
+        storage.save(&commands())?;
+        assert!(dir.path().join("commands/git.toml").is_file());
+        assert!(dir.path().join("commands/docker.toml").is_file());
+        assert_eq!(
+            storage.load()?.to_vec().sorted(),
+            commands().to_vec().sorted()
+        );
+
+        let mut without_docker = commands();
+        without_docker.remove("docker");
+        storage.save(&without_docker)?;
+        assert!(!dir.path().join("commands/docker.toml").exists());
+        assert_eq!(storage.load()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_save_a_namespace_that_is_not_a_valid_file_name() -> Result<()> {
+        let dir = TempDir::new()?;
+        let storage = NamespaceDirStorage::new(dir.path().join("commands"));
+        let command = CommandBuilder::default()
+            .alias("alias")
+            .namespace("../escape")
+            .command("echo")
+            .build();
+
+        let result = storage.save(&vec![command].to_command_map());
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            FileError::InvalidNamespaceFile {
+                namespace: "../escape".to_owned()
+            }
+            .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_back_up_the_commands_before_replacing_them() -> Result<()> {
+        let dir = TempDir::new()?;
+        let backups = Backups::new(dir.path(), 5);
+        let storage = BackedUpStorage::new(Box::new(InMemoryStorage::default()), backups);
+
+        storage.save(&commands())?;
+        storage.save(&CommandMap::new())?;
+
+        let backups = Backups::new(dir.path(), 5);
+        let list = backups.list()?;
+        assert_eq!(list.len(), 1);
+        assert_eq!(backups.load(&list[0])?.to_vec().len(), 2);
+        assert!(storage.load()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_overwrite_the_changes_of_other_processes() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.toml");
+        let storage = TomlFileStorage::new(&path);
+        let other_process = TomlFileStorage::new(&path);
+
+        storage.save(&commands())?;
+        let fingerprint = storage.fingerprint()?;
+        other_process.save(&CommandMap::new())?;
+
+        let result = storage.save_if_unchanged(&commands(), &fingerprint);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            FileError::ChangedByAnotherProcess.to_string()
+        );
+        assert!(storage.load()?.is_empty());
+
+        let fingerprint = storage.fingerprint()?;
+        let saved = storage.save_if_unchanged(&commands(), &fingerprint)?;
+        assert_eq!(saved, storage.fingerprint()?);
+        assert_eq!(storage.load()?.to_vec().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_detect_the_changes_of_the_namespace_dir() -> Result<()> {
+        let dir = TempDir::new()?;
+        let storage = NamespaceDirStorage::new(dir.path().join("commands"));
+
+        let fingerprint = storage.fingerprint()?;
+        storage.save(&commands())?;
+
+        assert!(storage
+            .save_if_unchanged(&CommandMap::new(), &fingerprint)
+            .is_err());
+        assert_eq!(storage.load()?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_layer_the_commands_of_many_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        let command = |alias: &str, namespace: &str, command: &str| {
+            CommandBuilder::default()
+                .alias(alias)
+                .namespace(namespace)
+                .command(command)
+                .build()
+        };
+        let personal = dir.path().join("personal.toml");
+        let team = dir.path().join("team.toml");
+        let project = dir.path().join(".cl.toml");
+        save_at(
+            &vec![command("status", "git", "git status -sb")].to_command_map(),
+            &personal,
+        )?;
+        save_at(
+            &vec![
+                command("status", "git", "git status"),
+                command("ps", "docker", "docker ps"),
+            ]
+            .to_command_map(),
+            &team,
+        )?;
+        let storage = LayeredStorage::new(Layer::toml(&personal))
+            .under(Layer::toml(&team))
+            .over(Layer::toml(&project));
+
+        let mut commands = storage.load()?.to_vec().sorted();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[1].command, "git status -sb");
+        assert_eq!(commands[1].source.as_deref(), Some(personal.as_path()));
+        assert_eq!(commands[0].source.as_deref(), Some(team.as_path()));
+
+        commands[0].command = "docker ps -a".into();
+        commands.push(command("build", "cargo", "cargo build"));
+        storage.save(&commands.to_command_map())?;
+
+        let team_commands = load_from(&team)?.to_vec().sorted();
+        assert_eq!(team_commands.len(), 2);
+        assert_eq!(team_commands[0].command, "docker ps -a");
+        assert_eq!(team_commands[1].command, "git status");
+        assert_eq!(load_from(&personal)?.to_vec().len(), 2);
+        assert!(!project.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_save_the_read_only_layers() -> Result<()> {
+        let dir = TempDir::new()?;
+        let personal = dir.path().join("personal.toml");
+        let team = dir.path().join("team.toml");
+        let ps = CommandBuilder::default()
+            .alias("ps")
+            .namespace("docker")
+            .command("docker ps")
+            .build();
+        save_at(&vec![ps].to_command_map(), &team)?;
+        let storage =
+            LayeredStorage::new(Layer::toml(&personal)).under(Layer::toml(&team).read_only());
+
+        let mut commands = storage.load()?.to_vec();
+        assert!(commands[0].read_only);
+
+        // a forked command
+        commands[0].read_only = false;
+        commands[0].source = None;
+        commands[0].command = "docker ps -a".into();
+        storage.save(&commands.to_command_map())?;
+
+        assert_eq!(load_from(&team)?.to_vec()[0].command, "docker ps");
+        assert_eq!(load_from(&personal)?.to_vec()[0].command, "docker ps -a");
+        let commands = storage.load()?.to_vec();
+        assert_eq!(commands.len(), 1);
+        assert!(!commands[0].read_only);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_lock_the_read_only_layers() -> Result<()> {
+        let dir = TempDir::new()?;
+        let personal = dir.path().join("personal.toml");
+        let team = dir.path().join("team.toml");
+        save_at(&commands(), &team)?;
+        let storage =
+            LayeredStorage::new(Layer::toml(&personal)).under(Layer::toml(&team).read_only());
+
+        // e.g. the owner of the file is editing it
+        let _lock = lock(&team, LockKind::Exclusive)?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(storage.load().map(|commands| commands.to_vec().len()));
+        });
+
+        let loaded = receiver.recv_timeout(std::time::Duration::from_secs(5))?;
+        assert_eq!(loaded?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_save_the_layers_when_one_of_them_changed() -> Result<()> {
+        let dir = TempDir::new()?;
+        let personal = dir.path().join("personal.toml");
+        let project = dir.path().join(".cl.toml");
+        save_at(&commands(), &personal)?;
+        let storage = LayeredStorage::new(Layer::toml(&personal)).over(Layer::toml(&project));
+        let expected = storage.fingerprint()?;
+
+        // e.g. `cl add` in the project while the GUI is open
+        let added = CommandBuilder::default()
+            .alias("build")
+            .namespace("cargo")
+            .command("cargo build")
+            .build();
+        TomlFileStorage::new(&project).save(&vec![added].to_command_map())?;
+        let result = storage.save_if_unchanged(&CommandMap::new(), &expected);
+
+        assert!(matches!(
+            result.err().map(|err| err.downcast::<FileError>()),
+            Some(Ok(FileError::ChangedByAnotherProcess))
+        ));
+        assert_eq!(load_from(&personal)?.to_vec().len(), 2);
+        assert_eq!(load_from(&project)?.to_vec().len(), 1);
+
+        Ok(())
+    }
+
+    /// Records whether `other` could be locked while the commands are saved
+    struct LockProbe {
+        inner: TomlFileStorage,
+        other: PathBuf,
+        other_locked: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    impl Storage for LockProbe {
+        fn load(&self) -> Result<CommandMap<'static>> {
+            self.inner.load()
+        }
+
+        fn save(&self, commands: &CommandMap) -> Result<()> {
+            self.inner.save(commands)
+        }
+
+        fn fingerprint(&self) -> Result<Fingerprint> {
+            self.inner.fingerprint()
+        }
+
+        fn load_unlocked(&self) -> Result<CommandMap<'static>> {
+            self.inner.load_unlocked()
+        }
+
+        fn fingerprint_unlocked(&self) -> Result<Fingerprint> {
+            self.inner.fingerprint_unlocked()
+        }
+
+        fn save_unlocked(&self, commands: &CommandMap) -> Result<()> {
+            let other = self.other.clone();
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _lock = lock(&other, LockKind::Exclusive);
+                let _ = sender.send(());
+            });
+            let locked = receiver
+                .recv_timeout(std::time::Duration::from_millis(200))
+                .is_err();
+            self.other_locked
+                .store(locked, std::sync::atomic::Ordering::SeqCst);
+            self.inner.save_unlocked(commands)
+        }
+
+        fn lock_exclusive(&self) -> Result<Option<FileLock>> {
+            self.inner.lock_exclusive()
+        }
+
+        fn save_if_unchanged(
+            &self,
+            commands: &CommandMap,
+            expected: &Fingerprint,
+        ) -> Result<Fingerprint> {
+            self.inner.save_if_unchanged(commands, expected)
+        }
+    }
+
+    #[test]
+    fn should_lock_all_the_writable_layers_while_saving() -> Result<()> {
+        let dir = TempDir::new()?;
+        let personal = dir.path().join("personal.toml");
+        let project = dir.path().join(".cl.toml");
+        let other_locked = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let probe = LockProbe {
+            inner: TomlFileStorage::new(&personal),
+            other: project.clone(),
+            other_locked: other_locked.clone(),
+        };
+        let storage =
+            LayeredStorage::new(Layer::new(&personal, Box::new(probe))).over(Layer::toml(&project));
+        let expected = storage.fingerprint()?;
+
+        storage.save_if_unchanged(&commands(), &expected)?;
+
+        assert!(other_locked.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(storage.load()?.to_vec().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_the_commands_in_memory() -> Result<()> {
+        let storage = InMemoryStorage::default();
+        assert!(storage.load()?.is_empty());
+
+        storage.save(&commands())?;
+
+        assert_eq!(storage.load()?.to_vec().len(), 2);
+
+        Ok(())
+    }
+}
@@ -1,22 +1,237 @@
 use super::errors::FileError;
-use crate::CommandMap;
+use crate::{Command, CommandMap};
 use anyhow::Result;
+use serde::Serialize;
 use std::{fs::read_to_string, path::Path};
+use toml::{Table, Value};
+use tracing::debug;
+
+/// The version of the commands files written by this binary
+pub const SCHEMA_VERSION: i64 = 1;
+/// The top-level key of the schema version. The files without it are at version 0
+pub(super) const VERSION_KEY: &str = "schema-version";
+
+/// Upgrades a table of commands to the next schema version
+type Migration = fn(&mut Table) -> Result<()>;
+
+/// The nth migration upgrades the files at version n
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [v0_to_v1];
 
 pub struct Toml;
 
+/// The commands with the schema version, keeping the order of the commands fields
+#[derive(Serialize)]
+pub(super) struct Versioned<'a, 'cmd> {
+    #[serde(rename = "schema-version")]
+    version: i64,
+    #[serde(flatten)]
+    commands: &'a CommandMap<'cmd>,
+}
+
+impl<'a, 'cmd> Versioned<'a, 'cmd> {
+    /// The commands at the current schema version, unless a namespace takes the key of the version
+    pub(super) fn new(commands: &'a CommandMap<'cmd>) -> Result<Self, FileError> {
+        if commands.contains_key(VERSION_KEY) {
+            return Err(FileError::ReservedNamespace {
+                namespace: VERSION_KEY.to_owned(),
+            });
+        }
+
+        Ok(Self {
+            version: SCHEMA_VERSION,
+            commands,
+        })
+    }
+}
+
 impl Toml {
+    /// Loads the commands of a file, upgrading them if the file has an older schema version
     pub fn from_file<'f, P>(path: P) -> Result<CommandMap<'f>>
     where
         P: AsRef<Path>,
     {
+        let path = path.as_ref();
         let string_data = read_to_string(path)?;
-        let commands = toml::from_str::<CommandMap>(&string_data)?;
+        let mut table = toml::from_str::<Table>(&string_data)?;
 
+        let version = version_of(&table);
+        if version > SCHEMA_VERSION {
+            return Err(FileError::NewerSchemaVersion {
+                path: path.to_path_buf(),
+                version,
+                supported: SCHEMA_VERSION,
+            }
+            .into());
+        }
+        if version < SCHEMA_VERSION {
+            debug!(target: "cl_core::toml", path = %path.display(), version, "upgrading the commands");
+            migrate(&mut table, version, &MIGRATIONS)?;
+        }
+        table.remove(VERSION_KEY);
+
+        let commands = Value::Table(table).try_into::<CommandMap>()?;
         Ok(commands)
     }
 
     pub fn from_map(commands: &CommandMap) -> Result<String, FileError> {
-        toml::to_string(&commands).map_err(FileError::from)
+        let versioned = Versioned::new(commands)?;
+        toml::to_string(&versioned).map_err(FileError::from)
+    }
+
+    /// A single command as a TOML entry, e.g. to be changed in an editor
+    pub fn from_command(command: &Command) -> Result<String, FileError> {
+        toml::to_string(command).map_err(FileError::from)
+    }
+
+    /// Parses a single command entry, as written by [`Toml::from_command`]
+    pub fn to_command(entry: &str) -> Result<Command<'static>> {
+        Ok(toml::from_str(entry)?)
+    }
+
+    /// The schema version of an existing file, or `None` if it is missing or cannot be parsed
+    pub fn version_of_file<P>(path: P) -> Option<i64>
+    where
+        P: AsRef<Path>,
+    {
+        let string_data = read_to_string(path).ok()?;
+        let table = toml::from_str::<Table>(&string_data).ok()?;
+        Some(version_of(&table))
+    }
+}
+
+/// Only an integer is a version, as any other value would be a namespace
+fn version_of(table: &Table) -> i64 {
+    match table.get(VERSION_KEY) {
+        Some(Value::Integer(version)) => (*version).max(0),
+        _ => 0,
+    }
+}
+
+/// Runs the migrations from `version` up to the last one
+fn migrate(table: &mut Table, version: i64, migrations: &[Migration]) -> Result<()> {
+    for (from, migration) in migrations.iter().enumerate().skip(version as usize) {
+        debug!(target: "cl_core::toml", from, to = from + 1, "running migration");
+        migration(table)?;
+        table.insert(VERSION_KEY.to_owned(), Value::Integer(from as i64 + 1));
+    }
+
+    Ok(())
+}
+
+/// The version 1 only adds the schema version
+fn v0_to_v1(_: &mut Table) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CommandBuilder, CommandMapExt, CommandVecExt};
+    use tempfile::TempDir;
+
+    #[test]
+    fn should_write_the_schema_version() -> Result<()> {
+        let commands = vec![CommandBuilder::default()
+            .alias("status")
+            .namespace("git")
+            .command("git status")
+            .build()]
+        .to_command_map();
+
+        let toml = Toml::from_map(&commands)?;
+
+        assert!(toml.starts_with(&format!("{VERSION_KEY} = {SCHEMA_VERSION}\n")));
+        assert!(toml.contains("alias = \"status\"\nnamespace = \"git\"\ncommand = \"git status\""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_and_parse_a_single_command() -> Result<()> {
+        let command = CommandBuilder::default()
+            .alias("log")
+            .namespace("git")
+            .command("git log \\\n  --oneline")
+            .tags(Some(["vcs"]))
+            .build();
+
+        let entry = Toml::from_command(&command)?;
+        let parsed = Toml::to_command(&entry)?;
+
+        assert!(entry.starts_with("alias = \"log\"\nnamespace = \"git\"\n"));
+        assert_eq!(parsed, command);
+        assert_eq!(parsed.command, command.command);
+        assert_eq!(parsed.tags, command.tags);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_load_a_file_without_schema_version() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.toml");
+        std::fs::write(
+            &path,
+            "[[git]]\nalias = \"status\"\nnamespace = \"git\"\ncommand = \"git status\"\n",
+        )?;
+
+        let commands = Toml::from_file(&path)?.to_vec();
+
+        assert_eq!(commands.len(), 1);
+        assert_eq!(Toml::version_of_file(&path), Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_an_error_when_the_file_is_newer() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.toml");
+        std::fs::write(&path, format!("{VERSION_KEY} = {}\n", SCHEMA_VERSION + 1))?;
+
+        let result = Toml::from_file(&path);
+
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some(
+                FileError::NewerSchemaVersion {
+                    path,
+                    version: SCHEMA_VERSION + 1,
+                    supported: SCHEMA_VERSION,
+                }
+                .to_string()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_the_migrations_from_the_version_of_the_file() -> Result<()> {
+        let migrations: [Migration; 3] = [
+            |table| {
+                table.insert("first".to_owned(), Value::Boolean(true));
+                Ok(())
+            },
+            |table| {
+                table.insert("second".to_owned(), Value::Boolean(true));
+                Ok(())
+            },
+            |table| {
+                table.insert("third".to_owned(), Value::Boolean(true));
+                Ok(())
+            },
+        ];
+        let mut table = Table::new();
+        table.insert(VERSION_KEY.to_owned(), Value::Integer(1));
+
+        migrate(&mut table, 1, &migrations)?;
+
+        assert!(!table.contains_key("first"));
+        assert!(table.contains_key("second"));
+        assert!(table.contains_key("third"));
+        assert_eq!(version_of(&table), 3);
+
+        Ok(())
     }
 }
@@ -0,0 +1,92 @@
+use super::errors::FileError;
+use super::toml::{Versioned, SCHEMA_VERSION, VERSION_KEY};
+use crate::CommandMap;
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+use std::{fs::read_to_string, path::Path};
+
+/// The commands as a YAML mapping of namespaces, as in the TOML files
+///
+/// Only the TOML files are migrated: the YAML ones are read as the current schema version, unless
+/// they were written by a newer one
+pub struct Yaml;
+
+impl Yaml {
+    pub fn from_file<'f, P>(path: P) -> Result<CommandMap<'f>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let string_data = read_to_string(path)?;
+        // an empty document has no commands
+        let mut mapping =
+            serde_yaml::from_str::<Option<Mapping>>(&string_data)?.unwrap_or_default();
+
+        let version = mapping
+            .remove(VERSION_KEY)
+            .and_then(|version| version.as_i64())
+            .unwrap_or_default();
+        if version > SCHEMA_VERSION {
+            return Err(FileError::NewerSchemaVersion {
+                path: path.to_path_buf(),
+                version,
+                supported: SCHEMA_VERSION,
+            }
+            .into());
+        }
+
+        let commands = serde_yaml::from_value::<CommandMap>(Value::Mapping(mapping))?;
+        Ok(commands)
+    }
+
+    pub fn from_map(commands: &CommandMap) -> Result<String, FileError> {
+        let versioned = Versioned::new(commands)?;
+        serde_yaml::to_string(&versioned).map_err(FileError::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CommandBuilder, CommandMapExt, CommandVecExt};
+    use tempfile::TempDir;
+
+    #[test]
+    fn should_write_and_load_the_commands() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.yaml");
+        let command = CommandBuilder::default()
+            .alias("log")
+            .namespace("git")
+            .command("git log \\\n  --oneline")
+            .description(Some("The short log"))
+            .build();
+
+        let yaml = Yaml::from_map(&vec![command.clone()].to_command_map())?;
+        std::fs::write(&path, &yaml)?;
+        let loaded = Yaml::from_file(&path)?.to_vec();
+
+        assert!(yaml.starts_with(&format!("{VERSION_KEY}: {SCHEMA_VERSION}\n")));
+        assert_eq!(loaded, vec![command.clone()]);
+        assert_eq!(loaded[0].command, command.command);
+        assert_eq!(loaded[0].description, command.description);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_return_an_error_when_the_file_is_newer() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("commands.yml");
+        std::fs::write(&path, format!("{VERSION_KEY}: {}\n", SCHEMA_VERSION + 1))?;
+
+        let result = Yaml::from_file(&path);
+
+        assert!(matches!(
+            result.err().map(|err| err.downcast::<FileError>()),
+            Some(Ok(FileError::NewerSchemaVersion { .. }))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const DEFAULT_RETRIES: u32 = 3;
+
+/// What a pipeline does when one of its steps fails
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FailurePolicy {
+    /// Stops the pipeline, failing with the status of the step
+    #[default]
+    Stop,
+    /// Ignores the failure and runs the next step
+    Continue,
+    /// Runs the step again, up to `retries` times, and then stops the pipeline
+    Retry,
+}
+
+/// A single step of a pipeline command
+///
+/// Either runs a command line (`run`) or references another command by its alias
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Step {
+    /// The command line to run. Supports the `#{name}` placeholders of the pipeline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<String>,
+    /// The alias of the command to run as this step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// The namespace of the referenced command, in case of duplicate aliases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// What to do if the step fails. Defaults to `stop`
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub on_failure: FailurePolicy,
+    /// How many times the step is retried with the `retry` policy. Defaults to 3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// The shell that runs the step. Defaults to the pipeline's one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// The directory the step runs from. Defaults to the pipeline's one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Environment variables added to the pipeline's ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+}
+
+fn is_default(policy: &FailurePolicy) -> bool {
+    *policy == FailurePolicy::default()
+}
+
+impl Step {
+    pub fn run(command: impl Into<String>) -> Step {
+        Self {
+            run: Some(command.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn reference(alias: impl Into<String>, namespace: Option<String>) -> Step {
+        Self {
+            alias: Some(alias.into()),
+            namespace,
+            ..Default::default()
+        }
+    }
+
+    /// Checks if the step references another command instead of running its own command line
+    pub fn is_reference(&self) -> bool {
+        self.run.is_none() && self.alias.is_some()
+    }
+
+    /// How many times the step runs at most
+    pub fn attempts(&self) -> u32 {
+        match self.on_failure {
+            FailurePolicy::Retry => 1 + self.retries.unwrap_or(DEFAULT_RETRIES),
+            _ => 1,
+        }
+    }
+
+    /// A short, human readable representation of the step
+    pub fn describe(&self) -> String {
+        match (&self.run, &self.alias, &self.namespace) {
+            (Some(run), _, _) => run.to_owned(),
+            (None, Some(alias), Some(namespace)) => format!("@{namespace}.{alias}"),
+            (None, Some(alias), None) => format!("@{alias}"),
+            (None, None, _) => String::new(),
+        }
+    }
+
+    /// Checks if the step has exactly one of a command line or a reference
+    ///
+    /// ## Arguments
+    /// * `index` - The 1-based position of the step, used in the error
+    pub fn validate(&self, index: usize) -> Result<(), CommandError> {
+        let has_run = self
+            .run
+            .as_deref()
+            .is_some_and(|run| !run.trim().is_empty());
+        let has_alias = self
+            .alias
+            .as_deref()
+            .is_some_and(|alias| !alias.trim().is_empty());
+
+        if has_run == has_alias {
+            return Err(CommandError::InvalidStep { index });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_validate_the_steps() {
+        assert!(Step::run("echo").validate(1).is_ok());
+        assert!(Step::reference("alias", None).validate(1).is_ok());
+
+        let empty = Step::default();
+        assert_eq!(
+            empty.validate(2).unwrap_err().to_string(),
+            CommandError::InvalidStep { index: 2 }.to_string()
+        );
+
+        let both = Step {
+            run: Some("echo".to_owned()),
+            alias: Some("alias".to_owned()),
+            ..Default::default()
+        };
+        assert!(both.validate(1).is_err());
+    }
+
+    #[test]
+    fn should_count_the_attempts_of_a_step() {
+        let mut step = Step::run("echo");
+        assert_eq!(step.attempts(), 1);
+
+        step.on_failure = FailurePolicy::Retry;
+        assert_eq!(step.attempts(), 4);
+
+        step.retries = Some(1);
+        assert_eq!(step.attempts(), 2);
+    }
+
+    #[test]
+    fn should_parse_steps_from_toml() {
+        let toml = r#"
+            run = "cargo test"
+            on_failure = "retry"
+            retries = 2
+        "#;
+
+        let step: Step = toml::from_str(toml).unwrap();
+
+        assert_eq!(step.run.as_deref(), Some("cargo test"));
+        assert_eq!(step.on_failure, FailurePolicy::Retry);
+        assert_eq!(step.retries, Some(2));
+    }
+}
@@ -10,6 +10,8 @@ use tui_textarea::{CursorMove, TextArea};
 #[derive(Default, Debug)]
 pub struct EditableTextbox {
     pub name: FieldName,
+    /// Title shown instead of the field name. Not a required field
+    pub title: Option<String>,
     pub textarea: TextArea<'static>,
     pub active: bool,
     pub modified: bool,
@@ -51,10 +53,13 @@ impl Renderable for EditableTextbox {
                     .fg(theme.text_color.into())
                     .bg(theme.background_color.into()),
             )
-            .title(if self.modified {
-                format!("{} *", self.name)
-            } else {
-                self.name.to_string()
+            .title({
+                let title = self.title.clone().unwrap_or_else(|| self.name.to_string());
+                if self.modified {
+                    format!("{title} *")
+                } else {
+                    title
+                }
             });
         self.textarea.set_block(block);
 
@@ -70,6 +75,13 @@ impl Renderable for EditableTextbox {
     }
 }
 
+impl crate::observer::event::NotifyTarget for EditableTextbox {
+    type Payload = crate::observer::event::EditableTextboxEvent;
+    fn wrap(payload: Self::Payload) -> crate::observer::event::Event {
+        crate::observer::event::Event::EditableTextbox(payload)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,8 +97,10 @@ mod tests {
 
     #[test]
     fn update_content_resets_modified_flag() {
-        let mut tb = EditableTextbox::default();
-        tb.modified = true;
+        let mut tb = EditableTextbox {
+            modified: true,
+            ..Default::default()
+        };
         tb.update_content(Some("value"));
         assert!(!tb.modified);
     }
@@ -100,10 +114,3 @@ mod tests {
         assert!(lines.is_empty());
     }
 }
-
-impl crate::observer::event::NotifyTarget for EditableTextbox {
-    type Payload = crate::observer::event::EditableTextboxEvent;
-    fn wrap(payload: Self::Payload) -> crate::observer::event::Event {
-        crate::observer::event::Event::EditableTextbox(payload)
-    }
-}
@@ -60,12 +60,12 @@ where
     T: Observable + Debug + Any,
 {
     /// Borrow the inner value as a type-erased `ObservableComponent`.
-    pub fn as_observable(&self) -> Ref<dyn ObservableComponent> {
+    pub fn as_observable(&self) -> Ref<'_, dyn ObservableComponent> {
         self.0.borrow()
     }
 
     /// Mutably borrow the inner value as a type-erased `ObservableComponent`.
-    pub fn as_observable_mut(&self) -> RefMut<dyn ObservableComponent> {
+    pub fn as_observable_mut(&self) -> RefMut<'_, dyn ObservableComponent> {
         self.0.borrow_mut()
     }
 
@@ -74,12 +74,12 @@ where
     /// Prefer this over [`borrow`](Self::borrow) + downcast whenever the
     /// concrete type is known (e.g. reading `ScreenState` fields from
     /// `FormScreenLayer`).
-    pub fn borrow_inner(&self) -> Ref<T> {
+    pub fn borrow_inner(&self) -> Ref<'_, T> {
         self.0.borrow()
     }
 
     /// Mutably borrow the inner `T` directly, with no downcast required.
-    pub fn borrow_inner_mut(&self) -> RefMut<T> {
+    pub fn borrow_inner_mut(&self) -> RefMut<'_, T> {
         self.0.borrow_mut()
     }
 
@@ -90,7 +90,7 @@ where
     }
 
     /// Returns a `RefMut<dyn Observable>` for the inner value.
-    pub fn get_observable_mut(&self) -> RefMut<dyn Observable> {
+    pub fn get_observable_mut(&self) -> RefMut<'_, dyn Observable> {
         RefMut::map(self.0.borrow_mut(), |inner| inner as &mut dyn Observable)
     }
 }
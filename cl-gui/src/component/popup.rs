@@ -3,6 +3,7 @@ use crate::component::table::{Cell, CustomWidth, Row, Table};
 use crate::component::Renderable;
 use crate::screen::command::ScreenCommandCallback;
 use crate::screen::theme::Theme;
+use crate::state::state_event::StateEvent::{MergeCommands, ReloadCommands};
 use std::fmt::Debug;
 use std::rc::Rc;
 use tui::layout::Alignment::Center;
@@ -87,6 +88,38 @@ impl Popup {
         }
     }
 
+    pub fn conflict() -> Self {
+        Popup {
+            title: "Conflict".to_string(),
+            content: "The commands were changed by another process.\n\
+                Merge your changes with them or discard your changes and reload them?"
+                .to_string(),
+            buttons: vec![
+                Button::new(
+                    "Merge",
+                    true,
+                    FutureEventType::State(|state| {
+                        async_fn_body! {
+                            oneshot!(state, MergeCommands)?.map_err(|e| anyhow::anyhow!(e))
+                        }
+                    }),
+                    ScreenCommandCallback::UpdateAll,
+                ),
+                Button::new(
+                    "Reload",
+                    false,
+                    FutureEventType::State(|state| {
+                        async_fn_body! {
+                            oneshot!(state, ReloadCommands)?.map_err(|e| anyhow::anyhow!(e))
+                        }
+                    }),
+                    ScreenCommandCallback::UpdateAll,
+                ),
+            ],
+            ..Default::default()
+        }
+    }
+
     pub fn help_main() -> Self {
         Popup {
             title: "Help".to_string(),
@@ -251,6 +284,11 @@ fn main_options() -> Table {
         ]),
         Row::from_iter([Cell::from("Move up"), Cell::from("<K/↑>")]),
         Row::from_iter([Cell::from("Move down"), Cell::from("<J/↓>")]),
+        Row::from_iter([Cell::from("Run selected command"), Cell::from("<Enter>")]),
+        Row::from_iter([
+            Cell::from("Run selected command with arguments"),
+            Cell::from("<R>"),
+        ]),
         Row::from_iter([Cell::from("Copy selected command"), Cell::from("<Y>")]),
         Row::from_iter([Cell::from("Search commands"), Cell::from("<F//>")]),
         Row::from_iter([Cell::from("Close search"), Cell::from("<Esc/Enter/↑/↓>")]),
@@ -262,6 +300,7 @@ fn main_options() -> Table {
 fn form_options() -> Table {
     vec![
         Row::from_iter([Cell::from("Save"), Cell::from("<Ctrl-S>")]),
+        Row::from_iter([Cell::from("Edit in $EDITOR"), Cell::from("<Ctrl-O>")]),
         Row::from_iter([Cell::from("Cancel / exit"), Cell::from("<Esc/Ctrl-C>")]),
         Row::from_iter([Cell::from("Next field"), Cell::from("<Tab>")]),
         Row::from_iter([Cell::from("Previous field"), Cell::from("<Shift-Tab>")]),
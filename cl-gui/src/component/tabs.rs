@@ -183,6 +183,13 @@ impl Renderable for Tabs {
     }
 }
 
+impl crate::observer::event::NotifyTarget for Tabs {
+    type Payload = crate::observer::event::TabsEvent;
+    fn wrap(payload: Self::Payload) -> crate::observer::event::Event {
+        crate::observer::event::Event::Tabs(payload)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,10 +229,3 @@ mod tests {
         assert!(items[0].len() <= 15);
     }
 }
-
-impl crate::observer::event::NotifyTarget for Tabs {
-    type Payload = crate::observer::event::TabsEvent;
-    fn wrap(payload: Self::Payload) -> crate::observer::event::Event {
-        crate::observer::event::Event::Tabs(payload)
-    }
-}
@@ -45,6 +45,13 @@ impl Renderable for TextBox {
     }
 }
 
+impl crate::observer::event::NotifyTarget for TextBox {
+    type Payload = crate::observer::event::TextBoxEvent;
+    fn wrap(payload: Self::Payload) -> crate::observer::event::Event {
+        crate::observer::event::Event::TextBox(payload)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,10 +93,3 @@ mod tests {
         assert_eq!(tb.content, None);
     }
 }
-
-impl crate::observer::event::NotifyTarget for TextBox {
-    type Payload = crate::observer::event::TextBoxEvent;
-    fn wrap(payload: Self::Payload) -> crate::observer::event::Event {
-        crate::observer::event::Event::TextBox(payload)
-    }
-}
@@ -44,14 +44,14 @@ pub enum TabsEvent {
 /// Events handled by read-only [`TextBox`](crate::component::TextBox) components.
 #[derive(Clone, Debug)]
 pub enum TextBoxEvent {
-    UpdateCommand(Command<'static>),
+    UpdateCommand(Box<Command<'static>>),
     UpdateContent(String),
 }
 
 /// Events handled by [`EditableTextbox`](crate::component::EditableTextbox) components.
 #[derive(Clone, Debug)]
 pub enum EditableTextboxEvent {
-    UpdateCommand(Command<'static>),
+    UpdateCommand(Box<Command<'static>>),
     KeyInput(crossterm::event::KeyEvent),
     GetFieldContent(Sender<StateEvent>),
     SetField(FieldName),
@@ -87,6 +87,8 @@ pub enum PopupEvent {
 pub enum PopupType {
     Dialog(String, FutureEventType, ScreenCommandCallback),
     Help(ActiveScreen),
+    /// Asks how to solve a conflict with the changes made by another process
+    Conflict,
 }
 
 // ---------------------------------------------------------------------------
@@ -1,6 +1,6 @@
 use crate::component::EditableTextbox;
 use crate::observer::event::{EditableTextboxEvent, Event};
-use crate::observer::observable::textbox_observable::cow_some_or_none;
+use crate::observer::observable::textbox_observable::{cow_some_or_none, env_lines};
 use crate::observer::observable::{Observable, ObservableFuture};
 use crate::state::state_event::FieldName;
 use crate::state::state_event::StateEvent::EditField;
@@ -20,6 +20,10 @@ impl Observable for EditableTextbox {
                             .map(|v| v.iter().map(|c| c.as_ref()).collect::<Vec<_>>().join(", ")),
                         FieldName::Namespace => cow_some_or_none(command.namespace),
                         FieldName::Alias => cow_some_or_none(command.alias),
+                        FieldName::Cwd => command.cwd.map(|c| c.to_string()),
+                        FieldName::Env => command.env.as_ref().map(env_lines),
+                        // filled by the run dialog itself, not by the command fields
+                        FieldName::Parameter(_) | FieldName::Arguments => return None,
                     };
                     self.update_content(content);
                 }
@@ -148,8 +152,15 @@ mod tests {
             command: "echo hi".into(),
             description: None,
             tags: None,
+            parameters: None,
+            shell: None,
+            cwd: None,
+            env: None,
+            steps: None,
+            source: None,
+            read_only: false,
         };
-        let event = Event::EditableTextbox(EditableTextboxEvent::UpdateCommand(cmd));
+        let event = Event::EditableTextbox(EditableTextboxEvent::UpdateCommand(Box::new(cmd)));
         if let Some(fut) = tb.on_listen(event) {
             fut.await;
         }
@@ -16,6 +16,7 @@ impl Observable for Popup {
                         ActiveScreen::Main => *self = Popup::help_main(),
                         ActiveScreen::Form => *self = Popup::help_form(),
                     },
+                    PopupType::Conflict => *self = Popup::conflict(),
                 },
                 PopupEvent::NextChoice => self.next(),
                 PopupEvent::PreviousChoice => self.previous(),
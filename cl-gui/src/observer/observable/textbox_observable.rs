@@ -2,7 +2,9 @@ use crate::component::TextBox;
 use crate::observer::event::{Event, TextBoxEvent};
 use crate::observer::observable::SyncObservable;
 use crate::state::state_event::FieldName;
+use cl_core::{Command, FailurePolicy};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use tracing::debug;
 
 impl SyncObservable for TextBox {
@@ -11,8 +13,8 @@ impl SyncObservable for TextBox {
             Event::TextBox(e) => match e {
                 TextBoxEvent::UpdateCommand(command) => {
                     let content = match self.name {
-                        FieldName::Command => cow_some_or_none(command.command),
-                        FieldName::Description => command.description.map(|desc| desc.to_string()),
+                        FieldName::Command => cow_some_or_none(command.script()),
+                        FieldName::Description => describe(&command),
                         FieldName::Tags => command.tags.map(|vec| {
                             vec.iter()
                                 .map(|cow| cow.as_ref())
@@ -21,6 +23,9 @@ impl SyncObservable for TextBox {
                         }),
                         FieldName::Namespace => cow_some_or_none(command.namespace),
                         FieldName::Alias => cow_some_or_none(command.alias),
+                        FieldName::Cwd => command.cwd.map(|c| c.to_string()),
+                        FieldName::Env => command.env.as_ref().map(env_lines),
+                        FieldName::Parameter(_) | FieldName::Arguments => None,
                     };
                     self.update_content(content);
                 }
@@ -40,6 +45,67 @@ impl SyncObservable for TextBox {
     }
 }
 
+/// Description of the command followed by its named parameters declarations, failure policies of its steps,
+/// working directory and environment
+fn describe(command: &Command) -> Option<String> {
+    let mut sections = vec![command.description()];
+
+    let parameters = command.parameters.as_deref().unwrap_or_default();
+    if !parameters.is_empty() {
+        let parameters = parameters
+            .iter()
+            .map(|p| format!("  - {}", p.summary()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("Parameters:\n{parameters}"));
+    }
+
+    let policies = command
+        .steps()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, step)| {
+            let policy = match step.on_failure {
+                FailurePolicy::Stop => return None,
+                FailurePolicy::Continue => "continue".to_owned(),
+                FailurePolicy::Retry => format!("retry {} times", step.attempts() - 1),
+            };
+            Some(format!("  {}. {}: {policy}", idx + 1, step.describe()))
+        })
+        .collect::<Vec<_>>();
+    if !policies.is_empty() {
+        sections.push(format!("On failure:\n{}", policies.join("\n")));
+    }
+
+    if let Some(cwd) = &command.cwd {
+        sections.push(format!("Working directory: {cwd}"));
+    }
+
+    if let Some(env) = &command.env {
+        let env = env_lines(env)
+            .lines()
+            .map(|line| format!("  {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("Environment:\n{env}"));
+    }
+
+    let description = sections
+        .into_iter()
+        .filter(|section| !section.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!description.is_empty()).then_some(description)
+}
+
+/// The environment variables as `KEY=VALUE` lines
+pub fn env_lines(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 // ---------------------------------------------------------------------------
 // Helper: converts Cow<str> to Option<String> (None when empty).
 // Used by TextBox and EditableTextbox observables.
@@ -81,6 +147,67 @@ mod tests {
         assert_eq!(cow_some_or_none(cow), Some("hello".to_owned()));
     }
 
+    #[test]
+    fn description_textbox_shows_parameters_declarations() {
+        let command = cl_core::CommandBuilder::default()
+            .alias("deploy")
+            .namespace("ns")
+            .command("deploy #{env}")
+            .description(Some("deploys the app"))
+            .parameters(Some(vec![cl_core::Parameter {
+                name: "env".into(),
+                description: Some("target environment".into()),
+                ..Default::default()
+            }]))
+            .build();
+        let mut tb = TextBox {
+            name: FieldName::Description,
+            ..Default::default()
+        };
+        tb.on_event(Event::TextBox(TextBoxEvent::UpdateCommand(Box::new(
+            command,
+        ))));
+        assert_eq!(
+            tb.content.as_deref(),
+            Some("deploys the app\n\nParameters:\n  - env (string): target environment")
+        );
+    }
+
+    #[test]
+    fn textboxes_show_the_steps_of_a_pipeline() {
+        let command = cl_core::CommandBuilder::default()
+            .alias("ci")
+            .namespace("ns")
+            .steps(Some(vec![
+                cl_core::Step::run("cargo fmt --check"),
+                cl_core::Step {
+                    on_failure: FailurePolicy::Retry,
+                    ..cl_core::Step::reference("test", Some("rust".into()))
+                },
+            ]))
+            .build();
+        let mut command_tb = TextBox {
+            name: FieldName::Command,
+            ..Default::default()
+        };
+        let mut description_tb = TextBox {
+            name: FieldName::Description,
+            ..Default::default()
+        };
+        let event = TextBoxEvent::UpdateCommand(Box::new(command));
+        command_tb.on_event(Event::TextBox(event.clone()));
+        description_tb.on_event(Event::TextBox(event));
+
+        assert_eq!(
+            command_tb.content.as_deref(),
+            Some("cargo fmt --check\n@rust.test")
+        );
+        assert_eq!(
+            description_tb.content.as_deref(),
+            Some("On failure:\n  2. @rust.test: retry 3 times")
+        );
+    }
+
     #[test]
     fn wrong_event_variant_is_ignored() {
         let mut tb = TextBox {
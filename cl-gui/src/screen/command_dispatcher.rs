@@ -5,12 +5,12 @@ use crate::observer::event::{Event, ListEvent, PopupEvent, PopupType, TextBoxEve
 use crate::observer::observable::Observable;
 use crate::observer::subscription::SubscriptionSet;
 use crate::screen::key_mapping::command::{ScreenCommand, ScreenCommandCallback};
-use crate::screen::layer::{Layer, MainScreenLayer, PopupLayer};
+use crate::screen::layer::{FormMode, Layer, MainScreenLayer, PopupLayer};
 use crate::screen::theme::Theme;
 use crate::signal_handler::Signal::UserInt;
 use crate::signal_handler::SignalHandler;
 use crate::state::state_event::StateEvent;
-use crate::state::state_event::StateEvent::CurrentCommand;
+use crate::state::state_event::StateEvent::{CurrentCommand, HasConflict};
 use cl_core::Command;
 use std::any::TypeId;
 use std::cell::RefCell;
@@ -51,6 +51,9 @@ pub struct LayerStack {
     pub subscriptions: SubscriptionSet<TypeId, Rc<RefCell<dyn Observable>>>,
     pub layers: Vec<Box<dyn Layer>>,
     pub snapshot: NavigationSnapshot,
+    /// Set when the command of the form should be edited in the user's editor, which needs the
+    /// terminal owned by the UI actor
+    pub editor_request: Option<FormMode>,
 }
 
 impl LayerStack {
@@ -60,6 +63,7 @@ impl LayerStack {
             subscriptions,
             layers: vec![Box::new(initial)],
             snapshot: NavigationSnapshot::default(),
+            editor_request: None,
         }
     }
 
@@ -84,6 +88,11 @@ impl LayerStack {
         }
     }
 
+    /// Whether the topmost layer is the main screen.
+    pub fn is_main_on_top(&self) -> bool {
+        self.layers.last().is_some_and(|layer| layer.is_main())
+    }
+
     /// Remove all subscriptions that were registered by `layer`.
     ///
     /// Only the per-listener handles belonging to this layer are removed.
@@ -286,6 +295,10 @@ impl CommandDispatcher {
                 }
                 ScreenCommand::Form(cb) => {
                     if let Err(error_msg) = cb.handle(state_tx.clone()).await {
+                        // conflicts with other processes are solved in the popup shown by `UpdateAll`
+                        if oneshot!(state_tx, HasConflict).unwrap_or_default() {
+                            continue;
+                        }
                         layer_stack
                             .add_layer(Box::new(PopupLayer::default()), state_tx)
                             .await;
@@ -299,6 +312,9 @@ impl CommandDispatcher {
                             .await;
                     }
                 }
+                ScreenCommand::OpenEditor(mode) => {
+                    layer_stack.editor_request = Some(mode);
+                }
                 ScreenCommand::NavigateNext => {
                     Self::navigate(layer_stack, state_tx, true).await;
                 }
@@ -350,7 +366,7 @@ impl CommandDispatcher {
             layer_stack
                 .notify(
                     TypeId::of::<TextBox>(),
-                    Event::TextBox(TextBoxEvent::UpdateCommand(cmd)),
+                    Event::TextBox(TextBoxEvent::UpdateCommand(Box::new(cmd))),
                 )
                 .await;
 
@@ -451,6 +467,22 @@ mod tests {
         assert_eq!(stack.layers.len(), 1);
     }
 
+    #[tokio::test]
+    async fn main_is_on_top_only_without_other_layers() {
+        let mut stack = LayerStack::new(MainScreenLayer::default());
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        assert!(stack.is_main_on_top());
+
+        stack.add_layer(Box::new(PopupLayer::default()), &tx).await;
+        assert!(!stack.is_main_on_top());
+
+        stack.remove_last_layer();
+        stack
+            .replace_current_layer(Box::new(PopupLayer::default()), &tx)
+            .await;
+        assert!(!stack.is_main_on_top());
+    }
+
     #[tokio::test]
     async fn replace_current_layer_keeps_count() {
         let mut stack = LayerStack::new(MainScreenLayer::default());
@@ -505,6 +537,25 @@ mod tests {
         assert_eq!(stack.layers.len(), 2);
     }
 
+    #[tokio::test]
+    async fn dispatch_open_editor_requests_the_editor() {
+        let mut stack = LayerStack::new(MainScreenLayer::default());
+        let mut clipboard = None;
+        let (state_tx, _state_rx) = tokio::sync::mpsc::channel(1);
+        let (mut sig_handler, _sig_rx) = SignalHandler::create();
+
+        CommandDispatcher::dispatch(
+            vec![ScreenCommand::OpenEditor(FormMode::Insert)],
+            &mut stack,
+            &mut clipboard,
+            &state_tx,
+            &mut sig_handler,
+        )
+        .await;
+
+        assert_eq!(stack.editor_request, Some(FormMode::Insert));
+    }
+
     #[tokio::test]
     async fn dispatch_pop_last_layer_pops() {
         let mut stack = LayerStack::new(MainScreenLayer::default());
@@ -1,20 +1,38 @@
 use crate::screen::layer::FormMode;
 use crate::state::state_event::StateEvent;
+use cl_core::Command;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 use tracing::{debug, error};
 
 pub enum FormCallback {
     Save(FormMode),
+    /// Saves the command changed outside of the form, e.g. in an editor
+    SaveCommand(FormMode, Box<Command<'static>>),
 }
 
 impl FormCallback {
     pub async fn handle(self, state_tx: Sender<StateEvent>) -> Result<(), String> {
         let (tx, rx) = oneshot::channel();
 
+        let operation = match &self {
+            FormCallback::Save(FormMode::Edit) | FormCallback::SaveCommand(FormMode::Edit, _) => {
+                "edit"
+            }
+            FormCallback::Save(FormMode::Insert)
+            | FormCallback::SaveCommand(FormMode::Insert, _) => "insert",
+        };
         let event = match self {
             FormCallback::Save(FormMode::Edit) => StateEvent::EditCommand { respond_to: tx },
             FormCallback::Save(FormMode::Insert) => StateEvent::InsertCommand { respond_to: tx },
+            FormCallback::SaveCommand(FormMode::Edit, command) => StateEvent::ReplaceCommand {
+                command,
+                respond_to: tx,
+            },
+            FormCallback::SaveCommand(FormMode::Insert, command) => StateEvent::AddCommand {
+                command,
+                respond_to: tx,
+            },
         };
 
         if let Err(e) = state_tx.send(event).await {
@@ -29,10 +47,6 @@ impl FormCallback {
                 Ok(())
             }
             Ok(Err(e)) => {
-                let operation = match &self {
-                    FormCallback::Save(FormMode::Edit) => "edit",
-                    FormCallback::Save(FormMode::Insert) => "insert",
-                };
                 let msg = format!("Failed to {operation} command: {e}");
                 error!("FormCallback: {}", msg);
                 Err(msg)
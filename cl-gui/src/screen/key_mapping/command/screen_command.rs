@@ -1,6 +1,6 @@
 use crate::observer::event::Event;
 use crate::screen::key_mapping::command::{FormCallback, ScreenCommandCallback};
-use crate::screen::layer::Layer;
+use crate::screen::layer::{FormMode, Layer};
 use cl_core::Command;
 use std::any::TypeId;
 use tokio::sync::mpsc::Receiver;
@@ -23,6 +23,8 @@ pub enum ScreenCommand {
     GetFieldContent,
     /// Save command (edit or insert depending on form mode)
     Form(FormCallback),
+    /// Suspend the screen to edit the command of the form in the user's editor
+    OpenEditor(FormMode),
     /// Navigate to the next command in the list (UI-local, no state round-trip)
     NavigateNext,
     /// Navigate to the previous command in the list (UI-local, no state round-trip)
@@ -1,9 +1,13 @@
-use crate::component::{List, Tabs, TextBox};
-use crate::observer::event::{EditableTextboxEvent, Event, ListEvent, TabsEvent, TextBoxEvent};
+use crate::component::{List, Popup, Tabs, TextBox};
+use crate::observer::event::{
+    EditableTextboxEvent, Event, ListEvent, PopupEvent, PopupType, TabsEvent, TextBoxEvent,
+};
 use crate::screen::key_mapping::command::ScreenCommand;
+use crate::screen::layer::PopupLayer;
 use crate::state::state_event::StateEvent;
 use crate::state::state_event::StateEvent::{
-    CommandDetails, CurrentCommand, GetAllListItems, GetAllNamespaces,
+    CommandDetails, CurrentCommand, GetAllListItems, GetAllNamespaces, GetSelectedNamespace,
+    HasConflict,
 };
 use cl_core::CommandVecExt;
 use std::any::TypeId;
@@ -18,7 +22,7 @@ pub enum ScreenCommandCallback {
     /// Load command details
     LoadCommandDetails(TypeId),
     /// Save changes to the current command
-    SaveChanges(Option<cl_core::Command<'static>>),
+    SaveChanges(Option<Box<cl_core::Command<'static>>>),
     /// Do nothing
     DoNothing,
     /// Exit the edit screen and return to the main screen
@@ -29,13 +33,16 @@ impl ScreenCommandCallback {
     pub async fn handle(self, state_tx: &Sender<StateEvent>) -> Option<Vec<ScreenCommand>> {
         match self {
             ScreenCommandCallback::UpdateAll => {
-                let (items, tabs, cmd) = tokio::join!(
+                let (items, tabs, namespace, cmd) = tokio::join!(
                     async { oneshot!(state_tx, GetAllListItems).ok() },
                     async { oneshot!(state_tx, GetAllNamespaces).ok() },
+                    async { oneshot!(state_tx, GetSelectedNamespace).ok() },
                     async { oneshot!(state_tx, CurrentCommand).ok() },
                 );
 
-                if let (Some(items), Some(tabs), Some(cmd)) = (items, tabs, cmd) {
+                if let (Some(items), Some(tabs), Some(namespace), Some(cmd)) =
+                    (items, tabs, namespace, cmd)
+                {
                     let selected_idx = cmd.as_ref().map_or(0, |c| c.current_idx);
 
                     let mut events: Vec<ScreenCommand> = vec![
@@ -48,6 +55,11 @@ impl ScreenCommandCallback {
                             TypeId::of::<Tabs>(),
                             Event::Tabs(TabsEvent::UpdateAll(tabs)),
                         )),
+                        // the namespaces were replaced, but the selected one may still be there
+                        ScreenCommand::Notify((
+                            TypeId::of::<Tabs>(),
+                            Event::Tabs(TabsEvent::Next(namespace.idx)),
+                        )),
                         ScreenCommand::Notify((
                             TypeId::of::<List>(),
                             Event::List(ListEvent::UpdateAll(items.aliases())),
@@ -57,7 +69,7 @@ impl ScreenCommandCallback {
                     if let Some(cmd) = cmd {
                         events.push(ScreenCommand::Notify((
                             TypeId::of::<TextBox>(),
-                            Event::TextBox(TextBoxEvent::UpdateCommand(cmd.value)),
+                            Event::TextBox(TextBoxEvent::UpdateCommand(Box::new(cmd.value))),
                         )));
                         events.push(ScreenCommand::Notify((
                             TypeId::of::<List>(),
@@ -65,6 +77,15 @@ impl ScreenCommandCallback {
                         )));
                     };
 
+                    // the changes that could not be saved are merged or discarded by the user
+                    if oneshot!(state_tx, HasConflict).unwrap_or_default() {
+                        events.push(ScreenCommand::AddLayer(Box::new(PopupLayer::default())));
+                        events.push(ScreenCommand::Notify((
+                            TypeId::of::<Popup>(),
+                            Event::Popup(PopupEvent::Create(PopupType::Conflict)),
+                        )));
+                    }
+
                     Some(events)
                 } else {
                     None
@@ -76,7 +97,7 @@ impl ScreenCommandCallback {
                     debug!("got cmd: {:?}", cmd);
                     Some(vec![ScreenCommand::Notify((
                         type_id,
-                        Event::EditableTextbox(EditableTextboxEvent::UpdateCommand(cmd)),
+                        Event::EditableTextbox(EditableTextboxEvent::UpdateCommand(Box::new(cmd))),
                     ))])
                 } else {
                     debug!("no command details found");
@@ -78,6 +78,14 @@ impl FormScreenLayer {
                     ];
                     Some(events)
                 }
+                KeyEvent {
+                    code: KeyCode::Char('o'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } => Some(vec![
+                    ScreenCommand::GetFieldContent,
+                    ScreenCommand::OpenEditor(mode),
+                ]),
                 KeyEvent {
                     code: KeyCode::Tab,
                     modifiers: KeyModifiers::NONE,
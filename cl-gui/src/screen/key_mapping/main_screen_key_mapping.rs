@@ -6,11 +6,14 @@ use crate::screen::key_mapping::ScreenCommand::{
     AddLayer, CopyToClipboard, Quit, ReplaceCurrentLayer,
 };
 use crate::screen::key_mapping::{create_notify_command, ScreenCommand};
-use crate::screen::layer::{FormScreenLayer, MainScreenLayer, PopupLayer, QuickSearchLayer};
+use crate::screen::layer::{
+    FormScreenLayer, MainScreenLayer, PopupLayer, QuickSearchLayer, RunDialogLayer,
+};
 use crate::screen::ActiveScreen::Main;
 use crate::state::state_event::StateEvent;
 use crate::state::state_event::StateEvent::{
-    DeleteCommand, ExecuteCommand, GetCurrentQuery, NextTab, PreviousTab,
+    CurrentCommand, DeleteCommand, ExecuteCommand, ExpandedCommand, ForkCommand, GetCurrentQuery,
+    HasConflict, NextTab, PreviousTab,
 };
 use cl_core::CommandVecExt;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -31,21 +34,25 @@ impl MainScreenLayer {
                 KeyEvent {
                     code: KeyCode::Char('d'),
                     ..
-                } => Some(vec![
+                } => read_only_popup(&state_tx).await.or_else(|| Some(vec![
                     AddLayer(Box::new(PopupLayer::default())),
                     create_notify_command::<Popup>(PopupEvent::Create(Dialog(
                         "Are you sure you want to delete this command?".to_string(),
                         FutureEventType::State(|state| {
                             async_fn_body! {
                                 let result = oneshot!(state, DeleteCommand)?;
-                                result.map_err(|e| anyhow::anyhow!(e))?;
-                                debug!("Command deleted");
+                                // conflicts with other processes are solved in the popup shown by `UpdateAll`
+                                let has_conflict = result.is_err() && oneshot!(state, HasConflict)?;
+                                if !has_conflict {
+                                    result.map_err(|e| anyhow::anyhow!(e))?;
+                                    debug!("Command deleted");
+                                };
                                 Ok(())
                             }
                         }),
                         ScreenCommandCallback::UpdateAll,
                     ))),
-                ]),
+                ])),
                 // Navigate next — handled by CommandDispatcher using the local snapshot
                 KeyEvent {
                     code: KeyCode::Char('j'),
@@ -73,7 +80,7 @@ impl MainScreenLayer {
                             create_notify_command::<List>(ListEvent::UpdateAll(aliases)),
                             create_notify_command::<Tabs>(TabsEvent::Next(selected_namespace.idx)),
                             create_notify_command::<TextBox>(TextBoxEvent::UpdateCommand(
-                                selected_command.value.clone(),
+                                Box::new(selected_command.value.clone()),
                             )),
                         ];
 
@@ -98,7 +105,7 @@ impl MainScreenLayer {
                                 selected_namespace.idx,
                             )),
                             create_notify_command::<TextBox>(TextBoxEvent::UpdateCommand(
-                                selected_command.value.clone(),
+                                Box::new(selected_command.value.clone()),
                             )),
                         ];
 
@@ -146,12 +153,30 @@ impl MainScreenLayer {
                     code: KeyCode::Enter,
                     modifiers: KeyModifiers::NONE,
                     ..
-                } => {
-                    if let Err(e) = state_tx.send(ExecuteCommand).await {
-                        tracing::error!("failed to send ExecuteCommand: {e}");
+                } => match oneshot!(state_tx, ExpandedCommand) {
+                    // named parameters must be filled before running the command
+                    Ok(Some(Ok(command))) if command.has_named_parameter() => {
+                        Some(vec![AddLayer(Box::new(RunDialogLayer::new(command)))])
                     }
-                    Some(vec![Quit])
-                }
+                    Ok(Some(Err(error))) => Some(error_popup(error)),
+                    _ => {
+                        if let Err(e) = state_tx.send(ExecuteCommand).await {
+                            tracing::error!("failed to send ExecuteCommand: {e}");
+                        }
+                        Some(vec![Quit])
+                    }
+                },
+                KeyEvent {
+                    code: KeyCode::Char('r'),
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                } => match oneshot!(state_tx, ExpandedCommand) {
+                    Ok(Some(Ok(command))) => {
+                        Some(vec![AddLayer(Box::new(RunDialogLayer::new(command)))])
+                    }
+                    Ok(Some(Err(error))) => Some(error_popup(error)),
+                    _ => None,
+                },
                 KeyEvent {
                     code: KeyCode::Char('q'),
                     modifiers: KeyModifiers::NONE,
@@ -161,7 +186,7 @@ impl MainScreenLayer {
                     code: KeyCode::Char('e'),
                     modifiers: KeyModifiers::NONE,
                     ..
-                } => {
+                } => read_only_popup(&state_tx).await.or_else(|| {
                     let events = vec![
                         ReplaceCurrentLayer(Box::new(FormScreenLayer::edit())),
                         ScreenCommand::Callback(ScreenCommandCallback::LoadCommandDetails(
@@ -169,7 +194,7 @@ impl MainScreenLayer {
                         )),
                     ];
                     Some(events)
-                }
+                }),
                 KeyEvent {
                     code: KeyCode::Char('i'),
                     modifiers: KeyModifiers::NONE,
@@ -184,10 +209,52 @@ impl MainScreenLayer {
     }
 }
 
+/// Offers to fork the selected command if it is read-only, as it cannot be edited or deleted
+async fn read_only_popup(state_tx: &Sender<StateEvent>) -> Option<Vec<ScreenCommand>> {
+    let selected = oneshot!(state_tx, CurrentCommand).ok().flatten()?;
+    if !selected.value.read_only {
+        return None;
+    }
+
+    let path = selected
+        .value
+        .source
+        .map(|source| source.display().to_string())
+        .unwrap_or_default();
+    Some(vec![
+        AddLayer(Box::new(PopupLayer::default())),
+        create_notify_command::<Popup>(PopupEvent::Create(Dialog(
+            format!(
+                "This command comes from the read-only file '{path}' and cannot be changed.\n\
+                Do you want to fork it into your commands?"
+            ),
+            FutureEventType::State(|state| {
+                async_fn_body! {
+                    oneshot!(state, ForkCommand)?.map_err(|e| anyhow::anyhow!(e))
+                }
+            }),
+            ScreenCommandCallback::UpdateAll,
+        ))),
+    ])
+}
+
+/// Shows `error` in a dialog popup
+fn error_popup(error: String) -> Vec<ScreenCommand> {
+    vec![
+        AddLayer(Box::new(PopupLayer::default())),
+        create_notify_command::<Popup>(PopupEvent::Create(Dialog(
+            format!("Error: {error}"),
+            FutureEventType::State(|_| async_fn_body! { Ok(()) }),
+            ScreenCommandCallback::DoNothing,
+        ))),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::screen::layer::Layer;
+    use crate::state::selected_command::SelectedCommand;
     use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
 
     fn key(code: KeyCode) -> KeyEvent {
@@ -201,7 +268,8 @@ mod tests {
 
     fn run_key(code: KeyCode) -> Option<Vec<ScreenCommand>> {
         let layer = MainScreenLayer::default();
-        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        // without a state actor, the requests to the state fail
+        let (tx, _) = tokio::sync::mpsc::channel(16);
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
@@ -268,6 +336,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn edit_key_offers_to_fork_a_read_only_command() {
+        let layer = MainScreenLayer::default();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let result = rt.block_on(async {
+            tokio::spawn(async move {
+                while let Some(event) = rx.recv().await {
+                    if let StateEvent::CurrentCommand { respond_to } = event {
+                        let command = cl_core::Command {
+                            read_only: true,
+                            ..Default::default()
+                        };
+                        let _ = respond_to.send(Some(SelectedCommand::new(command, 0)));
+                    }
+                }
+            });
+            layer.handle_key_event(key(KeyCode::Char('e')), tx).await
+        });
+
+        let cmds = result.unwrap();
+        assert!(
+            cmds.iter().any(|c| matches!(c, ScreenCommand::AddLayer(_))),
+            "expected a popup for a read-only command"
+        );
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, ScreenCommand::ReplaceCurrentLayer(_))),
+            "read-only commands must not be edited"
+        );
+    }
+
     #[test]
     fn insert_key_returns_replace_layer() {
         let result = run_key(KeyCode::Char('i'));
@@ -1,6 +1,7 @@
 mod form_screen_key_mapping;
 mod main_screen_key_mapping;
 mod popup_key_mapping;
+mod run_dialog_key_mapping;
 mod search_key_mapping;
 
 pub mod command;
@@ -0,0 +1,128 @@
+use crate::component::{EditableTextbox, FutureEventType, Popup, ScreenState};
+use crate::observer::event::PopupType::Dialog;
+use crate::observer::event::{EditableTextboxEvent, PopupEvent, ScreenStateEvent};
+use crate::screen::command::ScreenCommandCallback;
+use crate::screen::key_mapping::ScreenCommand::{AddLayer, PopLastLayer, Quit};
+use crate::screen::key_mapping::{create_notify_command, ScreenCommand};
+use crate::screen::layer::{PopupLayer, RunDialogLayer};
+use crate::state::state_event::StateEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::future::Future;
+use std::pin::Pin;
+use tokio::sync::mpsc::Sender;
+use tracing::debug;
+
+impl RunDialogLayer {
+    pub(crate) fn map_key_event<'a>(
+        &'a self,
+        key: KeyEvent,
+        state_tx: Sender<StateEvent>,
+    ) -> Pin<Box<dyn Future<Output = Option<Vec<ScreenCommand>>> + 'a>> {
+        // Extract all self-borrowed data up front so the async block owns
+        // only plain values — no borrow of `self` crosses the await point.
+        let next_field = self.get_next_field();
+        let prev_field = self.get_previous_field();
+        let resolved = self.resolve();
+
+        Box::pin(async move {
+            match key {
+                KeyEvent {
+                    code: KeyCode::Esc,
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                }
+                | KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } => Some(vec![PopLastLayer(None)]),
+                KeyEvent {
+                    code: KeyCode::Enter,
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                } => match resolved {
+                    Ok(command) => {
+                        debug!(target: "clr_run_dialog_key_mapping", "Running {}", command.command);
+                        if let Err(e) = state_tx
+                            .send(StateEvent::RunCommand(Box::new(command)))
+                            .await
+                        {
+                            tracing::error!("failed to send RunCommand: {e}");
+                        }
+                        Some(vec![Quit])
+                    }
+                    Err(error) => Some(vec![
+                        AddLayer(Box::new(PopupLayer::default())),
+                        create_notify_command::<Popup>(PopupEvent::Create(Dialog(
+                            format!("Error: {error}"),
+                            FutureEventType::State(|_| async_fn_body! { Ok(()) }),
+                            ScreenCommandCallback::DoNothing,
+                        ))),
+                    ]),
+                },
+                KeyEvent {
+                    code: KeyCode::Tab,
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                } => Some(vec![
+                    create_notify_command::<EditableTextbox>(EditableTextboxEvent::SetField(
+                        next_field,
+                    )),
+                    create_notify_command::<ScreenState>(ScreenStateEvent::SetField(next_field)),
+                ]),
+                KeyEvent {
+                    code: KeyCode::BackTab,
+                    modifiers: KeyModifiers::SHIFT,
+                    ..
+                } => Some(vec![
+                    create_notify_command::<EditableTextbox>(EditableTextboxEvent::SetField(
+                        prev_field,
+                    )),
+                    create_notify_command::<ScreenState>(ScreenStateEvent::SetField(prev_field)),
+                ]),
+                input => Some(vec![
+                    create_notify_command::<EditableTextbox>(EditableTextboxEvent::KeyInput(input)),
+                    create_notify_command::<ScreenState>(ScreenStateEvent::KeyInput(input)),
+                ]),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screen::layer::Layer;
+    use cl_core::CommandBuilder;
+
+    fn run_key(code: KeyCode) -> Option<Vec<ScreenCommand>> {
+        let command = CommandBuilder::default()
+            .alias("greet")
+            .namespace("ns")
+            .command("echo #{name}")
+            .build();
+        let layer = RunDialogLayer::new(command);
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(layer.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE), tx))
+    }
+
+    #[test]
+    fn esc_key_closes_the_dialog() {
+        let cmds = run_key(KeyCode::Esc).unwrap();
+        assert!(matches!(cmds[..], [ScreenCommand::PopLastLayer(None)]));
+    }
+
+    #[test]
+    fn enter_key_shows_an_error_when_parameters_are_missing() {
+        let cmds = run_key(KeyCode::Enter).unwrap();
+        assert!(
+            cmds.iter().any(|c| matches!(c, ScreenCommand::AddLayer(_))),
+            "expected an error popup"
+        );
+        assert!(!cmds.iter().any(|c| matches!(c, ScreenCommand::Quit)));
+    }
+}
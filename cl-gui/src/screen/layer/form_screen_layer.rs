@@ -36,6 +36,8 @@ pub struct FormScreenLayer {
     pub command: RenderableComponent<EditableTextbox>,
     pub tags: RenderableComponent<EditableTextbox>,
     pub description: RenderableComponent<EditableTextbox>,
+    pub cwd: RenderableComponent<EditableTextbox>,
+    pub env: RenderableComponent<EditableTextbox>,
     pub screen_state: StateComponent<ScreenState>,
     pub listeners: BTreeMap<TypeId, Vec<Rc<RefCell<dyn Observable>>>>,
     pub app_name: StaticInfo,
@@ -49,6 +51,8 @@ const FIELD_ORDER: &[FieldName] = &[
     FieldName::Command,
     FieldName::Description,
     FieldName::Tags,
+    FieldName::Cwd,
+    FieldName::Env,
 ];
 
 impl FormScreenLayer {
@@ -85,12 +89,22 @@ impl FormScreenLayer {
             name: FieldName::Description,
             ..Default::default()
         };
+        let cwd = EditableTextbox {
+            name: FieldName::Cwd,
+            ..Default::default()
+        };
+        let env = EditableTextbox {
+            name: FieldName::Env,
+            ..Default::default()
+        };
 
         let alias_component = RenderableComponent::new(alias);
         let namespace_component = RenderableComponent::new(namespace);
         let command_component = RenderableComponent::new(command);
         let tags_component = RenderableComponent::new(tags);
         let description_component = RenderableComponent::new(description);
+        let cwd_component = RenderableComponent::new(cwd);
+        let env_component = RenderableComponent::new(env);
 
         let screen_state_component = StateComponent::new(screen_state);
 
@@ -103,6 +117,8 @@ impl FormScreenLayer {
                 command_component.get_observable(),
                 tags_component.get_observable(),
                 description_component.get_observable(),
+                cwd_component.get_observable(),
+                env_component.get_observable(),
             ],
         );
 
@@ -125,6 +141,8 @@ impl FormScreenLayer {
             command: command_component,
             tags: tags_component,
             description: description_component,
+            cwd: cwd_component,
+            env: env_component,
             screen_state: screen_state_component,
             listeners,
             app_name,
@@ -208,13 +226,15 @@ impl FormScreenLayer {
             )
     }
 
-    fn build_progress_lines(&self) -> Vec<Line> {
+    fn build_progress_lines(&self) -> Vec<Line<'_>> {
         let fields = [
             (&self.alias, "alias", true),
             (&self.namespace, "namespace", true),
             (&self.command, "command", true),
             (&self.description, "description", false),
             (&self.tags, "tags", false),
+            (&self.cwd, "working dir", false),
+            (&self.env, "environment", false),
         ];
 
         fields
@@ -228,7 +248,7 @@ impl FormScreenLayer {
             .collect()
     }
 
-    fn build_preview_lines(&self) -> Vec<Line> {
+    fn build_preview_lines(&self) -> Vec<Line<'_>> {
         let mut lines = Vec::new();
 
         // Alias & Namespace
@@ -267,6 +287,24 @@ impl FormScreenLayer {
             lines.push(Line::from(format!("Tags: {tags_text}")));
         }
 
+        // Working directory
+        let cwd_text = self.cwd.borrow_inner().textarea.lines().join("");
+        if !cwd_text.is_empty() {
+            lines.push(Line::from(format!("Cwd: {cwd_text}")));
+        }
+
+        // Environment
+        let env_lines = self.env.borrow_inner().textarea.lines().to_vec();
+        let env_text = env_lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !env_text.is_empty() {
+            lines.push(Line::from(format!("Env: {env_text}")));
+        }
+
         lines
     }
 
@@ -277,6 +315,9 @@ impl FormScreenLayer {
             FieldName::Command => "Command: shell command to execute (use #{param} for parameters)",
             FieldName::Description => "Description: optional summary of what the command does",
             FieldName::Tags => "Tags: optional labels to categorize the command",
+            FieldName::Cwd => "Working directory: optional directory to run the command from",
+            FieldName::Env => "Environment: optional variables, one KEY=VALUE per line",
+            FieldName::Parameter(_) | FieldName::Arguments => "",
         }
     }
 
@@ -333,6 +374,7 @@ impl Layer for FormScreenLayer {
             Constraint::Length(5), //Alias & Namespace
             Constraint::Fill(1),   //Command
             Constraint::Length(5), //Desc & Tags
+            Constraint::Length(5), //Cwd & Env
         ];
 
         let drawable_chunks = Layout::default()
@@ -364,6 +406,12 @@ impl Layer for FormScreenLayer {
             .split(right_side[2]);
         let (third_row1, third_row2) = (third_row[0], third_row[1]);
 
+        let fourth_row = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(right_side[3]);
+        let (fourth_row1, fourth_row2) = (fourth_row[0], fourth_row[1]);
+
         let left_panel_splits = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Max(3), Constraint::Fill(1)])
@@ -411,6 +459,8 @@ impl Layer for FormScreenLayer {
             { self.command, second_row1 },
             { self.description, third_row1},
             { self.tags, third_row2 },
+            { self.cwd, fourth_row1 },
+            { self.env, fourth_row2 },
         }
     }
 
@@ -459,7 +509,7 @@ mod tests {
 
         let previous_field = layer.get_previous_field();
 
-        assert_eq!(previous_field, FieldName::Tags);
+        assert_eq!(previous_field, FieldName::Env);
     }
 
     #[test]
@@ -470,7 +520,7 @@ mod tests {
         assert_eq!(next, FieldName::Namespace);
 
         let prev = layer.get_previous_field();
-        assert_eq!(prev, FieldName::Tags);
+        assert_eq!(prev, FieldName::Env);
     }
 
     #[test]
@@ -485,7 +535,7 @@ mod tests {
         let layer = FormScreenLayer::insert();
         let lines = layer.build_progress_lines();
 
-        assert_eq!(lines.len(), 5);
+        assert_eq!(lines.len(), 7);
         assert!(lines[0].to_string().contains(" "));
         assert!(lines[0].to_string().contains("alias"));
     }
@@ -523,6 +573,8 @@ mod tests {
             FieldName::Command,
             FieldName::Description,
             FieldName::Tags,
+            FieldName::Cwd,
+            FieldName::Env,
         ];
         let hints: Vec<_> = fields.iter().map(FormScreenLayer::hint_for_field).collect();
 
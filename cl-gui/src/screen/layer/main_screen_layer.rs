@@ -128,6 +128,10 @@ impl Layer for MainScreenLayer {
         self.map_key_event(key, state_tx)
     }
 
+    fn is_main(&self) -> bool {
+        true
+    }
+
     fn render(&mut self, frame: &mut Frame, theme: &Theme) {
         let drawable_area = [Constraint::Fill(2), Constraint::Max(3)];
         let areas = [
@@ -2,12 +2,14 @@ mod form_screen_layer;
 mod main_screen_layer;
 mod popup_layer;
 mod quick_search_layer;
+mod run_dialog_layer;
 
 pub use form_screen_layer::FormMode;
 pub use form_screen_layer::FormScreenLayer;
 pub use main_screen_layer::MainScreenLayer;
 pub use popup_layer::PopupLayer;
 pub use quick_search_layer::QuickSearchLayer;
+pub use run_dialog_layer::RunDialogLayer;
 
 use crate::observer::observable::Observable;
 use crate::screen::key_mapping::command::ScreenCommand;
@@ -58,4 +60,9 @@ pub trait Layer {
 
     /// Called before this layer is removed from the screen.
     fn on_detach(&mut self) {}
+
+    /// Whether this is the main screen, with no form nor dialog open on top of it.
+    fn is_main(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,279 @@
+use crate::component::{EditableTextbox, RenderableComponent, ScreenState, StateComponent};
+use crate::observer::observable::Observable;
+use crate::screen::key_mapping::command::ScreenCommand;
+use crate::screen::layer::Layer;
+use crate::screen::theme::Theme;
+use crate::state::state_event::{FieldName, StateEvent};
+use anyhow::Result;
+use cl_core::Command;
+use crossterm::event::KeyEvent;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use tokio::sync::mpsc::Sender;
+use tui::layout::{Constraint, Layout, Rect};
+use tui::style::Style;
+use tui::widgets::{Block, Clear, Paragraph, Wrap};
+use tui::Frame;
+
+const FOOTER: &str = "<Enter> run  <Tab/Shift-Tab> switch field  <Esc> cancel";
+
+/// Collects the named parameters (and extra arguments) of a command before running it
+pub struct RunDialogLayer {
+    pub command: Command<'static>,
+    pub parameters: Vec<(String, RenderableComponent<EditableTextbox>)>,
+    pub arguments: RenderableComponent<EditableTextbox>,
+    pub screen_state: StateComponent<ScreenState>,
+    pub listeners: BTreeMap<TypeId, Vec<Rc<RefCell<dyn Observable>>>>,
+}
+
+impl RunDialogLayer {
+    pub fn new(command: Command<'static>) -> Self {
+        let names = command.named_parameters();
+        let first_field = if names.is_empty() {
+            FieldName::Arguments
+        } else {
+            FieldName::Parameter(0)
+        };
+
+        let parameters: Vec<(String, RenderableComponent<EditableTextbox>)> = names
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let name_field = FieldName::Parameter(idx);
+                let mut textbox = EditableTextbox {
+                    name: name_field,
+                    title: Some(format!("#{{{name}}}")),
+                    ..Default::default()
+                };
+                let default = command.parameter(&name).and_then(|p| p.default.clone());
+                textbox.update_content(default);
+                textbox.set_active(name_field == first_field);
+                (name, RenderableComponent::new(textbox))
+            })
+            .collect();
+
+        let arguments = RenderableComponent::new(EditableTextbox {
+            name: FieldName::Arguments,
+            active: first_field == FieldName::Arguments,
+            ..Default::default()
+        });
+
+        let screen_state = StateComponent::new(ScreenState::new(first_field));
+
+        let mut textboxes: Vec<Rc<RefCell<dyn Observable>>> = parameters
+            .iter()
+            .map(|(_, component)| component.get_observable())
+            .collect();
+        textboxes.push(arguments.get_observable());
+
+        let mut listeners = BTreeMap::new();
+        listeners.insert(TypeId::of::<EditableTextbox>(), textboxes);
+        listeners.insert(
+            TypeId::of::<ScreenState>(),
+            vec![screen_state.get_observable()],
+        );
+
+        Self {
+            command,
+            parameters,
+            arguments,
+            screen_state,
+            listeners,
+        }
+    }
+
+    pub fn get_next_field(&self) -> FieldName {
+        self.navigate_field(1)
+    }
+
+    pub fn get_previous_field(&self) -> FieldName {
+        self.navigate_field(-1)
+    }
+
+    fn fields(&self) -> Vec<FieldName> {
+        (0..self.parameters.len())
+            .map(FieldName::Parameter)
+            .chain([FieldName::Arguments])
+            .collect()
+    }
+
+    fn navigate_field(&self, delta: isize) -> FieldName {
+        let fields = self.fields();
+        let current = self.screen_state.borrow_inner().current_field;
+        let pos = fields.iter().position(|f| f == &current).unwrap_or(0);
+        let idx = ((pos as isize + delta).rem_euclid(fields.len() as isize)) as usize;
+        fields[idx]
+    }
+
+    /// The command with the values typed so far
+    pub fn resolve(&self) -> Result<Command<'static>> {
+        let values: HashMap<String, String> = self
+            .parameters
+            .iter()
+            .map(|(name, component)| (name.to_owned(), content_of(component)))
+            .collect();
+
+        self.command
+            .with_arguments(&values, &content_of(&self.arguments))
+    }
+
+    fn hint(&self) -> String {
+        match self.screen_state.borrow_inner().current_field {
+            FieldName::Parameter(idx) => self
+                .parameters
+                .get(idx)
+                .map(|(name, _)| {
+                    self.command
+                        .parameter(name)
+                        .map(|p| p.summary())
+                        .unwrap_or_else(|| name.to_owned())
+                })
+                .unwrap_or_default(),
+            _ if self.command.is_pipeline() => "Pipelines accept no extra arguments".to_owned(),
+            _ => "Extra arguments appended to the command (optional)".to_owned(),
+        }
+    }
+}
+
+fn content_of(component: &RenderableComponent<EditableTextbox>) -> String {
+    component.borrow_inner().textarea.lines().join("\n")
+}
+
+impl Layer for RunDialogLayer {
+    fn handle_key_event<'a>(
+        &'a self,
+        key: KeyEvent,
+        state_tx: Sender<StateEvent>,
+    ) -> Pin<Box<dyn Future<Output = Option<Vec<ScreenCommand>>> + 'a>> {
+        self.map_key_event(key, state_tx)
+    }
+
+    fn render(&mut self, frame: &mut Frame, theme: &Theme) {
+        let style = Style::default()
+            .fg(theme.text_color.into())
+            .bg(theme.background_color.into());
+
+        // 3 rows per textbox, 4 for the preview, 2 for the hint and the footer, 2 for the border
+        let fields = self.parameters.len() as u16 + 1;
+        let height = (fields * 3 + 4 + 2 + 2).min(frame.area().height);
+        let area = centered_rect(60, height, frame.area());
+
+        let block = Block::bordered()
+            .title(format!(
+                "Run {}.{}",
+                self.command.namespace, self.command.alias
+            ))
+            .style(style);
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let mut constraints = vec![Constraint::Length(3); fields as usize];
+        constraints.extend([
+            Constraint::Length(4),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ]);
+        let chunks = Layout::vertical(constraints).split(inner);
+
+        for (idx, (_, component)) in self.parameters.iter_mut().enumerate() {
+            component.render(frame, chunks[idx], theme);
+        }
+        let fields = fields as usize;
+        self.arguments.render(frame, chunks[fields - 1], theme);
+
+        let preview = match self.resolve() {
+            Ok(command) => command.command.to_string(),
+            Err(error) => error.to_string(),
+        };
+        let preview = Paragraph::new(preview)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title("Preview").style(style))
+            .style(style);
+        frame.render_widget(preview, chunks[fields]);
+        frame.render_widget(Paragraph::new(self.hint()).style(style), chunks[fields + 1]);
+        frame.render_widget(Paragraph::new(FOOTER).style(style), chunks[fields + 2]);
+    }
+
+    fn get_listeners(&self) -> &BTreeMap<TypeId, Vec<Rc<RefCell<dyn Observable>>>> {
+        &self.listeners
+    }
+}
+
+/// Return a [`Rect`] centered within `r`, `percent_x` wide and `height` rows tall.
+fn centered_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
+    let margin_h = (100 - percent_x) / 2;
+
+    let vertical = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .split(r);
+
+    Layout::horizontal([
+        Constraint::Percentage(margin_h),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage(margin_h),
+    ])
+    .split(vertical[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cl_core::{CommandBuilder, Parameter};
+
+    fn command() -> Command<'static> {
+        CommandBuilder::default()
+            .alias("greet")
+            .namespace("ns")
+            .command("echo #{greeting}, #{name}")
+            .parameters(Some(vec![Parameter {
+                name: "greeting".to_owned(),
+                default: Some("hello".to_owned()),
+                ..Default::default()
+            }]))
+            .build()
+    }
+
+    #[test]
+    fn should_create_one_textbox_per_named_parameter() {
+        let layer = RunDialogLayer::new(command());
+
+        let names: Vec<_> = layer.parameters.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["greeting", "name"]);
+        assert_eq!(content_of(&layer.parameters[0].1), "hello");
+        assert!(layer.parameters[0].1.borrow_inner().is_active());
+    }
+
+    #[test]
+    fn should_navigate_through_parameters_and_arguments() {
+        let layer = RunDialogLayer::new(command());
+
+        assert_eq!(layer.get_next_field(), FieldName::Parameter(1));
+        assert_eq!(layer.get_previous_field(), FieldName::Arguments);
+    }
+
+    #[test]
+    fn should_resolve_the_command_with_the_typed_values() {
+        let layer = RunDialogLayer::new(command());
+        assert!(layer.resolve().is_err());
+
+        layer.parameters[1]
+            .1
+            .borrow_inner_mut()
+            .update_content(Some("John"));
+        layer
+            .arguments
+            .borrow_inner_mut()
+            .update_content(Some("-n"));
+
+        let resolved = layer.resolve().unwrap();
+        assert_eq!(resolved.command, "echo hello, John -n");
+    }
+}
@@ -12,7 +12,7 @@ use crate::signal_handler::SignalHandler;
 use crate::state::state_event::StateEvent;
 use cl_core::Command;
 use crossterm::event::Event as CrosstermEvent;
-use layer::MainScreenLayer;
+use layer::{FormMode, MainScreenLayer};
 use std::any::TypeId;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
@@ -85,6 +85,48 @@ impl Screen {
         }
     }
 
+    /// Updates all the components with the state, e.g. after the commands were reloaded
+    pub async fn refresh(
+        &mut self,
+        state_tx: &Sender<StateEvent>,
+        sig_handler: &mut SignalHandler,
+    ) {
+        self.dispatch(
+            vec![command::ScreenCommand::Callback(
+                command::ScreenCommandCallback::UpdateAll,
+            )],
+            state_tx,
+            sig_handler,
+        )
+        .await;
+    }
+
+    pub async fn dispatch(
+        &mut self,
+        commands: Vec<command::ScreenCommand>,
+        state_tx: &Sender<StateEvent>,
+        sig_handler: &mut SignalHandler,
+    ) {
+        CommandDispatcher::dispatch(
+            commands,
+            &mut self.layer_stack,
+            &mut self.clipboard,
+            state_tx,
+            sig_handler,
+        )
+        .await;
+    }
+
+    /// Whether the main screen is on top, i.e. no form nor dialog acts on the selected command
+    pub fn is_main_layer_on_top(&self) -> bool {
+        self.layer_stack.is_main_on_top()
+    }
+
+    /// Takes the pending request to edit the command of the form in the user's editor
+    pub fn take_editor_request(&mut self) -> Option<FormMode> {
+        self.layer_stack.editor_request.take()
+    }
+
     pub async fn notify(&mut self, id: TypeId, event: Event) {
         self.layer_stack.notify(id, event).await;
     }
@@ -1,4 +1,6 @@
-use cl_core::{Command, CommandBuilder};
+use anyhow::Result;
+use cl_core::{parse_env_var, Command, CommandBuilder};
+use std::collections::BTreeMap;
 
 #[derive(Default)]
 pub struct EditState {
@@ -7,6 +9,8 @@ pub struct EditState {
     description: Option<String>,
     tags: Option<Vec<String>>,
     namespace: Option<String>,
+    cwd: Option<String>,
+    env: Option<String>,
 }
 
 impl EditState {
@@ -30,15 +34,37 @@ impl EditState {
         self.namespace = namespace;
     }
 
+    pub fn update_cwd(&mut self, cwd: Option<String>) {
+        self.cwd = cwd;
+    }
+
+    /// Updates the environment variables, one `KEY=VALUE` per line
+    pub fn update_env(&mut self, env: Option<String>) {
+        self.env = env;
+    }
+
     /// Returns a snapshot of the current edit state without consuming fields.
-    pub fn get(&self) -> Command<'static> {
-        CommandBuilder::default()
+    ///
+    /// Fails if the environment variables are not valid `KEY=VALUE` lines
+    pub fn get(&self) -> Result<Command<'static>> {
+        let env = self
+            .env
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(parse_env_var)
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        Ok(CommandBuilder::default()
             .alias(self.alias.clone().unwrap_or_default())
             .command(self.command.clone().unwrap_or_default())
             .namespace(self.namespace.clone().unwrap_or_default())
             .description(self.description.clone())
             .tags(self.tags.clone())
-            .build()
+            .cwd(self.cwd.clone())
+            .env(Some(env))
+            .build())
     }
 
     /// Consumes the edit state fields after a successful operation.
@@ -49,6 +75,8 @@ impl EditState {
         self.namespace = None;
         self.description = None;
         self.tags = None;
+        self.cwd = None;
+        self.env = None;
     }
 }
 
@@ -113,7 +141,7 @@ mod tests {
         state.update_description(Some("d".to_string()));
         state.update_tags(Some(vec!["t1".to_string()]));
 
-        let cmd = state.get();
+        let cmd = state.get().unwrap();
 
         assert_eq!(cmd.alias, "a");
         assert_eq!(cmd.command, "c");
@@ -122,6 +150,33 @@ mod tests {
         assert_eq!(cmd.tags_as_string(), "t1".to_string());
     }
 
+    #[test]
+    fn test_get_command_with_cwd_and_env() {
+        let mut state = EditState::default();
+        state.update_cwd(Some("~/projects".to_string()));
+        state.update_env(Some("FOO=bar\n\nBAZ=qux=1\n".to_string()));
+
+        let cmd = state.get().unwrap();
+
+        assert_eq!(cmd.cwd.as_deref(), Some("~/projects"));
+        assert_eq!(
+            cmd.env,
+            Some(BTreeMap::from([
+                ("BAZ".to_string(), "qux=1".to_string()),
+                ("FOO".to_string(), "bar".to_string()),
+            ]))
+        );
+
+        state.update_cwd(Some(String::new()));
+        state.update_env(Some("not a variable".to_string()));
+        assert!(state.get().is_err());
+
+        state.update_env(None);
+        let cmd = state.get().unwrap();
+        assert!(cmd.cwd.is_none());
+        assert!(cmd.env.is_none());
+    }
+
     #[test]
     fn test_get_does_not_consume_fields() {
         let mut state = EditState::default();
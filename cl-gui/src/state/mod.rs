@@ -5,14 +5,14 @@ use crate::state::selected_namespace::SelectedNamespace;
 use crate::state::state_event::FieldName;
 use anyhow::bail;
 use cl_core::{
-    fs, Command, CommandExec, CommandMap, CommandMapExt, CommandVec, CommandVecExt, Commands,
-    Config,
+    Command, CommandExec, CommandMap, CommandMapExt, CommandVec, CommandVecExt, Commands, Config,
+    ExecStatus, FileError, Fingerprint, Frecency, History, SortMode, Storage,
 };
 use nucleo_matcher::pattern::{Atom, AtomKind, CaseMatching, Normalization};
 use nucleo_matcher::{Config as MatcherConfig, Matcher, Utf32Str};
-use std::cmp::Reverse;
-use std::collections::HashMap;
-use tracing::{debug, error};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, warn};
 
 mod edit;
 pub mod selected_command;
@@ -26,12 +26,24 @@ pub struct State {
     selected_namespace: SelectedNamespace,
     namespaces: Vec<String>,
     config: Box<dyn Config>,
+    storage: Arc<dyn Storage>,
+
+    // conflicts with other processes
+    /// The stored commands, as last loaded or saved
+    stored: CommandMap<'static>,
+    fingerprint: Fingerprint,
+    /// Whether the last save was rejected because another process changed the stored commands
+    has_conflict: bool,
 
     // search
     current_query: Option<String>,
     cmd_map: CommandMap<'static>,
     current_items: CommandVec<'static>,
 
+    // sort
+    sort_mode: SortMode,
+    frecency: Frecency,
+
     // edit
     edit_state: EditState,
 }
@@ -41,14 +53,23 @@ const DEFAULT_NAMESPACE: &str = "All";
 impl State {
     pub fn new(cfg: impl Config + 'static) -> anyhow::Result<State> {
         // cmd load
-        let command_map = fs::load_from(cfg.command_file_path())?;
+        let storage: Arc<dyn Storage> = Arc::from(cfg.storage());
+        // taken before loading, so the commands changed in the meantime are not overwritten
+        let fingerprint = storage.fingerprint()?;
+        let command_map = storage.load()?;
+        let stored = command_map.clone();
         let commands = Commands::init(command_map);
-        let commands_as_list = commands.as_list().sorted();
         let cmd_map = commands.as_map().clone();
 
-        let selected = SelectedCommand::first_from_vec(&commands_as_list);
+        // sort
+        let sort_mode = cfg.preferences().sort_mode();
+        let frecency = match sort_mode {
+            SortMode::Frecency => load_frecency(&cfg),
+            SortMode::Alphabetical => Frecency::default(),
+        };
+        let current_items = sort(commands.as_list(), sort_mode, &frecency);
 
-        let current_items = commands_as_list;
+        let selected = SelectedCommand::first_from_vec(&current_items);
 
         // namespaces
         let namespaces: Vec<String> = commands.as_list().namespaces();
@@ -61,9 +82,15 @@ impl State {
             selected_command: selected,
             namespaces,
             config: Box::new(cfg),
+            storage,
+            stored,
+            fingerprint,
+            has_conflict: false,
             current_query: None,
             current_items,
             cmd_map,
+            sort_mode,
+            frecency,
             edit_state: EditState::default(),
         })
     }
@@ -88,6 +115,10 @@ impl State {
         &self.namespaces
     }
 
+    pub fn get_selected_namespace(&self) -> &SelectedNamespace {
+        &self.selected_namespace
+    }
+
     fn set_namespaces(&mut self, items: &CommandVec<'static>) {
         self.namespaces = append_default_namespace(items.namespaces());
     }
@@ -159,12 +190,43 @@ impl State {
         (self.selected_namespace.to_owned(), filtered_commands)
     }
 
-    pub fn execute(&self) {
-        if let Some(selected_command) = &self.selected_command {
-            let command = selected_command.value.to_owned();
-            debug!("executing command: {:?}", command);
-            if let Err(e) = command.exec(false, self.config.preferences().quiet_mode()) {
+    pub fn execute(&self) -> Option<ExecStatus> {
+        match self.expanded_command()? {
+            Ok(command) => self.run(&command),
+            Err(e) => {
+                error!("failed to resolve the references of the command: {e}");
+                None
+            }
+        }
+    }
+
+    /// The selected command with its references to other commands resolved
+    pub fn expanded_command(&self) -> Option<anyhow::Result<Command<'static>>> {
+        self.selected_command
+            .as_ref()
+            .map(|selected_command| self.commands.expand(&selected_command.value))
+    }
+
+    /// Runs `command`, reporting in the `stderr` if it did not succeed
+    pub fn run(&self, command: &Command) -> Option<ExecStatus> {
+        debug!("executing command: {:?}", command);
+        let history = History::new(self.config.history_file_path());
+        let preferences = self.config.preferences();
+        match command.exec(
+            false,
+            preferences.quiet_mode(),
+            preferences.shell(),
+            Some(&history),
+        ) {
+            Ok(status) => {
+                if !status.success() {
+                    eprintln!("{}.{} {status}", command.namespace, command.alias);
+                }
+                Some(status)
+            }
+            Err(e) => {
                 error!("failed to execute command '{}': {}", command.alias, e);
+                None
             }
         }
     }
@@ -173,11 +235,9 @@ impl State {
         if let Some(selected_command) = &self.selected_command {
             let command = &selected_command.value;
             match self.commands.remove(command) {
-                Ok(map) => {
-                    let map = map.clone();
-                    let path = self.config.command_file_path();
-                    tokio::task::spawn_blocking(move || fs::save_at(&map, path)).await??;
-                    self.current_items = self.commands.as_list().sorted();
+                Ok(_) => {
+                    self.save().await?;
+                    self.current_items = self.sort(self.commands.as_list());
                     self.cmd_map = self.commands.as_map().clone();
                     self.selected_command = self
                         .current_items
@@ -193,15 +253,30 @@ impl State {
         Ok(())
     }
 
+    /// Copies the selected read-only command to the personal commands, so it can be changed
+    pub async fn fork_command(&mut self) -> anyhow::Result<()> {
+        if let Some(selected_command) = &self.selected_command {
+            let command = selected_command.value.clone();
+            self.commands.fork(&command)?;
+            self.save().await?;
+            self.refresh();
+        }
+        debug!("command forked");
+        Ok(())
+    }
+
     /// Filters the commands based on a query and a namespace
     ///
+    /// The matching commands of all the namespaces are shown, so the default namespace is selected
+    ///
     /// ## Arguments
     /// * `query` - A String slice representing the user's query
     ///
     pub fn filter(&mut self, query: &str) {
+        self.selected_namespace = SelectedNamespace::new(0, DEFAULT_NAMESPACE.to_string());
         if query.is_empty() {
             self.current_query = None;
-            let all_commands = self.cmd_map.to_vec().sorted();
+            let all_commands = self.sort(self.cmd_map.to_vec());
             self.selected_command = SelectedCommand::first_from_vec(&all_commands);
             self.set_namespaces(&all_commands);
             self.current_items = all_commands;
@@ -209,7 +284,7 @@ impl State {
         }
 
         self.current_query = Some(query.to_string());
-        let current_items = self.fuzzy_find(query).to_vec().sorted();
+        let current_items = self.rank(self.ff_vec(query, &self.cmd_map.to_vec()));
         self.selected_command = SelectedCommand::first_from_vec(&current_items);
         self.set_namespaces(&current_items);
         self.current_items = current_items;
@@ -226,33 +301,28 @@ impl State {
         );
 
         let mut buf = Vec::new();
-        let mut scored: Vec<(usize, u16)> = command_vec
+        let mut scored: Vec<(usize, f64)> = command_vec
             .iter()
             .enumerate()
             .filter_map(|(idx, item)| {
                 let score =
                     atom.score(Utf32Str::new(&item.lookup_string(), &mut buf), &mut matcher);
                 score.map(|s| {
-                    debug!("item: {}, score: {s}", item.alias);
-                    (idx, s)
+                    // frequently used commands get up to twice the score of the unused ones
+                    let boost = 1.0 + self.frecency.relative_score(item);
+                    debug!("item: {}, score: {s}, boost: {boost}", item.alias);
+                    (idx, s as f64 * boost)
                 })
             })
             .collect();
 
-        scored.sort_by_key(|&(_, score)| Reverse(score));
+        scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
         scored
             .into_iter()
             .map(|(idx, _)| command_vec[idx].clone())
             .collect()
     }
 
-    fn fuzzy_find(&self, query: &str) -> CommandMap<'static> {
-        self.cmd_map
-            .iter()
-            .map(|(namespace, vec)| (namespace.clone(), self.ff_vec(query, vec)))
-            .collect::<HashMap<_, _>>()
-    }
-
     fn get_commands_by_namespace(&self, namespace: &str) -> CommandVec<'static> {
         debug!("filtering commands by namespace: {}", namespace);
         let result = if namespace == DEFAULT_NAMESPACE {
@@ -261,14 +331,26 @@ impl State {
             self.cmd_map.get(namespace).cloned().unwrap_or_default()
         };
 
-        {
-            if let Some(query) = &self.current_query {
-                self.ff_vec(query, &result)
-            } else {
-                result
-            }
+        if let Some(query) = &self.current_query {
+            self.rank(self.ff_vec(query, &result))
+        } else {
+            self.sort(result)
+        }
+    }
+
+    /// Orders the commands according to the sort mode
+    fn sort(&self, items: CommandVec<'static>) -> CommandVec<'static> {
+        sort(items, self.sort_mode, &self.frecency)
+    }
+
+    /// Orders the fuzzy search results according to the sort mode
+    ///
+    /// In the frecency mode the results keep their ranking, as it already takes the usage into account
+    fn rank(&self, mut items: CommandVec<'static>) -> CommandVec<'static> {
+        match self.sort_mode {
+            SortMode::Alphabetical => items.sorted(),
+            SortMode::Frecency => items,
         }
-        .sorted()
     }
 
     pub fn set_editable_command(&mut self, field_name: FieldName, content: String) {
@@ -286,20 +368,23 @@ impl State {
             }
             FieldName::Command => self.edit_state.update_command(Some(content)),
             FieldName::Namespace => self.edit_state.update_namespace(Some(content)),
+            FieldName::Cwd => self.edit_state.update_cwd(Some(content)),
+            FieldName::Env => self.edit_state.update_env(Some(content)),
+            FieldName::Parameter(_) | FieldName::Arguments => {
+                debug!("{field_name} is not an editable command field")
+            }
         }
     }
 
     pub async fn insert_command(&mut self) -> anyhow::Result<()> {
-        let new_command = self.edit_state.get();
+        let new_command = self.edit_state.get()?;
 
         debug!("About to insert command: {:#?}", new_command);
         match self.commands.add(&new_command) {
-            Ok(map) => {
+            Ok(_) => {
                 debug!("Command inserted successfully");
-                let map = map.clone();
-                let path = self.config.command_file_path();
-                tokio::task::spawn_blocking(move || fs::save_at(&map, path)).await??;
-                self.current_items = self.commands.as_list().sorted();
+                self.save().await?;
+                self.current_items = self.sort(self.commands.as_list());
                 self.cmd_map = self.commands.as_map().clone();
                 let selected_command = SelectedCommand::new(new_command, 0);
                 self.selected_command = Some(selected_command);
@@ -314,22 +399,15 @@ impl State {
     }
 
     pub async fn edit_command(&mut self) -> anyhow::Result<()> {
-        let edited = self.edit_state.get();
-
-        let actual = self
-            .selected_command
-            .as_ref()
-            .map(|selected| selected.value.clone())
-            .unwrap_or_default();
+        let edited = self.draft_command(true)?;
+        let actual = self.selected_value();
 
         debug!("About to change command: {:#?} to {:#?}", actual, edited);
         match self.commands.edit(&edited, &actual) {
-            Ok(map) => {
+            Ok(_) => {
                 debug!("Command edited successfully");
-                let map = map.clone();
-                let path = self.config.command_file_path();
-                tokio::task::spawn_blocking(move || fs::save_at(&map, path)).await??;
-                self.current_items = self.commands.as_list().sorted();
+                self.save().await?;
+                self.current_items = self.sort(self.commands.as_list());
                 self.cmd_map = self.commands.as_map().clone();
                 let selected_command = SelectedCommand::new(
                     edited,
@@ -349,6 +427,194 @@ impl State {
 
         Ok(())
     }
+
+    /// The command of the form. When `editing` the selected command, the fields the form does not
+    /// show are kept from it
+    pub fn draft_command(&self, editing: bool) -> anyhow::Result<Command<'static>> {
+        let mut draft = self.edit_state.get()?;
+        if editing {
+            let actual = self.selected_value();
+            // parameters declarations, the shell and the steps are not editable in the form, so keep the current ones
+            draft.parameters = actual.parameters;
+            draft.shell = actual.shell;
+            draft.steps = actual.steps;
+            // saved back to the file it came from
+            draft.source = actual.source;
+        }
+
+        Ok(draft)
+    }
+
+    /// Replaces the selected command with one changed outside of the form, e.g. in an editor
+    pub async fn replace_command(&mut self, command: Command<'static>) -> anyhow::Result<()> {
+        let actual = self.selected_value();
+        self.commands.edit(&command, &actual)?;
+        self.after_change(command).await
+    }
+
+    /// Adds a command written outside of the form, e.g. in an editor
+    pub async fn add_command(&mut self, command: Command<'static>) -> anyhow::Result<()> {
+        self.commands.add(&command)?;
+        self.after_change(command).await
+    }
+
+    /// Saves the changed commands, selecting the given one
+    async fn after_change(&mut self, command: Command<'static>) -> anyhow::Result<()> {
+        self.save().await?;
+        self.current_items = self.sort(self.commands.as_list());
+        self.cmd_map = self.commands.as_map().clone();
+        let current_idx = self
+            .selected_command
+            .as_ref()
+            .map_or(0, |selected| selected.current_idx);
+        self.selected_command = Some(SelectedCommand::new(command, current_idx));
+        self.edit_state.clear();
+
+        Ok(())
+    }
+
+    fn selected_value(&self) -> Command<'static> {
+        self.selected_command
+            .as_ref()
+            .map(|selected| selected.value.clone())
+            .unwrap_or_default()
+    }
+
+    /// Saves the commands, unless another process changed the stored ones since they were loaded
+    ///
+    /// In that case the changes are kept in memory until they are merged or discarded
+    async fn save(&mut self) -> anyhow::Result<()> {
+        let map = self.commands.as_map().clone();
+        let storage = Arc::clone(&self.storage);
+        let expected = self.fingerprint;
+        let result =
+            tokio::task::spawn_blocking(move || storage.save_if_unchanged(&map, &expected)).await?;
+
+        match result {
+            Ok(fingerprint) => {
+                self.fingerprint = fingerprint;
+                self.stored = self.commands.as_map().clone();
+                self.has_conflict = false;
+                Ok(())
+            }
+            Err(err) => {
+                if let Some(FileError::ChangedByAnotherProcess) = err.downcast_ref() {
+                    warn!("the commands were changed by another process, waiting for a merge or a reload");
+                    self.has_conflict = true;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Whether there are changes that could not be saved because of another process
+    pub fn has_conflict(&self) -> bool {
+        self.has_conflict
+    }
+
+    async fn load(&self) -> anyhow::Result<(Fingerprint, CommandMap<'static>)> {
+        let storage = Arc::clone(&self.storage);
+        tokio::task::spawn_blocking(move || Ok((storage.fingerprint()?, storage.load()?))).await?
+    }
+
+    /// Replaces the commands with the stored ones, discarding the unsaved changes
+    pub async fn reload(&mut self) -> anyhow::Result<()> {
+        let (fingerprint, stored) = self.load().await?;
+        debug!("reloading the commands");
+        self.commands = Commands::init(stored.clone());
+        self.stored = stored;
+        self.fingerprint = fingerprint;
+        self.has_conflict = false;
+        self.refresh();
+
+        Ok(())
+    }
+
+    /// Reloads the commands if another process changed them, unless there are unsaved changes
+    ///
+    /// Returns whether the commands were reloaded
+    pub async fn reload_if_changed(&mut self) -> anyhow::Result<bool> {
+        if self.has_conflict {
+            return Ok(false);
+        }
+
+        let storage = Arc::clone(&self.storage);
+        let fingerprint = tokio::task::spawn_blocking(move || storage.fingerprint()).await??;
+        if fingerprint == self.fingerprint {
+            return Ok(false);
+        }
+
+        debug!("the commands were changed by another process");
+        self.reload().await?;
+        Ok(true)
+    }
+
+    /// Applies the unsaved changes over the stored commands and saves them
+    pub async fn merge(&mut self) -> anyhow::Result<()> {
+        let (fingerprint, stored) = self.load().await?;
+        debug!("merging the changes with the stored commands");
+        self.commands.merge(&self.stored, stored.clone());
+        self.stored = stored;
+        self.fingerprint = fingerprint;
+        let result = self.save().await;
+        self.refresh();
+
+        result
+    }
+
+    /// Updates the items after the commands were replaced, keeping the query, the selected namespace
+    /// and the selected command
+    ///
+    /// The default namespace is selected when the selected one no longer exists
+    fn refresh(&mut self) {
+        let selected = self.selected_command.as_ref().map(|s| s.value.clone());
+        let namespace = self.selected_namespace.name.clone();
+        self.cmd_map = self.commands.as_map().clone();
+        self.filter(&self.get_current_query());
+
+        if let Some(idx) = self
+            .namespaces
+            .iter()
+            .position(|name| *name == namespace)
+            .filter(|_| namespace != DEFAULT_NAMESPACE)
+        {
+            self.selected_namespace = SelectedNamespace::new(idx, namespace);
+            self.current_items = self.get_commands_by_namespace(&self.selected_namespace.name);
+            self.selected_command = SelectedCommand::first_from_vec(&self.current_items);
+        }
+
+        if let Some(idx) = selected
+            .and_then(|selected| self.current_items.iter().position(|item| *item == selected))
+        {
+            self.select(idx);
+        }
+    }
+}
+
+fn sort(
+    mut items: CommandVec<'static>,
+    sort_mode: SortMode,
+    frecency: &Frecency,
+) -> CommandVec<'static> {
+    match sort_mode {
+        SortMode::Alphabetical => items.sorted(),
+        SortMode::Frecency => items.sorted_by_frecency(frecency),
+    }
+}
+
+fn load_frecency(cfg: &impl Config) -> Frecency {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    match History::new(cfg.history_file_path()).load() {
+        Ok(entries) => Frecency::from_history(&entries, now),
+        Err(e) => {
+            warn!("cannot load the execution history, ignoring the usage stats: {e}");
+            Frecency::default()
+        }
+    }
 }
 
 fn append_default_namespace(mut namespaces: Vec<String>) -> Vec<String> {
@@ -365,13 +631,16 @@ fn append_default_namespace(mut namespaces: Vec<String>) -> Vec<String> {
 mod test {
     use super::*;
     use anyhow::Result;
-    use cl_core::{CommandBuilder, Preferences};
+    use cl_core::{CommandBuilder, HistoryEntry, Layer, Preferences, TomlFileStorage};
+    use std::collections::HashMap;
     use std::fs::File;
     use std::path::PathBuf;
     use tempfile::TempDir;
 
     struct TestConfig {
         cfp: PathBuf,
+        preferences: Preferences,
+        read_only_sources: Vec<PathBuf>,
         _tempdir: TempDir,
     }
 
@@ -384,6 +653,8 @@ mod test {
 
             Ok(Self {
                 cfp,
+                preferences: Preferences::default(),
+                read_only_sources: Vec::new(),
                 _tempdir: tempdir,
             })
         }
@@ -402,23 +673,34 @@ mod test {
         }
 
         fn preferences(&self) -> &Preferences {
-            todo!()
+            &self.preferences
         }
 
         fn preferences_mut(&mut self) -> &mut Preferences {
-            todo!()
+            &mut self.preferences
         }
 
         fn command_file_path(&self) -> PathBuf {
             self.cfp.clone()
         }
 
+        fn sources(&self) -> Vec<Layer> {
+            self.read_only_sources
+                .iter()
+                .map(|source| Layer::toml(source).read_only())
+                .collect()
+        }
+
         fn log_dir_path(&self) -> anyhow::Result<PathBuf> {
             todo!()
         }
     }
 
     fn setup_state() -> Result<State> {
+        setup_state_with(TestConfig::new()?)
+    }
+
+    fn setup_state_with(cfg: TestConfig) -> Result<State> {
         let mut commands = HashMap::new();
         let command1 = CommandBuilder::default()
             .command("azul")
@@ -433,8 +715,7 @@ mod test {
         commands.insert(command1.namespace.to_string(), vec![command1]);
         commands.insert(command2.namespace.to_string(), vec![command2]);
 
-        let cfg = TestConfig::new()?;
-        fs::save_at(&commands, cfg.command_file_path())?;
+        cfg.storage().save(&commands)?;
 
         State::new(cfg)
     }
@@ -487,6 +768,33 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn should_sort_the_most_used_commands_first_in_frecency_mode() -> Result<()> {
+        let mut cfg = TestConfig::new()?;
+        cfg.preferences.set_sort_mode(SortMode::Frecency);
+        let history = History::new(cfg.history_file_path());
+        let laranja = CommandBuilder::default()
+            .command("laranja")
+            .namespace("laranja")
+            .alias("laranja")
+            .build();
+        let now = SystemTime::now();
+        history.append(&HistoryEntry::new(
+            &laranja,
+            now,
+            Default::default(),
+            Some(0),
+        ))?;
+
+        let mut state = setup_state_with(cfg)?;
+        assert_eq!(state.current_items.aliases(), vec!["laranja", "azul"]);
+
+        state.filter("");
+        assert_eq!(state.current_items.aliases(), vec!["laranja", "azul"]);
+
+        Ok(())
+    }
+
     #[test]
     fn should_restore_items_on_clear_filter() -> Result<()> {
         let mut state = setup_state()?;
@@ -546,6 +854,196 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_replace_the_selected_command_with_the_edited_one() -> Result<()> {
+        let mut state = setup_state()?;
+        let selected = state
+            .get_selected_command()
+            .expect("should have selected command")
+            .value
+            .clone();
+        state
+            .edit_state
+            .update_alias(Some(selected.alias.to_string()));
+        state
+            .edit_state
+            .update_namespace(Some(selected.namespace.to_string()));
+        state
+            .edit_state
+            .update_command(Some(selected.command.to_string()));
+
+        let mut edited = state.draft_command(true)?;
+        edited.shell = Some("bash".into());
+        state.replace_command(edited).await?;
+
+        let replaced = state
+            .cmd_map
+            .to_vec()
+            .into_iter()
+            .find(|c| c == &selected)
+            .expect("should keep the command");
+        assert_eq!(replaced.shell.as_deref(), Some("bash"));
+        assert_eq!(state.cmd_map.to_vec().len(), state.stored.to_vec().len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_merge_the_changes_made_by_another_process() -> Result<()> {
+        let cfg = TestConfig::new()?;
+        let other_process = cfg.storage();
+        let mut state = setup_state_with(cfg)?;
+
+        let mut stored = other_process.load()?;
+        stored.insert(
+            "other".to_owned(),
+            vec![CommandBuilder::default()
+                .command("other")
+                .namespace("other")
+                .alias("other")
+                .build()],
+        );
+        other_process.save(&stored)?;
+
+        assert!(state.delete_command().await.is_err());
+        assert!(state.has_conflict());
+        assert_eq!(other_process.load()?.to_vec().len(), 3);
+
+        state.merge().await?;
+
+        assert!(!state.has_conflict());
+        assert_eq!(other_process.load()?.to_vec().len(), 2);
+        assert_eq!(state.cmd_map.to_vec().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_discard_the_changes_on_reload() -> Result<()> {
+        let cfg = TestConfig::new()?;
+        let other_process = cfg.storage();
+        let mut state = setup_state_with(cfg)?;
+        other_process.save(&CommandMap::new())?;
+
+        assert!(state.delete_command().await.is_err());
+        state.reload().await?;
+
+        assert!(!state.has_conflict());
+        assert!(state.cmd_map.is_empty());
+        assert!(state.current_items.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_reload_the_commands_changed_by_another_process() -> Result<()> {
+        let cfg = TestConfig::new()?;
+        let other_process = cfg.storage();
+        let mut state = setup_state_with(cfg)?;
+        state.filter("a");
+        state.select(1);
+        let selected = state.get_selected_command().map(|s| s.value.clone());
+
+        assert!(!state.reload_if_changed().await?);
+
+        let mut stored = other_process.load()?;
+        stored.insert(
+            "amarelo".to_owned(),
+            vec![CommandBuilder::default()
+                .command("amarelo")
+                .namespace("amarelo")
+                .alias("amarelo")
+                .build()],
+        );
+        other_process.save(&stored)?;
+
+        assert!(state.reload_if_changed().await?);
+        assert_eq!(state.get_current_query(), "a");
+        assert_eq!(state.current_items.len(), 3);
+        assert_eq!(
+            state.get_selected_command().map(|s| s.value.clone()),
+            selected
+        );
+        assert!(state.namespaces.contains(&"amarelo".to_owned()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_selected_namespace_when_the_commands_are_reloaded() -> Result<()> {
+        let cfg = TestConfig::new()?;
+        let other_process = cfg.storage();
+        let mut state = setup_state_with(cfg)?;
+        state.next_tab();
+        state.next_tab();
+
+        let mut stored = other_process.load()?;
+        stored.insert(
+            "amarelo".to_owned(),
+            vec![CommandBuilder::default()
+                .command("amarelo")
+                .namespace("amarelo")
+                .alias("amarelo")
+                .build()],
+        );
+        other_process.save(&stored)?;
+
+        assert!(state.reload_if_changed().await?);
+        let selected_namespace = state.get_selected_namespace();
+        assert_eq!(selected_namespace.name, "laranja");
+        assert_eq!(
+            state.namespaces[selected_namespace.idx],
+            selected_namespace.name
+        );
+        assert_eq!(state.current_items.len(), 1);
+        assert_eq!(state.current_items[0].alias, "laranja");
+
+        stored.remove("laranja");
+        other_process.save(&stored)?;
+
+        assert!(state.reload_if_changed().await?);
+        assert_eq!(state.get_selected_namespace().idx, 0);
+        assert_eq!(state.get_selected_namespace().name, "All");
+        assert_eq!(state.current_items.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_fork_a_read_only_command() -> Result<()> {
+        let mut cfg = TestConfig::new()?;
+        let team = cfg.cfp.with_file_name("team.toml");
+        let command = CommandBuilder::default()
+            .command("docker ps")
+            .namespace("docker")
+            .alias("ps")
+            .build();
+        TomlFileStorage::new(&team).save(&vec![command].to_command_map())?;
+        cfg.read_only_sources.push(team.clone());
+        let mut state = setup_state_with(cfg)?;
+        state.filter("ps");
+
+        assert!(state.delete_command().await.is_err());
+
+        state.fork_command().await?;
+
+        let selected = state.get_selected_command().expect("should have a command");
+        assert_eq!(selected.value.alias, "ps");
+        assert!(!selected.value.read_only);
+        state.delete_command().await?;
+        assert_eq!(TomlFileStorage::new(&team).load()?.to_vec().len(), 1);
+
+        // the read-only command is back once the fork is deleted
+        state.reload().await?;
+        assert!(state
+            .cmd_map
+            .to_vec()
+            .iter()
+            .any(|c| c.alias == "ps" && c.read_only));
+
+        Ok(())
+    }
+
     #[test]
     fn should_append_the_default_namespace() -> Result<()> {
         let namespaces = vec!["a".to_string(), "a".to_string(), "b".to_string()];
@@ -2,7 +2,7 @@ use crate::state::selected_command::SelectedCommand;
 use crate::state::state_event::StateEvent;
 use crate::state::State;
 use anyhow::Result;
-use cl_core::Config;
+use cl_core::{Config, ExecStatus};
 use tokio::sync::mpsc::Receiver;
 use tracing::{debug, error};
 
@@ -19,6 +19,7 @@ macro_rules! respond {
 pub struct StateActor {
     state: State,
     receiver: Receiver<StateEvent>,
+    exec_status: Option<ExecStatus>,
 }
 
 impl StateActor {
@@ -26,9 +27,15 @@ impl StateActor {
         Ok(Self {
             state: State::new(config)?,
             receiver,
+            exec_status: None,
         })
     }
 
+    /// How the command executed from the TUI finished, if any was executed
+    pub fn exec_status(&self) -> Option<ExecStatus> {
+        self.exec_status
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         while let Some(message) = self.receiver.recv().await {
             self.handle_message(message).await?;
@@ -39,7 +46,8 @@ impl StateActor {
 
     async fn handle_message(&mut self, message: StateEvent) -> Result<()> {
         match message {
-            StateEvent::ExecuteCommand => self.state.execute(),
+            StateEvent::ExecuteCommand => self.exec_status = self.state.execute(),
+            StateEvent::RunCommand(command) => self.exec_status = self.state.run(&command),
             StateEvent::GetAllListItems { respond_to } => {
                 let all_items = self.state.get_all_items().clone();
                 respond!(respond_to, all_items, "GetAllListItems");
@@ -90,10 +98,18 @@ impl StateActor {
                 let namespaces = self.state.get_all_namespaces().to_vec();
                 respond!(respond_to, namespaces, "GetAllNamespaces");
             }
+            StateEvent::GetSelectedNamespace { respond_to } => {
+                let selected_namespace = self.state.get_selected_namespace().to_owned();
+                respond!(respond_to, selected_namespace, "GetSelectedNamespace");
+            }
             StateEvent::DeleteCommand { respond_to } => {
                 let res = self.state.delete_command().await.map_err(|e| e.to_string());
                 respond!(respond_to, res, "DeleteCommand");
             }
+            StateEvent::ForkCommand { respond_to } => {
+                let res = self.state.fork_command().await.map_err(|e| e.to_string());
+                respond!(respond_to, res, "ForkCommand");
+            }
             StateEvent::Filter(query) => {
                 debug!("filtering with query: {}", query);
                 self.state.filter(&query)
@@ -105,6 +121,13 @@ impl StateActor {
                     "GetCurrentQuery"
                 );
             }
+            StateEvent::ExpandedCommand { respond_to } => {
+                let command = self
+                    .state
+                    .expanded_command()
+                    .map(|result| result.map_err(|e| e.to_string()));
+                respond!(respond_to, command, "ExpandedCommand");
+            }
             StateEvent::CommandDetails { respond_to } => {
                 let command = self.state.get_selected_command().map(|s| s.value.clone());
                 respond!(respond_to, command, "CommandDetails");
@@ -129,10 +152,69 @@ impl StateActor {
                 }
                 respond!(respond_to, result, "InsertCommand");
             }
+            StateEvent::DraftCommand {
+                editing,
+                respond_to,
+            } => {
+                let command = self.state.draft_command(editing).map_err(|e| e.to_string());
+                respond!(respond_to, command, "DraftCommand");
+            }
+            StateEvent::ReplaceCommand {
+                command,
+                respond_to,
+            } => {
+                let result = self
+                    .state
+                    .replace_command(*command)
+                    .await
+                    .map_err(|e| e.to_string());
+                if let Err(ref e) = result {
+                    error!("Failed to replace command: {}", e);
+                }
+                respond!(respond_to, result, "ReplaceCommand");
+            }
+            StateEvent::AddCommand {
+                command,
+                respond_to,
+            } => {
+                let result = self
+                    .state
+                    .add_command(*command)
+                    .await
+                    .map_err(|e| e.to_string());
+                if let Err(ref e) = result {
+                    error!("Failed to add command: {}", e);
+                }
+                respond!(respond_to, result, "AddCommand");
+            }
             StateEvent::SyncSelection(idx) => {
                 debug!("syncing selection to index {}", idx);
                 self.state.select(idx);
             }
+            StateEvent::HasConflict { respond_to } => {
+                respond!(respond_to, self.state.has_conflict(), "HasConflict");
+            }
+            StateEvent::ReloadCommands { respond_to } => {
+                let result = self.state.reload().await.map_err(|e| e.to_string());
+                if let Err(ref e) = result {
+                    error!("Failed to reload the commands: {}", e);
+                }
+                respond!(respond_to, result, "ReloadCommands");
+            }
+            StateEvent::ReloadIfChanged { respond_to } => {
+                let reloaded = self.state.reload_if_changed().await.unwrap_or_else(|e| {
+                    error!("Failed to reload the changed commands: {}", e);
+                    false
+                });
+                respond!(respond_to, reloaded, "ReloadIfChanged");
+            }
+            StateEvent::MergeCommands { respond_to } => {
+                let result = self.state.merge().await.map_err(|e| e.to_string());
+                if let Err(ref e) = result {
+                    error!("Failed to merge the commands: {}", e);
+                }
+                respond!(respond_to, result, "MergeCommands");
+            }
         }
 
         Ok(())
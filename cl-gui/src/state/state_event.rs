@@ -8,6 +8,8 @@ use tokio::sync::oneshot;
 pub enum StateEvent {
     /// Execute the selected command
     ExecuteCommand,
+    /// Execute the given command, with its named parameters already replaced
+    RunCommand(Box<Command<'static>>),
     /// Get all list items based on the current namespace
     GetAllListItems {
         respond_to: oneshot::Sender<Vec<Command<'static>>>,
@@ -16,10 +18,18 @@ pub enum StateEvent {
     GetAllNamespaces {
         respond_to: oneshot::Sender<Vec<String>>,
     },
+    /// Get the namespace of the selected tab
+    GetSelectedNamespace {
+        respond_to: oneshot::Sender<SelectedNamespace>,
+    },
     /// Get the current selected command
     CurrentCommand {
         respond_to: oneshot::Sender<Option<SelectedCommand>>,
     },
+    /// Get the current selected command, with its references to other commands resolved
+    ExpandedCommand {
+        respond_to: oneshot::Sender<Option<Result<Command<'static>, String>>>,
+    },
     /// Get the previous tab info
     PreviousTab {
         respond_to: oneshot::Sender<(SelectedNamespace, SelectedCommand, Vec<Command<'static>>)>,
@@ -32,6 +42,10 @@ pub enum StateEvent {
     DeleteCommand {
         respond_to: oneshot::Sender<Result<(), String>>,
     },
+    /// Copy the selected read-only command to the personal commands
+    ForkCommand {
+        respond_to: oneshot::Sender<Result<(), String>>,
+    },
     /// Filter
     Filter(String),
     /// Get current query
@@ -50,8 +64,36 @@ pub enum StateEvent {
     InsertCommand {
         respond_to: oneshot::Sender<Result<(), String>>,
     },
+    /// Get the command of the form, keeping the fields the form does not show from the selected
+    /// command when `editing` it
+    DraftCommand {
+        editing: bool,
+        respond_to: oneshot::Sender<Result<Command<'static>, String>>,
+    },
+    /// Replace the selected command with one changed outside of the form, e.g. in an editor
+    ReplaceCommand {
+        command: Box<Command<'static>>,
+        respond_to: oneshot::Sender<Result<(), String>>,
+    },
+    /// Add a command written outside of the form, e.g. in an editor
+    AddCommand {
+        command: Box<Command<'static>>,
+        respond_to: oneshot::Sender<Result<(), String>>,
+    },
     /// Sync the selected index from UI-local navigation (fire-and-forget)
     SyncSelection(usize),
+    /// Check if the last save was rejected because another process changed the commands
+    HasConflict { respond_to: oneshot::Sender<bool> },
+    /// Discard the unsaved changes and reload the stored commands
+    ReloadCommands {
+        respond_to: oneshot::Sender<Result<(), String>>,
+    },
+    /// Reload the commands if another process changed them, responding whether they were reloaded
+    ReloadIfChanged { respond_to: oneshot::Sender<bool> },
+    /// Merge the unsaved changes with the stored commands and save them
+    MergeCommands {
+        respond_to: oneshot::Sender<Result<(), String>>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
@@ -67,6 +109,14 @@ pub enum FieldName {
     Command,
     /// Edit the command namespace
     Namespace,
+    /// Edit the command working directory
+    Cwd,
+    /// Edit the command environment variables, one `KEY=VALUE` per line
+    Env,
+    /// Fill the value of the nth named parameter before running the command
+    Parameter(usize),
+    /// Fill the extra arguments appended to the command before running it
+    Arguments,
 }
 
 impl fmt::Display for FieldName {
@@ -77,6 +127,10 @@ impl fmt::Display for FieldName {
             FieldName::Tags => write!(f, "Tags"),
             FieldName::Command => write!(f, "Command"),
             FieldName::Namespace => write!(f, "Namespace"),
+            FieldName::Cwd => write!(f, "Working Directory"),
+            FieldName::Env => write!(f, "Environment"),
+            FieldName::Parameter(idx) => write!(f, "Parameter {idx}"),
+            FieldName::Arguments => write!(f, "Arguments"),
         }
     }
 }
@@ -1,19 +1,32 @@
-use crate::component::{List, Tabs, TextBox};
+use crate::component::{FutureEventType, List, Popup, Tabs, TextBox};
 use crate::crossterm::{restore_terminal, setup_terminal};
-use crate::observer::event::{Event, ListEvent, TabsEvent, TextBoxEvent};
+use crate::observer::event::{Event, ListEvent, PopupEvent, PopupType, TabsEvent, TextBoxEvent};
+use crate::screen::command::{FormCallback, ScreenCommand, ScreenCommandCallback};
+use crate::screen::layer::{FormMode, MainScreenLayer, PopupLayer};
 use crate::screen::Screen;
 use crate::signal_handler::{Signal, SignalHandler};
 use crate::state::selected_command::SelectedCommand;
 use crate::state::state_event::StateEvent;
-use crate::state::state_event::StateEvent::{GetAllListItems, GetAllNamespaces};
+use crate::state::state_event::StateEvent::{GetAllListItems, GetAllNamespaces, ReloadIfChanged};
 use anyhow::Result;
-use cl_core::CommandVecExt;
+use cl_core::{CommandVecExt, Editor};
 use crossterm::event::EventStream;
 use std::any::TypeId;
+use std::io::{self, BufRead, Stdout, Write};
+use std::time::Duration;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
 use tokio_stream::StreamExt;
 use tracing::{debug, error};
+use tui::backend::CrosstermBackend;
+use tui::Terminal;
+
+/// How often the stored commands are checked for changes made by other processes
+///
+/// Polling works the same for a file and a directory of commands, and keeps working when they are
+/// replaced, e.g. by an editor or by `git pull`
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct UiActor {
     screen: Screen,
@@ -61,7 +74,9 @@ impl UiActor {
             self.screen
                 .notify(
                     TypeId::of::<TextBox>(),
-                    Event::TextBox(TextBoxEvent::UpdateCommand(selected.value.clone())),
+                    Event::TextBox(TextBoxEvent::UpdateCommand(Box::new(
+                        selected.value.clone(),
+                    ))),
                 )
                 .await;
         }
@@ -78,6 +93,8 @@ impl UiActor {
     pub async fn run(&mut self, state_tx: Sender<StateEvent>) -> Result<()> {
         let mut terminal = setup_terminal()?;
         let mut crossterm_events = EventStream::new();
+        let mut watch = tokio::time::interval(WATCH_INTERVAL);
+        watch.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
         self.initial_load(state_tx.clone()).await;
 
@@ -110,6 +127,27 @@ impl UiActor {
                 event = crossterm_events.next() => {
                     self.screen.handle_key_event(event, &state_tx, &mut self.signal_handler).await;
                 },
+
+                // the commands changed by other processes are reloaded in place, but not while a form
+                // or a dialog is open, as it would act on another command if the selected one is gone
+                _ = watch.tick() => {
+                    if !self.screen.is_main_layer_on_top() {
+                        continue;
+                    }
+                    if !matches!(oneshot!(state_tx, ReloadIfChanged), Ok(true)) {
+                        continue;
+                    }
+                    self.screen.refresh(&state_tx, &mut self.signal_handler).await;
+                },
+            }
+
+            if let Some(mode) = self.screen.take_editor_request() {
+                // the events stream reads the terminal in the background, stealing the editor's input
+                drop(crossterm_events);
+                if let Err(err) = self.open_editor(mode, &mut terminal, &state_tx).await {
+                    break Err(err);
+                }
+                crossterm_events = EventStream::new();
             }
 
             if let Err(err) = terminal.draw(|frame| self.screen.render_layers(frame)) {
@@ -121,4 +159,84 @@ impl UiActor {
 
         result
     }
+
+    /// Suspends the screen to edit the command of the form in the user's editor, then saves it
+    ///
+    /// Only fails if the terminal cannot be restored, as the other errors are shown in a popup
+    async fn open_editor(
+        &mut self,
+        mode: FormMode,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        state_tx: &Sender<StateEvent>,
+    ) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        let event = StateEvent::DraftCommand {
+            editing: mode == FormMode::Edit,
+            respond_to: tx,
+        };
+        let draft = match state_tx.send(event).await {
+            Ok(_) => rx.await.map_err(|e| e.to_string()).and_then(|draft| draft),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let edited = match draft {
+            Ok(draft) => {
+                restore_terminal(terminal)?;
+                let edited = tokio::task::spawn_blocking(move || {
+                    Editor::from_env().edit_command(&draft, ask_to_reopen)
+                })
+                .await;
+                *terminal = setup_terminal()?;
+                terminal.clear()?;
+                match edited {
+                    Ok(result) => result.map_err(|e| format!("{e:#}")),
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+            Err(e) => Err(e),
+        };
+
+        let commands = match edited {
+            Ok(Some(command)) => vec![
+                ScreenCommand::Form(FormCallback::SaveCommand(mode, Box::new(command))),
+                ScreenCommand::ReplaceCurrentLayer(Box::new(MainScreenLayer::default())),
+                ScreenCommand::Callback(ScreenCommandCallback::UpdateAll),
+            ],
+            Ok(None) => {
+                debug!("the command was not changed in the editor");
+                vec![]
+            }
+            Err(e) => {
+                error!("cannot edit the command in the editor: {e}");
+                vec![
+                    ScreenCommand::AddLayer(Box::new(PopupLayer::default())),
+                    ScreenCommand::Notify((
+                        TypeId::of::<Popup>(),
+                        Event::Popup(PopupEvent::Create(PopupType::Dialog(
+                            format!("Error: {e}"),
+                            FutureEventType::State(|_| async_fn_body! { Ok(()) }),
+                            ScreenCommandCallback::DoNothing,
+                        ))),
+                    )),
+                ]
+            }
+        };
+        self.screen
+            .dispatch(commands, state_tx, &mut self.signal_handler)
+            .await;
+
+        Ok(())
+    }
+}
+
+/// Asks in the restored terminal whether to reopen the editor on the command that could not be
+/// saved, answered with no by default
+fn ask_to_reopen(err: &anyhow::Error) -> bool {
+    let mut stderr = io::stderr();
+    let _ = write!(stderr, "{err:#}\nReopen the editor? [y/N] ");
+    let _ = stderr.flush();
+
+    let mut input = String::new();
+    io::stdin().lock().read_line(&mut input).is_ok()
+        && matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
 }
@@ -4,9 +4,10 @@ use cl_core::{
     logger::{LoggerBuilder, LoggerType},
     Config, DefaultConfig,
 };
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let config = DefaultConfig::load().context("Cannot load the config file")?;
 
     let logger = LoggerBuilder::default()
@@ -34,12 +35,15 @@ async fn main() -> Result<()> {
 
 mod new_core {
     use anyhow::Result;
+    use cl_cli::exit_code;
     use cl_core::Config;
     use cl_gui::state::state_actor::StateActor;
     use cl_gui::ui::ui_actor::UiActor;
+    use std::process::ExitCode;
     use tokio::try_join;
 
-    pub async fn init(config: impl Config + 'static) -> Result<()> {
+    /// Runs the TUI, exiting with the code of the executed command, if any
+    pub async fn init(config: impl Config + 'static) -> Result<ExitCode> {
         let (state_tx, state_rx) = tokio::sync::mpsc::channel(8);
 
         let mut state_actor = StateActor::new(config, state_rx)?;
@@ -47,6 +51,9 @@ mod new_core {
 
         try_join!(state_actor.run(), ui_actor.run(state_tx))?;
 
-        Ok(())
+        Ok(state_actor
+            .exec_status()
+            .map(exit_code)
+            .unwrap_or(ExitCode::SUCCESS))
     }
 }